@@ -4,6 +4,36 @@ use sitemap_generator::{
     SitemapIndexBuilder, SitemapIndexEntry, UrlEntry, UrlWithImages, UrlWithVideos, VideoEntry,
     VideoSitemapBuilder,
 };
+use sitemap_generator::writer::XmlWriter;
+use sitemap_generator::UrlEntryRef;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Allocator that counts allocations, used to report the borrowed-entry savings
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Count the allocations made by a closure
+fn count_allocations<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    black_box(f());
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
 
 /// Benchmark building a standard sitemap with varying number of URLs
 fn bench_standard_sitemap(c: &mut Criterion) {
@@ -290,6 +320,68 @@ fn bench_memory_allocation(c: &mut Criterion) {
     group.finish();
 }
 
+/// A database row already held in memory
+struct PageRow {
+    url: String,
+    updated: String,
+}
+
+impl UrlEntryRef for PageRow {
+    fn loc(&self) -> &str {
+        &self.url
+    }
+
+    fn lastmod(&self) -> Option<&str> {
+        Some(&self.updated)
+    }
+
+    fn priority(&self) -> Option<f32> {
+        Some(0.8)
+    }
+}
+
+/// Benchmark rendering rows via owned `UrlEntry` clones vs borrowed `UrlEntryRef`
+fn bench_borrowed_entries(c: &mut Criterion) {
+    let mut group = c.benchmark_group("borrowed_entries");
+    let size = 10_000;
+
+    let rows: Vec<PageRow> = (0..size)
+        .map(|i| PageRow {
+            url: format!("https://example.com/page/{}", i),
+            updated: "2025-11-01".to_string(),
+        })
+        .collect();
+
+    let owned = |rows: &[PageRow]| {
+        let entries: Vec<UrlEntry> = rows
+            .iter()
+            .map(|row| UrlEntry::new(row.url.clone()).lastmod(row.updated.clone()).priority(0.8))
+            .collect();
+        let mut writer = XmlWriter::new();
+        writer.write_sitemap(&entries).unwrap();
+        writer.into_string().unwrap()
+    };
+    let borrowed = |rows: &[PageRow]| {
+        let mut writer = XmlWriter::new();
+        writer.write_sitemap_ref(rows).unwrap();
+        writer.into_string().unwrap()
+    };
+
+    assert_eq!(owned(&rows), borrowed(&rows));
+    println!(
+        "borrowed_entries/{}: {} allocations owned, {} allocations borrowed",
+        size,
+        count_allocations(|| owned(&rows)),
+        count_allocations(|| borrowed(&rows)),
+    );
+
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_function("owned", |b| b.iter(|| black_box(owned(&rows))));
+    group.bench_function("borrowed", |b| b.iter(|| black_box(borrowed(&rows))));
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_standard_sitemap,
@@ -301,6 +393,7 @@ criterion_group!(
     bench_compression_ratio,
     bench_validation_overhead,
     bench_memory_allocation,
+    bench_borrowed_entries,
 );
 
 criterion_main!(benches);
@@ -1,8 +1,8 @@
-/// Example: Creating a combined sitemap with multiple extensions
-///
-/// This demonstrates how to combine image, video, and news extensions
-/// in a single sitemap. This is useful for rich content pages like
-/// news articles with embedded images and videos.
+//! Example: Creating a combined sitemap with multiple extensions
+//!
+//! This demonstrates how to combine image, video, and news extensions
+//! in a single sitemap. This is useful for rich content pages like
+//! news articles with embedded images and videos.
 
 use sitemap_generator::{
     CombinedSitemapBuilder, UrlEntry, UrlWithExtensions,
@@ -1,8 +1,8 @@
-/// Example for flamegraph profiling
-///
-/// Run with: cargo flamegraph --example flamegraph_profile --release
-///
-/// This will generate a flamegraph.svg showing CPU and memory usage
+//! Example for flamegraph profiling
+//!
+//! Run with: cargo flamegraph --example flamegraph_profile --release
+//!
+//! This will generate a flamegraph.svg showing CPU and memory usage
 
 use sitemap_generator::{
     SitemapBuilder, ImageSitemapBuilder, VideoSitemapBuilder,
@@ -94,6 +94,7 @@ fn profile_video_sitemap(count: usize) {
                 format!("Video {}", i),
                 format!("Description for video {}", i)
             )
+            .content_loc(format!("https://example.com/video-{}.mp4", i))
             .duration(300)
             .rating(4.5)
         );
@@ -139,7 +140,8 @@ fn profile_combined_sitemap(count: usize) {
             format!("https://example.com/thumb-{}.jpg", i),
             format!("Video {}", i),
             "Description"
-        );
+        )
+        .content_loc(format!("https://example.com/video-{}.mp4", i));
 
         builder.add_url(
             UrlWithExtensions::new(UrlEntry::new(format!("https://example.com/combined-{}", i)))
@@ -1,7 +1,7 @@
-/// Demonstration of immediate memory cleanup after sitemap generation
-///
-/// This example shows that memory is released as soon as the sitemap
-/// is generated and the builder goes out of scope.
+//! Demonstration of immediate memory cleanup after sitemap generation
+//!
+//! This example shows that memory is released as soon as the sitemap
+//! is generated and the builder goes out of scope.
 
 use sitemap_generator::{SitemapBuilder, UrlEntry, ChangeFreq};
 use std::alloc::{GlobalAlloc, Layout, System};
@@ -167,7 +167,7 @@ fn test_scope_cleanup() {
     } // Builder and xml dropped here
 
     // Small allocation to trigger any pending deallocations
-    let _trigger = vec![0u8; 1];
+    let _trigger = Box::new(0u8);
 
     let after_scope = get_memory_stats().2;
     println!("Memory after scope: {}", format_bytes(after_scope));
@@ -210,7 +210,7 @@ fn test_multiple_generations() {
     }
 
     // Trigger cleanup
-    let _trigger = vec![0u8; 1];
+    let _trigger = Box::new(0u8);
 
     let end_memory = get_memory_stats().2;
     println!("\nFinal memory: {}", format_bytes(end_memory));
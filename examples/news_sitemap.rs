@@ -1,7 +1,7 @@
-/// Example: Creating a Google News sitemap
-///
-/// This demonstrates how to create a news sitemap for Google News.
-/// News sitemaps should only include articles published within the last 2 days.
+//! Example: Creating a Google News sitemap
+//!
+//! This demonstrates how to create a news sitemap for Google News.
+//! News sitemaps should only include articles published within the last 2 days.
 
 use sitemap_generator::{NewsSitemapBuilder, UrlEntry, UrlWithNews, NewsEntry, NewsPublication};
 
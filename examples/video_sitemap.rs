@@ -20,7 +20,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             "Learn everything you need to know about our amazing product in this comprehensive introduction video."
         )
         .content_loc("https://example.com/videos/intro.mp4")
-        .player_loc("https://example.com/player?video=intro")
         .duration(300) // 5 minutes
         .publication_date("2025-11-01")
         .rating(4.5)
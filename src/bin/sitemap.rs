@@ -0,0 +1,95 @@
+//! Command-line tool for working with sitemap files
+//!
+//! Usage:
+//!   sitemap diff <old> <new> [--json]
+//!   sitemap check <file>...
+
+use sitemap_generator::{ConformanceChecker, ConformanceReport, SitemapDiff, SitemapParser, UrlWithImages};
+use std::env;
+use std::process;
+
+const USAGE: &str = "Usage:
+  sitemap diff <old> <new> [--json]    Compare two sitemap files (.xml or .xml.gz)
+  sitemap check <file>...              Check sitemap files against the protocol schema";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("diff") => run_diff(&args[1..]),
+        Some("check") => run_check(&args[1..]),
+        Some("-h") | Some("--help") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn run_diff(args: &[String]) -> sitemap_generator::Result<()> {
+    let json = args.iter().any(|a| a == "--json");
+    let paths: Vec<&String> = args.iter().filter(|a| !a.starts_with("--")).collect();
+
+    if paths.len() != 2 {
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    }
+
+    let old = read_sitemap(paths[0])?;
+    let new = read_sitemap(paths[1])?;
+    let diff = SitemapDiff::between_images(&old, &new);
+
+    if json {
+        println!("{}", diff.to_json()?);
+    } else {
+        print!("{}", diff);
+    }
+
+    Ok(())
+}
+
+fn run_check(paths: &[String]) -> sitemap_generator::Result<()> {
+    if paths.is_empty() {
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    }
+
+    let mut conformant = true;
+    for path in paths {
+        let report = check_sitemap(path)?;
+        for diagnostic in &report.diagnostics {
+            println!("{}:{}", path, diagnostic);
+        }
+        conformant &= report.is_conformant();
+    }
+
+    if !conformant {
+        process::exit(1);
+    }
+
+    Ok(())
+}
+
+fn check_sitemap(path: &str) -> sitemap_generator::Result<ConformanceReport> {
+    if path.ends_with(".gz") {
+        ConformanceChecker::check_compressed(path)
+    } else {
+        ConformanceChecker::check_file(path)
+    }
+}
+
+fn read_sitemap(path: &str) -> sitemap_generator::Result<Vec<UrlWithImages>> {
+    if path.ends_with(".gz") {
+        SitemapParser::parse_image_compressed(path)
+    } else {
+        SitemapParser::parse_image_file(path)
+    }
+}
@@ -0,0 +1,432 @@
+//! Borrowed entry views for zero-copy sitemap generation
+//!
+//! The owned entry types hold every field as a `String`, so rendering rows
+//! that are already in memory means cloning each URL and title. These traits
+//! let the writer read fields straight from your own types instead. Only
+//! `loc` (and the required video fields) must be provided; every other field
+//! defaults to absent.
+//!
+//! The owned types implement the same traits, so both go through the same
+//! serialization code and produce identical output.
+
+use crate::extension::ExtensionElement;
+use crate::types::*;
+
+/// Fields of a `<url>` entry
+///
+/// # Example
+/// ```
+/// use sitemap_generator::writer::XmlWriter;
+/// use sitemap_generator::UrlEntryRef;
+///
+/// struct Page {
+///     url: String,
+///     updated: String,
+/// }
+///
+/// impl UrlEntryRef for Page {
+///     fn loc(&self) -> &str {
+///         &self.url
+///     }
+///
+///     fn lastmod(&self) -> Option<&str> {
+///         Some(&self.updated)
+///     }
+/// }
+///
+/// let pages = vec![Page { url: "https://example.com/".into(), updated: "2025-11-01".into() }];
+///
+/// let mut writer = XmlWriter::new();
+/// writer.write_sitemap_ref(&pages).unwrap();
+/// assert!(writer.into_string().unwrap().contains("<lastmod>2025-11-01</lastmod>"));
+/// ```
+pub trait UrlEntryRef {
+    /// URL of the page
+    fn loc(&self) -> &str;
+
+    /// Last modification date
+    fn lastmod(&self) -> Option<&str> {
+        None
+    }
+
+    /// Change frequency
+    fn changefreq(&self) -> Option<ChangeFreq> {
+        None
+    }
+
+    /// Priority (0.0 - 1.0)
+    fn priority(&self) -> Option<f32> {
+        None
+    }
+
+    /// Elements in custom namespaces
+    fn extensions(&self) -> &[ExtensionElement] {
+        &[]
+    }
+}
+
+/// Fields of an `<image:image>` entry
+pub trait ImageEntryRef {
+    /// URL of the image
+    fn loc(&self) -> &str;
+
+    /// Caption of the image
+    fn caption(&self) -> Option<&str> {
+        None
+    }
+
+    /// Geographic location of the image
+    fn geo_location(&self) -> Option<&str> {
+        None
+    }
+
+    /// Title of the image
+    fn title(&self) -> Option<&str> {
+        None
+    }
+
+    /// URL of the image license
+    fn license(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// Fields of a `<video:video>` entry
+pub trait VideoEntryRef {
+    /// URL of the video thumbnail
+    fn thumbnail_loc(&self) -> &str;
+
+    /// Title of the video
+    fn title(&self) -> &str;
+
+    /// Description of the video
+    fn description(&self) -> &str;
+
+    /// URL of the video file
+    fn content_loc(&self) -> Option<&str> {
+        None
+    }
+
+    /// URL of the video player
+    fn player_loc(&self) -> Option<&str> {
+        None
+    }
+
+    /// Duration in seconds
+    fn duration(&self) -> Option<u32> {
+        None
+    }
+
+    /// Publication date
+    fn publication_date(&self) -> Option<&str> {
+        None
+    }
+
+    /// Expiration date
+    fn expiration_date(&self) -> Option<&str> {
+        None
+    }
+
+    /// Rating (0.0 - 5.0)
+    fn rating(&self) -> Option<f32> {
+        None
+    }
+
+    /// View count
+    fn view_count(&self) -> Option<u64> {
+        None
+    }
+
+    /// Family friendly flag
+    fn family_friendly(&self) -> Option<bool> {
+        None
+    }
+
+    /// Tags
+    fn tags(&self) -> &[String] {
+        &[]
+    }
+
+    /// Category
+    fn category(&self) -> Option<&str> {
+        None
+    }
+
+    /// Country restriction
+    fn restriction(&self) -> Option<&VideoCountryRestriction> {
+        None
+    }
+
+    /// URL of the gallery page
+    fn gallery_loc(&self) -> Option<&str> {
+        None
+    }
+
+    /// Platform restriction
+    fn platform(&self) -> Option<&VideoPlatformRestriction> {
+        None
+    }
+
+    /// Prices
+    fn prices(&self) -> &[VideoPrice] {
+        &[]
+    }
+
+    /// Subscription requirement
+    fn requires_subscription(&self) -> Option<VideoRequiresSubscription> {
+        None
+    }
+
+    /// Uploader
+    fn uploader(&self) -> Option<&VideoUploader> {
+        None
+    }
+
+    /// Live stream flag
+    fn live(&self) -> Option<VideoLive> {
+        None
+    }
+}
+
+/// A `<url>` entry with images
+pub trait ImagesRef: UrlEntryRef {
+    /// Image type
+    type Image: ImageEntryRef;
+
+    /// Images on the page
+    fn images(&self) -> &[Self::Image];
+}
+
+/// A `<url>` entry with videos
+pub trait VideosRef: UrlEntryRef {
+    /// Video type
+    type Video: VideoEntryRef;
+
+    /// Videos on the page
+    fn videos(&self) -> &[Self::Video];
+}
+
+impl<T: UrlEntryRef + ?Sized> UrlEntryRef for &T {
+    fn loc(&self) -> &str {
+        (**self).loc()
+    }
+
+    fn lastmod(&self) -> Option<&str> {
+        (**self).lastmod()
+    }
+
+    fn changefreq(&self) -> Option<ChangeFreq> {
+        (**self).changefreq()
+    }
+
+    fn priority(&self) -> Option<f32> {
+        (**self).priority()
+    }
+
+    fn extensions(&self) -> &[ExtensionElement] {
+        (**self).extensions()
+    }
+}
+
+impl<T: ImagesRef + ?Sized> ImagesRef for &T {
+    type Image = T::Image;
+
+    fn images(&self) -> &[Self::Image] {
+        (**self).images()
+    }
+}
+
+impl<T: VideosRef + ?Sized> VideosRef for &T {
+    type Video = T::Video;
+
+    fn videos(&self) -> &[Self::Video] {
+        (**self).videos()
+    }
+}
+
+impl UrlEntryRef for UrlEntry {
+    fn loc(&self) -> &str {
+        &self.loc
+    }
+
+    fn lastmod(&self) -> Option<&str> {
+        self.lastmod.as_deref()
+    }
+
+    fn changefreq(&self) -> Option<ChangeFreq> {
+        self.changefreq
+    }
+
+    fn priority(&self) -> Option<f32> {
+        self.priority
+    }
+
+    fn extensions(&self) -> &[ExtensionElement] {
+        &self.extensions
+    }
+}
+
+impl ImageEntryRef for ImageEntry {
+    fn loc(&self) -> &str {
+        &self.loc
+    }
+
+    fn caption(&self) -> Option<&str> {
+        self.caption.as_deref()
+    }
+
+    fn geo_location(&self) -> Option<&str> {
+        let geo = self.geo_location.as_ref()?;
+        geo.city.as_deref().or(geo.state.as_deref()).or(geo.country.as_deref())
+    }
+
+    fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    fn license(&self) -> Option<&str> {
+        self.license.as_deref()
+    }
+}
+
+impl VideoEntryRef for VideoEntry {
+    fn thumbnail_loc(&self) -> &str {
+        &self.thumbnail_loc
+    }
+
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn content_loc(&self) -> Option<&str> {
+        self.content_loc.as_deref()
+    }
+
+    fn player_loc(&self) -> Option<&str> {
+        self.player_loc.as_deref()
+    }
+
+    fn duration(&self) -> Option<u32> {
+        self.duration
+    }
+
+    fn publication_date(&self) -> Option<&str> {
+        self.publication_date.as_deref()
+    }
+
+    fn expiration_date(&self) -> Option<&str> {
+        self.expiration_date.as_deref()
+    }
+
+    fn rating(&self) -> Option<f32> {
+        self.rating
+    }
+
+    fn view_count(&self) -> Option<u64> {
+        self.view_count
+    }
+
+    fn family_friendly(&self) -> Option<bool> {
+        self.family_friendly
+    }
+
+    fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    fn restriction(&self) -> Option<&VideoCountryRestriction> {
+        self.restriction.as_ref()
+    }
+
+    fn gallery_loc(&self) -> Option<&str> {
+        self.gallery_loc.as_deref()
+    }
+
+    fn platform(&self) -> Option<&VideoPlatformRestriction> {
+        self.platform.as_ref()
+    }
+
+    fn prices(&self) -> &[VideoPrice] {
+        &self.prices
+    }
+
+    fn requires_subscription(&self) -> Option<VideoRequiresSubscription> {
+        self.requires_subscription
+    }
+
+    fn uploader(&self) -> Option<&VideoUploader> {
+        self.uploader.as_ref()
+    }
+
+    fn live(&self) -> Option<VideoLive> {
+        self.live
+    }
+}
+
+impl UrlEntryRef for UrlWithImages {
+    fn loc(&self) -> &str {
+        &self.url.loc
+    }
+
+    fn lastmod(&self) -> Option<&str> {
+        self.url.lastmod.as_deref()
+    }
+
+    fn changefreq(&self) -> Option<ChangeFreq> {
+        self.url.changefreq
+    }
+
+    fn priority(&self) -> Option<f32> {
+        self.url.priority
+    }
+
+    fn extensions(&self) -> &[ExtensionElement] {
+        &self.url.extensions
+    }
+}
+
+impl ImagesRef for UrlWithImages {
+    type Image = ImageEntry;
+
+    fn images(&self) -> &[ImageEntry] {
+        &self.images
+    }
+}
+
+impl UrlEntryRef for UrlWithVideos {
+    fn loc(&self) -> &str {
+        &self.url.loc
+    }
+
+    fn lastmod(&self) -> Option<&str> {
+        self.url.lastmod.as_deref()
+    }
+
+    fn changefreq(&self) -> Option<ChangeFreq> {
+        self.url.changefreq
+    }
+
+    fn priority(&self) -> Option<f32> {
+        self.url.priority
+    }
+
+    fn extensions(&self) -> &[ExtensionElement] {
+        &self.url.extensions
+    }
+}
+
+impl VideosRef for UrlWithVideos {
+    type Video = VideoEntry;
+
+    fn videos(&self) -> &[VideoEntry] {
+        &self.videos
+    }
+}
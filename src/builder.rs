@@ -1,20 +1,29 @@
 //! Builder patterns for creating sitemaps
 
-use crate::error::Result;
+use crate::error::{Error, IoContext, Result};
+use crate::news_checks::NewsChecks;
+use crate::normalize::{DedupPolicy, NormalizationReport, UrlNormalizer};
+use crate::repair::{repair_entries, EntrySize, RepairedSitemap};
+use crate::resolve::resolved;
+use crate::scope::{ScopeViolation, SitemapScope};
+use crate::text::LengthUnit;
 use crate::types::*;
-use crate::validator::Validator;
-use crate::writer::XmlWriter;
+use crate::validator::{validate_each, Validator, MAX_NEWS_URLS, MAX_URLS};
+use crate::writer::{InvalidCharPolicy, UrlEncoding, WriterOptions, XmlWriter};
 use flate2::write::GzEncoder;
 use flate2::Compression;
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
+use url::Url;
 
 /// Builder for standard XML sitemaps
 #[derive(Debug, Default)]
 pub struct SitemapBuilder {
     entries: Vec<UrlEntry>,
     validate: bool,
+    writer_options: WriterOptions,
+    scope: Option<SitemapScope>,
 }
 
 impl SitemapBuilder {
@@ -23,6 +32,8 @@ impl SitemapBuilder {
         Self {
             entries: Vec::new(),
             validate: true,
+            writer_options: WriterOptions::default(),
+            scope: None,
         }
     }
 
@@ -43,6 +54,8 @@ impl SitemapBuilder {
         Self {
             entries: Vec::with_capacity(capacity),
             validate: true,
+            writer_options: WriterOptions::default(),
+            scope: None,
         }
     }
 
@@ -52,6 +65,59 @@ impl SitemapBuilder {
         self
     }
 
+    /// Reference an XSL stylesheet from the generated XML
+    ///
+    /// See [`DEFAULT_STYLESHEET`](crate::writer::DEFAULT_STYLESHEET) for a bundled one.
+    pub fn stylesheet(mut self, href: impl Into<String>) -> Self {
+        self.writer_options.stylesheet = Some(href.into());
+        self
+    }
+
+    /// Set how URLs are serialized (default: [`UrlEncoding::AsIs`])
+    pub fn url_encoding(mut self, encoding: UrlEncoding) -> Self {
+        self.writer_options.url_encoding = encoding;
+        self
+    }
+
+    /// Set how characters not allowed in XML are handled (default: [`InvalidCharPolicy::Reject`])
+    pub fn invalid_chars(mut self, policy: InvalidCharPolicy) -> Self {
+        self.writer_options.invalid_chars = policy;
+        self
+    }
+
+    /// Normalize text fields to Unicode NFC (disabled by default)
+    pub fn normalize_nfc(mut self, normalize: bool) -> Self {
+        self.writer_options.normalize_nfc = normalize;
+        self
+    }
+
+    /// Register a prefix for extension elements in a custom namespace
+    ///
+    /// Building fails if the prefix is also registered for another URI. See
+    /// [`extension`](crate::extension) for attaching elements to entries.
+    pub fn namespace(mut self, prefix: impl Into<String>, uri: impl Into<String>) -> Self {
+        self.writer_options.namespaces.push((prefix.into(), uri.into()));
+        self
+    }
+
+    /// Resolve relative URLs against a base URL when building
+    ///
+    /// Relative URLs are kept as added, so the same entries can be built for
+    /// different hosts.
+    pub fn base_url(mut self, base: Url) -> Self {
+        self.writer_options.base_url = Some(base);
+        self
+    }
+
+    /// Restrict URLs to the scope of the sitemap's public URL
+    ///
+    /// Out-of-scope URLs fail the build unless the scope is [`warn_only`](SitemapScope::warn_only),
+    /// also when [`validate`](Self::validate) is disabled.
+    pub fn scope(mut self, scope: SitemapScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
     /// Add a URL entry
     pub fn add_url(&mut self, entry: UrlEntry) -> &mut Self {
         self.entries.push(entry);
@@ -74,26 +140,53 @@ impl SitemapBuilder {
         self.entries.is_empty()
     }
 
+    /// Normalize all URLs and collapse entries that become duplicates
+    ///
+    /// See [`UrlNormalizer`](crate::normalize::UrlNormalizer) for the rules applied.
+    pub fn normalize_urls(&mut self, normalizer: &UrlNormalizer, policy: DedupPolicy) -> NormalizationReport {
+        let (entries, report) = normalizer.apply(std::mem::take(&mut self.entries), policy);
+        self.entries = entries;
+        report
+    }
+
+    /// Get the URLs outside the configured scope (empty if no scope is set)
+    pub fn scope_violations(&self) -> Vec<ScopeViolation> {
+        match self.scope {
+            Some(ref scope) => scope.violations(self.writer_options.base_url.as_ref(), &self.entries),
+            None => Vec::new(),
+        }
+    }
+
     /// Validate all entries
-    fn validate_entries(&self) -> Result<()> {
+    fn validate_entries(&self, entries: &[UrlEntry]) -> Result<()> {
+        // Check sitemap scope, which is enforced even with validation disabled
+        if let Some(ref scope) = self.scope {
+            scope.enforce(self.writer_options.base_url.as_ref(), entries)?;
+        }
+
         if !self.validate {
             return Ok(());
         }
 
         // Check URL count
-        Validator::validate_url_count(self.entries.len())?;
+        Validator::validate_url_count(entries.len())?;
 
         // Validate each entry
-        for entry in &self.entries {
-            Validator::validate_url(&entry.loc)?;
+        validate_each(entries, |entry| {
+            self.validate_entry(&resolved(self.writer_options.base_url.as_ref(), entry))
+        })
+    }
 
-            if let Some(ref lastmod) = entry.lastmod {
-                Validator::validate_date(lastmod)?;
-            }
+    /// Validate a single entry
+    fn validate_entry(&self, entry: &UrlEntry) -> Result<()> {
+        Validator::validate_url(&entry.loc)?;
 
-            if let Some(priority) = entry.priority {
-                Validator::validate_priority(priority)?;
-            }
+        if let Some(ref lastmod) = entry.lastmod {
+            Validator::validate_date(lastmod)?;
+        }
+
+        if let Some(priority) = entry.priority {
+            Validator::validate_priority(priority)?;
         }
 
         Ok(())
@@ -101,9 +194,9 @@ impl SitemapBuilder {
 
     /// Build the sitemap XML as a String
     pub fn build(&self) -> Result<String> {
-        self.validate_entries()?;
+        self.validate_entries(&self.entries)?;
 
-        let mut writer = XmlWriter::new();
+        let mut writer = XmlWriter::with_options(self.writer_options.clone());
         writer.write_sitemap(&self.entries)?;
         let xml = writer.into_string()?;
 
@@ -115,6 +208,30 @@ impl SitemapBuilder {
         Ok(xml)
     }
 
+    /// Build the XML, repairing invalid entries instead of failing
+    ///
+    /// Values with an obvious valid replacement are fixed, see
+    /// [`repair`](crate::repair) for the list. Entries that still fail
+    /// validation, or are over the URL or size limit, are left out. Validation runs
+    /// even if it is disabled for [`build`](Self::build).
+    pub fn build_repaired(&self) -> Result<RepairedSitemap> {
+        let base = self.writer_options.base_url.as_ref();
+        let entries = self.entries.clone();
+        let size = EntrySize::new(&self.writer_options, XmlWriter::write_sitemap)?;
+        let (entries, report) = repair_entries(entries, LengthUnit::default(), MAX_URLS, &size, |entry| {
+            if let Some(ref scope) = self.scope {
+                scope.enforce(base, std::slice::from_ref(entry))?;
+            }
+            self.validate_entry(&resolved(base, entry))
+        });
+
+        let mut writer = XmlWriter::with_options(self.writer_options.clone());
+        writer.write_sitemap(&entries)?;
+        let xml = writer.into_string()?;
+
+        Ok(RepairedSitemap { xml, report })
+    }
+
     /// Build the sitemap XML as bytes (Vec<u8>)
     ///
     /// This is useful for web frameworks like Axum, Actix-web, etc.
@@ -161,26 +278,31 @@ impl SitemapBuilder {
     pub fn build_compressed_bytes(&self) -> Result<Vec<u8>> {
         let xml = self.build()?;
         let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-        encoder.write_all(xml.as_bytes())?;
-        let compressed = encoder.finish()?;
-        Ok(compressed)
+        encoder
+            .write_all(xml.as_bytes())
+            .map_err(|err| Error::compression(None, err))?;
+        encoder.finish().map_err(|err| Error::compression(None, err))
     }
 
     /// Write sitemap to a file
     pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let xml = self.build()?;
-        let mut file = File::create(path)?;
-        file.write_all(xml.as_bytes())?;
+        let path = path.as_ref();
+        let mut file = File::create(path).with_path(path)?;
+        file.write_all(xml.as_bytes()).with_path(path)?;
         Ok(())
     }
 
     /// Write compressed sitemap to a file
     pub fn write_compressed<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let xml = self.build()?;
-        let file = File::create(path)?;
+        let path = path.as_ref();
+        let file = File::create(path).with_path(path)?;
         let mut encoder = GzEncoder::new(file, Compression::default());
-        encoder.write_all(xml.as_bytes())?;
-        encoder.finish()?;
+        encoder
+            .write_all(xml.as_bytes())
+            .map_err(|err| Error::compression(Some(path), err))?;
+        encoder.finish().map_err(|err| Error::compression(Some(path), err))?;
         Ok(())
     }
 }
@@ -190,6 +312,8 @@ impl SitemapBuilder {
 pub struct ImageSitemapBuilder {
     entries: Vec<UrlWithImages>,
     validate: bool,
+    writer_options: WriterOptions,
+    scope: Option<SitemapScope>,
 }
 
 impl ImageSitemapBuilder {
@@ -198,6 +322,8 @@ impl ImageSitemapBuilder {
         Self {
             entries: Vec::new(),
             validate: true,
+            writer_options: WriterOptions::default(),
+            scope: None,
         }
     }
 
@@ -218,6 +344,8 @@ impl ImageSitemapBuilder {
         Self {
             entries: Vec::with_capacity(capacity),
             validate: true,
+            writer_options: WriterOptions::default(),
+            scope: None,
         }
     }
 
@@ -227,6 +355,59 @@ impl ImageSitemapBuilder {
         self
     }
 
+    /// Reference an XSL stylesheet from the generated XML
+    ///
+    /// See [`DEFAULT_STYLESHEET`](crate::writer::DEFAULT_STYLESHEET) for a bundled one.
+    pub fn stylesheet(mut self, href: impl Into<String>) -> Self {
+        self.writer_options.stylesheet = Some(href.into());
+        self
+    }
+
+    /// Set how URLs are serialized (default: [`UrlEncoding::AsIs`])
+    pub fn url_encoding(mut self, encoding: UrlEncoding) -> Self {
+        self.writer_options.url_encoding = encoding;
+        self
+    }
+
+    /// Set how characters not allowed in XML are handled (default: [`InvalidCharPolicy::Reject`])
+    pub fn invalid_chars(mut self, policy: InvalidCharPolicy) -> Self {
+        self.writer_options.invalid_chars = policy;
+        self
+    }
+
+    /// Normalize text fields to Unicode NFC (disabled by default)
+    pub fn normalize_nfc(mut self, normalize: bool) -> Self {
+        self.writer_options.normalize_nfc = normalize;
+        self
+    }
+
+    /// Register a prefix for extension elements in a custom namespace
+    ///
+    /// Building fails if the prefix is also registered for another URI. See
+    /// [`extension`](crate::extension) for attaching elements to entries.
+    pub fn namespace(mut self, prefix: impl Into<String>, uri: impl Into<String>) -> Self {
+        self.writer_options.namespaces.push((prefix.into(), uri.into()));
+        self
+    }
+
+    /// Resolve relative URLs against a base URL when building
+    ///
+    /// Relative URLs are kept as added, so the same entries can be built for
+    /// different hosts.
+    pub fn base_url(mut self, base: Url) -> Self {
+        self.writer_options.base_url = Some(base);
+        self
+    }
+
+    /// Restrict URLs to the scope of the sitemap's public URL
+    ///
+    /// Out-of-scope URLs fail the build unless the scope is [`warn_only`](SitemapScope::warn_only),
+    /// also when [`validate`](Self::validate) is disabled.
+    pub fn scope(mut self, scope: SitemapScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
     /// Add a URL with images
     pub fn add_url(&mut self, entry: UrlWithImages) -> &mut Self {
         self.entries.push(entry);
@@ -249,31 +430,58 @@ impl ImageSitemapBuilder {
         self.entries.is_empty()
     }
 
+    /// Normalize all URLs and collapse entries that become duplicates
+    ///
+    /// See [`UrlNormalizer`](crate::normalize::UrlNormalizer) for the rules applied.
+    pub fn normalize_urls(&mut self, normalizer: &UrlNormalizer, policy: DedupPolicy) -> NormalizationReport {
+        let (entries, report) = normalizer.apply(std::mem::take(&mut self.entries), policy);
+        self.entries = entries;
+        report
+    }
+
+    /// Get the URLs outside the configured scope (empty if no scope is set)
+    pub fn scope_violations(&self) -> Vec<ScopeViolation> {
+        match self.scope {
+            Some(ref scope) => scope.violations(self.writer_options.base_url.as_ref(), &self.entries),
+            None => Vec::new(),
+        }
+    }
+
     /// Validate all entries
-    fn validate_entries(&self) -> Result<()> {
+    fn validate_entries(&self, entries: &[UrlWithImages]) -> Result<()> {
+        // Check sitemap scope, which is enforced even with validation disabled
+        if let Some(ref scope) = self.scope {
+            scope.enforce(self.writer_options.base_url.as_ref(), entries)?;
+        }
+
         if !self.validate {
             return Ok(());
         }
 
         // Check URL count
-        Validator::validate_url_count(self.entries.len())?;
+        Validator::validate_url_count(entries.len())?;
 
         // Validate each entry
-        for entry in &self.entries {
-            Validator::validate_url(&entry.url.loc)?;
+        validate_each(entries, |entry| {
+            self.validate_entry(&resolved(self.writer_options.base_url.as_ref(), entry))
+        })
+    }
 
-            if let Some(ref lastmod) = entry.url.lastmod {
-                Validator::validate_date(lastmod)?;
-            }
+    /// Validate a single entry
+    fn validate_entry(&self, entry: &UrlWithImages) -> Result<()> {
+        Validator::validate_url(&entry.url.loc)?;
 
-            if let Some(priority) = entry.url.priority {
-                Validator::validate_priority(priority)?;
-            }
+        if let Some(ref lastmod) = entry.url.lastmod {
+            Validator::validate_date(lastmod)?;
+        }
 
-            // Validate image URLs
-            for image in &entry.images {
-                Validator::validate_url(&image.loc)?;
-            }
+        if let Some(priority) = entry.url.priority {
+            Validator::validate_priority(priority)?;
+        }
+
+        // Validate image URLs
+        for image in &entry.images {
+            Validator::validate_url(&image.loc)?;
         }
 
         Ok(())
@@ -281,9 +489,9 @@ impl ImageSitemapBuilder {
 
     /// Build the sitemap XML as a String
     pub fn build(&self) -> Result<String> {
-        self.validate_entries()?;
+        self.validate_entries(&self.entries)?;
 
-        let mut writer = XmlWriter::new();
+        let mut writer = XmlWriter::with_options(self.writer_options.clone());
         writer.write_image_sitemap(&self.entries)?;
         let xml = writer.into_string()?;
 
@@ -295,6 +503,30 @@ impl ImageSitemapBuilder {
         Ok(xml)
     }
 
+    /// Build the XML, repairing invalid entries instead of failing
+    ///
+    /// Values with an obvious valid replacement are fixed, see
+    /// [`repair`](crate::repair) for the list. Entries that still fail
+    /// validation, or are over the URL or size limit, are left out. Validation runs
+    /// even if it is disabled for [`build`](Self::build).
+    pub fn build_repaired(&self) -> Result<RepairedSitemap> {
+        let base = self.writer_options.base_url.as_ref();
+        let entries = self.entries.clone();
+        let size = EntrySize::new(&self.writer_options, XmlWriter::write_image_sitemap)?;
+        let (entries, report) = repair_entries(entries, LengthUnit::default(), MAX_URLS, &size, |entry| {
+            if let Some(ref scope) = self.scope {
+                scope.enforce(base, std::slice::from_ref(entry))?;
+            }
+            self.validate_entry(&resolved(base, entry))
+        });
+
+        let mut writer = XmlWriter::with_options(self.writer_options.clone());
+        writer.write_image_sitemap(&entries)?;
+        let xml = writer.into_string()?;
+
+        Ok(RepairedSitemap { xml, report })
+    }
+
     /// Build the sitemap XML as bytes (Vec<u8>)
     pub fn build_bytes(&self) -> Result<Vec<u8>> {
         let xml = self.build()?;
@@ -305,26 +537,31 @@ impl ImageSitemapBuilder {
     pub fn build_compressed_bytes(&self) -> Result<Vec<u8>> {
         let xml = self.build()?;
         let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-        encoder.write_all(xml.as_bytes())?;
-        let compressed = encoder.finish()?;
-        Ok(compressed)
+        encoder
+            .write_all(xml.as_bytes())
+            .map_err(|err| Error::compression(None, err))?;
+        encoder.finish().map_err(|err| Error::compression(None, err))
     }
 
     /// Write sitemap to a file
     pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let xml = self.build()?;
-        let mut file = File::create(path)?;
-        file.write_all(xml.as_bytes())?;
+        let path = path.as_ref();
+        let mut file = File::create(path).with_path(path)?;
+        file.write_all(xml.as_bytes()).with_path(path)?;
         Ok(())
     }
 
     /// Write compressed sitemap to a file
     pub fn write_compressed<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let xml = self.build()?;
-        let file = File::create(path)?;
+        let path = path.as_ref();
+        let file = File::create(path).with_path(path)?;
         let mut encoder = GzEncoder::new(file, Compression::default());
-        encoder.write_all(xml.as_bytes())?;
-        encoder.finish()?;
+        encoder
+            .write_all(xml.as_bytes())
+            .map_err(|err| Error::compression(Some(path), err))?;
+        encoder.finish().map_err(|err| Error::compression(Some(path), err))?;
         Ok(())
     }
 }
@@ -334,6 +571,9 @@ impl ImageSitemapBuilder {
 pub struct VideoSitemapBuilder {
     entries: Vec<UrlWithVideos>,
     validate: bool,
+    writer_options: WriterOptions,
+    scope: Option<SitemapScope>,
+    length_unit: LengthUnit,
 }
 
 impl VideoSitemapBuilder {
@@ -342,6 +582,9 @@ impl VideoSitemapBuilder {
         Self {
             entries: Vec::new(),
             validate: true,
+            writer_options: WriterOptions::default(),
+            scope: None,
+            length_unit: LengthUnit::default(),
         }
     }
 
@@ -366,6 +609,9 @@ impl VideoSitemapBuilder {
         Self {
             entries: Vec::with_capacity(capacity),
             validate: true,
+            writer_options: WriterOptions::default(),
+            scope: None,
+            length_unit: LengthUnit::default(),
         }
     }
 
@@ -375,6 +621,66 @@ impl VideoSitemapBuilder {
         self
     }
 
+    /// Reference an XSL stylesheet from the generated XML
+    ///
+    /// See [`DEFAULT_STYLESHEET`](crate::writer::DEFAULT_STYLESHEET) for a bundled one.
+    pub fn stylesheet(mut self, href: impl Into<String>) -> Self {
+        self.writer_options.stylesheet = Some(href.into());
+        self
+    }
+
+    /// Set how URLs are serialized (default: [`UrlEncoding::AsIs`])
+    pub fn url_encoding(mut self, encoding: UrlEncoding) -> Self {
+        self.writer_options.url_encoding = encoding;
+        self
+    }
+
+    /// Set how characters not allowed in XML are handled (default: [`InvalidCharPolicy::Reject`])
+    pub fn invalid_chars(mut self, policy: InvalidCharPolicy) -> Self {
+        self.writer_options.invalid_chars = policy;
+        self
+    }
+
+    /// Normalize text fields to Unicode NFC (disabled by default)
+    pub fn normalize_nfc(mut self, normalize: bool) -> Self {
+        self.writer_options.normalize_nfc = normalize;
+        self
+    }
+
+    /// Register a prefix for extension elements in a custom namespace
+    ///
+    /// Building fails if the prefix is also registered for another URI. See
+    /// [`extension`](crate::extension) for attaching elements to entries.
+    pub fn namespace(mut self, prefix: impl Into<String>, uri: impl Into<String>) -> Self {
+        self.writer_options.namespaces.push((prefix.into(), uri.into()));
+        self
+    }
+
+    /// Resolve relative URLs against a base URL when building
+    ///
+    /// Relative URLs are kept as added, so the same entries can be built for
+    /// different hosts.
+    pub fn base_url(mut self, base: Url) -> Self {
+        self.writer_options.base_url = Some(base);
+        self
+    }
+
+    /// Restrict URLs to the scope of the sitemap's public URL
+    ///
+    /// Out-of-scope URLs fail the build unless the scope is [`warn_only`](SitemapScope::warn_only),
+    /// also when [`validate`](Self::validate) is disabled.
+    pub fn scope(mut self, scope: SitemapScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    /// Set how title, description and category lengths are counted
+    /// (default: [`LengthUnit::Chars`])
+    pub fn length_unit(mut self, unit: LengthUnit) -> Self {
+        self.length_unit = unit;
+        self
+    }
+
     /// Add a URL with videos
     pub fn add_url(&mut self, entry: UrlWithVideos) -> &mut Self {
         self.entries.push(entry);
@@ -397,53 +703,54 @@ impl VideoSitemapBuilder {
         self.entries.is_empty()
     }
 
+    /// Normalize all URLs and collapse entries that become duplicates
+    ///
+    /// See [`UrlNormalizer`](crate::normalize::UrlNormalizer) for the rules applied.
+    pub fn normalize_urls(&mut self, normalizer: &UrlNormalizer, policy: DedupPolicy) -> NormalizationReport {
+        let (entries, report) = normalizer.apply(std::mem::take(&mut self.entries), policy);
+        self.entries = entries;
+        report
+    }
+
+    /// Get the URLs outside the configured scope (empty if no scope is set)
+    pub fn scope_violations(&self) -> Vec<ScopeViolation> {
+        match self.scope {
+            Some(ref scope) => scope.violations(self.writer_options.base_url.as_ref(), &self.entries),
+            None => Vec::new(),
+        }
+    }
+
     /// Validate all entries
-    fn validate_entries(&self) -> Result<()> {
+    fn validate_entries(&self, entries: &[UrlWithVideos]) -> Result<()> {
+        // Check sitemap scope, which is enforced even with validation disabled
+        if let Some(ref scope) = self.scope {
+            scope.enforce(self.writer_options.base_url.as_ref(), entries)?;
+        }
+
         if !self.validate {
             return Ok(());
         }
 
         // Check URL count
-        Validator::validate_url_count(self.entries.len())?;
+        Validator::validate_url_count(entries.len())?;
 
         // Validate each entry
-        for entry in &self.entries {
-            Validator::validate_url(&entry.url.loc)?;
-
-            if let Some(ref lastmod) = entry.url.lastmod {
-                Validator::validate_date(lastmod)?;
-            }
-
-            // Validate videos
-            for video in &entry.videos {
-                Validator::validate_url(&video.thumbnail_loc)?;
-                Validator::validate_video_title(&video.title)?;
-                Validator::validate_video_description(&video.description)?;
-
-                if let Some(ref content_loc) = video.content_loc {
-                    Validator::validate_url(content_loc)?;
-                }
-
-                if let Some(ref player_loc) = video.player_loc {
-                    Validator::validate_url(player_loc)?;
-                }
-
-                if let Some(duration) = video.duration {
-                    Validator::validate_video_duration(duration)?;
-                }
+        validate_each(entries, |entry| {
+            self.validate_entry(&resolved(self.writer_options.base_url.as_ref(), entry))
+        })
+    }
 
-                if let Some(rating) = video.rating {
-                    Validator::validate_video_rating(rating)?;
-                }
+    /// Validate a single entry
+    fn validate_entry(&self, entry: &UrlWithVideos) -> Result<()> {
+        Validator::validate_url(&entry.url.loc)?;
 
-                if let Some(ref pub_date) = video.publication_date {
-                    Validator::validate_date(pub_date)?;
-                }
+        if let Some(ref lastmod) = entry.url.lastmod {
+            Validator::validate_date(lastmod)?;
+        }
 
-                if let Some(ref exp_date) = video.expiration_date {
-                    Validator::validate_date(exp_date)?;
-                }
-            }
+        // Validate videos
+        for video in &entry.videos {
+            Validator::validate_video(&entry.url.loc, video, self.length_unit)?;
         }
 
         Ok(())
@@ -451,9 +758,9 @@ impl VideoSitemapBuilder {
 
     /// Build the sitemap XML as a String
     pub fn build(&self) -> Result<String> {
-        self.validate_entries()?;
+        self.validate_entries(&self.entries)?;
 
-        let mut writer = XmlWriter::new();
+        let mut writer = XmlWriter::with_options(self.writer_options.clone());
         writer.write_video_sitemap(&self.entries)?;
         let xml = writer.into_string()?;
 
@@ -465,6 +772,30 @@ impl VideoSitemapBuilder {
         Ok(xml)
     }
 
+    /// Build the XML, repairing invalid entries instead of failing
+    ///
+    /// Values with an obvious valid replacement are fixed, see
+    /// [`repair`](crate::repair) for the list. Entries that still fail
+    /// validation, or are over the URL or size limit, are left out. Validation runs
+    /// even if it is disabled for [`build`](Self::build).
+    pub fn build_repaired(&self) -> Result<RepairedSitemap> {
+        let base = self.writer_options.base_url.as_ref();
+        let entries = self.entries.clone();
+        let size = EntrySize::new(&self.writer_options, XmlWriter::write_video_sitemap)?;
+        let (entries, report) = repair_entries(entries, self.length_unit, MAX_URLS, &size, |entry| {
+            if let Some(ref scope) = self.scope {
+                scope.enforce(base, std::slice::from_ref(entry))?;
+            }
+            self.validate_entry(&resolved(base, entry))
+        });
+
+        let mut writer = XmlWriter::with_options(self.writer_options.clone());
+        writer.write_video_sitemap(&entries)?;
+        let xml = writer.into_string()?;
+
+        Ok(RepairedSitemap { xml, report })
+    }
+
     /// Build the sitemap XML as bytes (Vec<u8>)
     pub fn build_bytes(&self) -> Result<Vec<u8>> {
         let xml = self.build()?;
@@ -475,26 +806,31 @@ impl VideoSitemapBuilder {
     pub fn build_compressed_bytes(&self) -> Result<Vec<u8>> {
         let xml = self.build()?;
         let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-        encoder.write_all(xml.as_bytes())?;
-        let compressed = encoder.finish()?;
-        Ok(compressed)
+        encoder
+            .write_all(xml.as_bytes())
+            .map_err(|err| Error::compression(None, err))?;
+        encoder.finish().map_err(|err| Error::compression(None, err))
     }
 
     /// Write sitemap to a file
     pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let xml = self.build()?;
-        let mut file = File::create(path)?;
-        file.write_all(xml.as_bytes())?;
+        let path = path.as_ref();
+        let mut file = File::create(path).with_path(path)?;
+        file.write_all(xml.as_bytes()).with_path(path)?;
         Ok(())
     }
 
     /// Write compressed sitemap to a file
     pub fn write_compressed<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let xml = self.build()?;
-        let file = File::create(path)?;
+        let path = path.as_ref();
+        let file = File::create(path).with_path(path)?;
         let mut encoder = GzEncoder::new(file, Compression::default());
-        encoder.write_all(xml.as_bytes())?;
-        encoder.finish()?;
+        encoder
+            .write_all(xml.as_bytes())
+            .map_err(|err| Error::compression(Some(path), err))?;
+        encoder.finish().map_err(|err| Error::compression(Some(path), err))?;
         Ok(())
     }
 }
@@ -504,6 +840,7 @@ impl VideoSitemapBuilder {
 pub struct SitemapIndexBuilder {
     entries: Vec<SitemapIndexEntry>,
     validate: bool,
+    writer_options: WriterOptions,
 }
 
 impl SitemapIndexBuilder {
@@ -512,6 +849,7 @@ impl SitemapIndexBuilder {
         Self {
             entries: Vec::new(),
             validate: true,
+            writer_options: WriterOptions::default(),
         }
     }
 
@@ -532,6 +870,7 @@ impl SitemapIndexBuilder {
         Self {
             entries: Vec::with_capacity(capacity),
             validate: true,
+            writer_options: WriterOptions::default(),
         }
     }
 
@@ -541,6 +880,41 @@ impl SitemapIndexBuilder {
         self
     }
 
+    /// Reference an XSL stylesheet from the generated XML
+    ///
+    /// See [`DEFAULT_STYLESHEET`](crate::writer::DEFAULT_STYLESHEET) for a bundled one.
+    pub fn stylesheet(mut self, href: impl Into<String>) -> Self {
+        self.writer_options.stylesheet = Some(href.into());
+        self
+    }
+
+    /// Set how URLs are serialized (default: [`UrlEncoding::AsIs`])
+    pub fn url_encoding(mut self, encoding: UrlEncoding) -> Self {
+        self.writer_options.url_encoding = encoding;
+        self
+    }
+
+    /// Set how characters not allowed in XML are handled (default: [`InvalidCharPolicy::Reject`])
+    pub fn invalid_chars(mut self, policy: InvalidCharPolicy) -> Self {
+        self.writer_options.invalid_chars = policy;
+        self
+    }
+
+    /// Normalize text fields to Unicode NFC (disabled by default)
+    pub fn normalize_nfc(mut self, normalize: bool) -> Self {
+        self.writer_options.normalize_nfc = normalize;
+        self
+    }
+
+    /// Resolve relative URLs against a base URL when building
+    ///
+    /// Relative URLs are kept as added, so the same entries can be built for
+    /// different hosts.
+    pub fn base_url(mut self, base: Url) -> Self {
+        self.writer_options.base_url = Some(base);
+        self
+    }
+
     /// Add a sitemap entry
     pub fn add_sitemap(&mut self, entry: SitemapIndexEntry) -> &mut Self {
         self.entries.push(entry);
@@ -563,19 +937,29 @@ impl SitemapIndexBuilder {
         self.entries.is_empty()
     }
 
+    /// Get the sitemap entries
+    pub fn entries(&self) -> &[SitemapIndexEntry] {
+        &self.entries
+    }
+
     /// Validate all entries
-    fn validate_entries(&self) -> Result<()> {
+    fn validate_entries(&self, entries: &[SitemapIndexEntry]) -> Result<()> {
         if !self.validate {
             return Ok(());
         }
 
         // Validate each entry
-        for entry in &self.entries {
-            Validator::validate_url(&entry.loc)?;
+        validate_each(entries, |entry| {
+            self.validate_entry(&resolved(self.writer_options.base_url.as_ref(), entry))
+        })
+    }
 
-            if let Some(ref lastmod) = entry.lastmod {
-                Validator::validate_date(lastmod)?;
-            }
+    /// Validate a single entry
+    fn validate_entry(&self, entry: &SitemapIndexEntry) -> Result<()> {
+        Validator::validate_url(&entry.loc)?;
+
+        if let Some(ref lastmod) = entry.lastmod {
+            Validator::validate_date(lastmod)?;
         }
 
         Ok(())
@@ -583,9 +967,9 @@ impl SitemapIndexBuilder {
 
     /// Build the sitemap index XML as a String
     pub fn build(&self) -> Result<String> {
-        self.validate_entries()?;
+        self.validate_entries(&self.entries)?;
 
-        let mut writer = XmlWriter::new();
+        let mut writer = XmlWriter::with_options(self.writer_options.clone());
         writer.write_sitemap_index(&self.entries)?;
         let xml = writer.into_string()?;
 
@@ -597,6 +981,27 @@ impl SitemapIndexBuilder {
         Ok(xml)
     }
 
+    /// Build the XML, repairing invalid entries instead of failing
+    ///
+    /// Values with an obvious valid replacement are fixed, see
+    /// [`repair`](crate::repair) for the list. Entries that still fail
+    /// validation, or are over the URL or size limit, are left out. Validation runs
+    /// even if it is disabled for [`build`](Self::build).
+    pub fn build_repaired(&self) -> Result<RepairedSitemap> {
+        let base = self.writer_options.base_url.as_ref();
+        let entries = self.entries.clone();
+        let size = EntrySize::new(&self.writer_options, XmlWriter::write_sitemap_index)?;
+        let (entries, report) = repair_entries(entries, LengthUnit::default(), MAX_URLS, &size, |entry| {
+            self.validate_entry(&resolved(base, entry))
+        });
+
+        let mut writer = XmlWriter::with_options(self.writer_options.clone());
+        writer.write_sitemap_index(&entries)?;
+        let xml = writer.into_string()?;
+
+        Ok(RepairedSitemap { xml, report })
+    }
+
     /// Build the sitemap index XML as bytes (Vec<u8>)
     pub fn build_bytes(&self) -> Result<Vec<u8>> {
         let xml = self.build()?;
@@ -607,26 +1012,31 @@ impl SitemapIndexBuilder {
     pub fn build_compressed_bytes(&self) -> Result<Vec<u8>> {
         let xml = self.build()?;
         let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-        encoder.write_all(xml.as_bytes())?;
-        let compressed = encoder.finish()?;
-        Ok(compressed)
+        encoder
+            .write_all(xml.as_bytes())
+            .map_err(|err| Error::compression(None, err))?;
+        encoder.finish().map_err(|err| Error::compression(None, err))
     }
 
     /// Write sitemap index to a file
     pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let xml = self.build()?;
-        let mut file = File::create(path)?;
-        file.write_all(xml.as_bytes())?;
+        let path = path.as_ref();
+        let mut file = File::create(path).with_path(path)?;
+        file.write_all(xml.as_bytes()).with_path(path)?;
         Ok(())
     }
 
     /// Write compressed sitemap index to a file
     pub fn write_compressed<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let xml = self.build()?;
-        let file = File::create(path)?;
+        let path = path.as_ref();
+        let file = File::create(path).with_path(path)?;
         let mut encoder = GzEncoder::new(file, Compression::default());
-        encoder.write_all(xml.as_bytes())?;
-        encoder.finish()?;
+        encoder
+            .write_all(xml.as_bytes())
+            .map_err(|err| Error::compression(Some(path), err))?;
+        encoder.finish().map_err(|err| Error::compression(Some(path), err))?;
         Ok(())
     }
 }
@@ -639,6 +1049,9 @@ impl SitemapIndexBuilder {
 pub struct NewsSitemapBuilder {
     entries: Vec<UrlWithNews>,
     validate: bool,
+    writer_options: WriterOptions,
+    scope: Option<SitemapScope>,
+    news_checks: NewsChecks,
 }
 
 impl NewsSitemapBuilder {
@@ -647,6 +1060,9 @@ impl NewsSitemapBuilder {
         Self {
             entries: Vec::new(),
             validate: true,
+            writer_options: WriterOptions::default(),
+            scope: None,
+            news_checks: NewsChecks::default(),
         }
     }
 
@@ -667,6 +1083,9 @@ impl NewsSitemapBuilder {
         Self {
             entries: Vec::with_capacity(capacity),
             validate: true,
+            writer_options: WriterOptions::default(),
+            scope: None,
+            news_checks: NewsChecks::default(),
         }
     }
 
@@ -676,6 +1095,67 @@ impl NewsSitemapBuilder {
         self
     }
 
+    /// Reference an XSL stylesheet from the generated XML
+    ///
+    /// See [`DEFAULT_STYLESHEET`](crate::writer::DEFAULT_STYLESHEET) for a bundled one.
+    pub fn stylesheet(mut self, href: impl Into<String>) -> Self {
+        self.writer_options.stylesheet = Some(href.into());
+        self
+    }
+
+    /// Set how URLs are serialized (default: [`UrlEncoding::AsIs`])
+    pub fn url_encoding(mut self, encoding: UrlEncoding) -> Self {
+        self.writer_options.url_encoding = encoding;
+        self
+    }
+
+    /// Set how characters not allowed in XML are handled (default: [`InvalidCharPolicy::Reject`])
+    pub fn invalid_chars(mut self, policy: InvalidCharPolicy) -> Self {
+        self.writer_options.invalid_chars = policy;
+        self
+    }
+
+    /// Normalize text fields to Unicode NFC (disabled by default)
+    pub fn normalize_nfc(mut self, normalize: bool) -> Self {
+        self.writer_options.normalize_nfc = normalize;
+        self
+    }
+
+    /// Register a prefix for extension elements in a custom namespace
+    ///
+    /// Building fails if the prefix is also registered for another URI. See
+    /// [`extension`](crate::extension) for attaching elements to entries.
+    pub fn namespace(mut self, prefix: impl Into<String>, uri: impl Into<String>) -> Self {
+        self.writer_options.namespaces.push((prefix.into(), uri.into()));
+        self
+    }
+
+    /// Resolve relative URLs against a base URL when building
+    ///
+    /// Relative URLs are kept as added, so the same entries can be built for
+    /// different hosts.
+    pub fn base_url(mut self, base: Url) -> Self {
+        self.writer_options.base_url = Some(base);
+        self
+    }
+
+    /// Restrict URLs to the scope of the sitemap's public URL
+    ///
+    /// Out-of-scope URLs fail the build unless the scope is [`warn_only`](SitemapScope::warn_only),
+    /// also when [`validate`](Self::validate) is disabled.
+    pub fn scope(mut self, scope: SitemapScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    /// Apply editorial checks to the news entries (none by default)
+    ///
+    /// See [`NewsChecks`] for the available checks.
+    pub fn news_checks(mut self, checks: NewsChecks) -> Self {
+        self.news_checks = checks;
+        self
+    }
+
     /// Add a URL with news metadata
     pub fn add_url(&mut self, entry: UrlWithNews) -> &mut Self {
         self.entries.push(entry);
@@ -698,40 +1178,74 @@ impl NewsSitemapBuilder {
         self.entries.is_empty()
     }
 
+    /// Normalize all URLs and collapse entries that become duplicates
+    ///
+    /// See [`UrlNormalizer`](crate::normalize::UrlNormalizer) for the rules applied.
+    pub fn normalize_urls(&mut self, normalizer: &UrlNormalizer, policy: DedupPolicy) -> NormalizationReport {
+        let (entries, report) = normalizer.apply(std::mem::take(&mut self.entries), policy);
+        self.entries = entries;
+        report
+    }
+
+    /// Get the URLs outside the configured scope (empty if no scope is set)
+    pub fn scope_violations(&self) -> Vec<ScopeViolation> {
+        match self.scope {
+            Some(ref scope) => scope.violations(self.writer_options.base_url.as_ref(), &self.entries),
+            None => Vec::new(),
+        }
+    }
+
     /// Validate all entries
-    fn validate_entries(&self) -> Result<()> {
+    fn validate_entries(&self, entries: &[UrlWithNews]) -> Result<()> {
+        // Check sitemap scope, which is enforced even with validation disabled
+        if let Some(ref scope) = self.scope {
+            scope.enforce(self.writer_options.base_url.as_ref(), entries)?;
+        }
+
         if !self.validate {
             return Ok(());
         }
 
         // Validate URL count (max 1,000 for news sitemaps)
-        Validator::validate_news_url_count(self.entries.len())?;
+        Validator::validate_news_url_count(entries.len())?;
 
         // Validate each entry
-        for entry in &self.entries {
-            // Validate URL
-            Validator::validate_url(&entry.url.loc)?;
+        validate_each(entries, |entry| {
+            self.validate_entry(&resolved(self.writer_options.base_url.as_ref(), entry))
+        })?;
+
+        // Check publication names across entries
+        self.news_checks.check_publications(
+            entries
+                .iter()
+                .enumerate()
+                .map(|(index, entry)| (index, entry.url.loc.as_str(), &entry.news)),
+        )
+    }
 
-            // Validate publication date
-            Validator::validate_date(&entry.news.publication_date)?;
+    /// Validate a single entry
+    fn validate_entry(&self, entry: &UrlWithNews) -> Result<()> {
+        // Validate URL
+        Validator::validate_url(&entry.url.loc)?;
 
-            // Validate language code
-            Validator::validate_language_code(&entry.news.publication.language)?;
+        // Validate publication date
+        Validator::validate_date_field("news:publication_date", &entry.news.publication_date)?;
 
-            // Validate stock tickers if present
-            if let Some(ref tickers) = entry.news.stock_tickers {
-                Validator::validate_stock_tickers(tickers)?;
-            }
-        }
+        // Validate language code
+        Validator::validate_language_code(&entry.news.publication.language)?;
 
-        Ok(())
+        // Validate keywords and stock tickers
+        Validator::validate_keywords(&entry.news.keywords)?;
+        Validator::validate_stock_tickers(&entry.news.stock_tickers)?;
+
+        self.news_checks.check_entry(&entry.news)
     }
 
     /// Build the news sitemap XML as a String
     pub fn build(&self) -> Result<String> {
-        self.validate_entries()?;
+        self.validate_entries(&self.entries)?;
 
-        let mut writer = XmlWriter::new();
+        let mut writer = XmlWriter::with_options(self.writer_options.clone());
         writer.write_news_sitemap(&self.entries)?;
         let xml = writer.into_string()?;
 
@@ -743,6 +1257,30 @@ impl NewsSitemapBuilder {
         Ok(xml)
     }
 
+    /// Build the XML, repairing invalid entries instead of failing
+    ///
+    /// Values with an obvious valid replacement are fixed, see
+    /// [`repair`](crate::repair) for the list. Entries that still fail
+    /// validation, or are over the URL or size limit, are left out. Validation runs
+    /// even if it is disabled for [`build`](Self::build).
+    pub fn build_repaired(&self) -> Result<RepairedSitemap> {
+        let base = self.writer_options.base_url.as_ref();
+        let entries = self.entries.clone();
+        let size = EntrySize::new(&self.writer_options, XmlWriter::write_news_sitemap)?;
+        let (entries, report) = repair_entries(entries, LengthUnit::default(), MAX_NEWS_URLS, &size, |entry| {
+            if let Some(ref scope) = self.scope {
+                scope.enforce(base, std::slice::from_ref(entry))?;
+            }
+            self.validate_entry(&resolved(base, entry))
+        });
+
+        let mut writer = XmlWriter::with_options(self.writer_options.clone());
+        writer.write_news_sitemap(&entries)?;
+        let xml = writer.into_string()?;
+
+        Ok(RepairedSitemap { xml, report })
+    }
+
     /// Build the news sitemap XML as bytes (Vec<u8>)
     /// Perfect for web framework responses
     pub fn build_bytes(&self) -> Result<Vec<u8>> {
@@ -755,26 +1293,31 @@ impl NewsSitemapBuilder {
     pub fn build_compressed_bytes(&self) -> Result<Vec<u8>> {
         let xml = self.build()?;
         let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-        encoder.write_all(xml.as_bytes())?;
-        let compressed = encoder.finish()?;
-        Ok(compressed)
+        encoder
+            .write_all(xml.as_bytes())
+            .map_err(|err| Error::compression(None, err))?;
+        encoder.finish().map_err(|err| Error::compression(None, err))
     }
 
     /// Write news sitemap to a file
     pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let xml = self.build()?;
-        let mut file = File::create(path)?;
-        file.write_all(xml.as_bytes())?;
+        let path = path.as_ref();
+        let mut file = File::create(path).with_path(path)?;
+        file.write_all(xml.as_bytes()).with_path(path)?;
         Ok(())
     }
 
     /// Write compressed news sitemap to a file
     pub fn write_compressed<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let xml = self.build()?;
-        let file = File::create(path)?;
+        let path = path.as_ref();
+        let file = File::create(path).with_path(path)?;
         let mut encoder = GzEncoder::new(file, Compression::default());
-        encoder.write_all(xml.as_bytes())?;
-        encoder.finish()?;
+        encoder
+            .write_all(xml.as_bytes())
+            .map_err(|err| Error::compression(Some(path), err))?;
+        encoder.finish().map_err(|err| Error::compression(Some(path), err))?;
         Ok(())
     }
 }
@@ -792,6 +1335,10 @@ impl Default for NewsSitemapBuilder {
 pub struct CombinedSitemapBuilder {
     entries: Vec<UrlWithExtensions>,
     validate: bool,
+    writer_options: WriterOptions,
+    scope: Option<SitemapScope>,
+    length_unit: LengthUnit,
+    news_checks: NewsChecks,
 }
 
 impl CombinedSitemapBuilder {
@@ -800,6 +1347,10 @@ impl CombinedSitemapBuilder {
         Self {
             entries: Vec::new(),
             validate: true,
+            writer_options: WriterOptions::default(),
+            scope: None,
+            length_unit: LengthUnit::default(),
+            news_checks: NewsChecks::default(),
         }
     }
 
@@ -820,6 +1371,10 @@ impl CombinedSitemapBuilder {
         Self {
             entries: Vec::with_capacity(capacity),
             validate: true,
+            writer_options: WriterOptions::default(),
+            scope: None,
+            length_unit: LengthUnit::default(),
+            news_checks: NewsChecks::default(),
         }
     }
 
@@ -829,6 +1384,74 @@ impl CombinedSitemapBuilder {
         self
     }
 
+    /// Reference an XSL stylesheet from the generated XML
+    ///
+    /// See [`DEFAULT_STYLESHEET`](crate::writer::DEFAULT_STYLESHEET) for a bundled one.
+    pub fn stylesheet(mut self, href: impl Into<String>) -> Self {
+        self.writer_options.stylesheet = Some(href.into());
+        self
+    }
+
+    /// Set how URLs are serialized (default: [`UrlEncoding::AsIs`])
+    pub fn url_encoding(mut self, encoding: UrlEncoding) -> Self {
+        self.writer_options.url_encoding = encoding;
+        self
+    }
+
+    /// Set how characters not allowed in XML are handled (default: [`InvalidCharPolicy::Reject`])
+    pub fn invalid_chars(mut self, policy: InvalidCharPolicy) -> Self {
+        self.writer_options.invalid_chars = policy;
+        self
+    }
+
+    /// Normalize text fields to Unicode NFC (disabled by default)
+    pub fn normalize_nfc(mut self, normalize: bool) -> Self {
+        self.writer_options.normalize_nfc = normalize;
+        self
+    }
+
+    /// Register a prefix for extension elements in a custom namespace
+    ///
+    /// Building fails if the prefix is also registered for another URI. See
+    /// [`extension`](crate::extension) for attaching elements to entries.
+    pub fn namespace(mut self, prefix: impl Into<String>, uri: impl Into<String>) -> Self {
+        self.writer_options.namespaces.push((prefix.into(), uri.into()));
+        self
+    }
+
+    /// Resolve relative URLs against a base URL when building
+    ///
+    /// Relative URLs are kept as added, so the same entries can be built for
+    /// different hosts.
+    pub fn base_url(mut self, base: Url) -> Self {
+        self.writer_options.base_url = Some(base);
+        self
+    }
+
+    /// Restrict URLs to the scope of the sitemap's public URL
+    ///
+    /// Out-of-scope URLs fail the build unless the scope is [`warn_only`](SitemapScope::warn_only),
+    /// also when [`validate`](Self::validate) is disabled.
+    pub fn scope(mut self, scope: SitemapScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    /// Set how title, description and category lengths are counted
+    /// (default: [`LengthUnit::Chars`])
+    pub fn length_unit(mut self, unit: LengthUnit) -> Self {
+        self.length_unit = unit;
+        self
+    }
+
+    /// Apply editorial checks to entries with news metadata (none by default)
+    ///
+    /// See [`NewsChecks`] for the available checks.
+    pub fn news_checks(mut self, checks: NewsChecks) -> Self {
+        self.news_checks = checks;
+        self
+    }
+
     /// Add a URL with extensions
     pub fn add_url(&mut self, entry: UrlWithExtensions) -> &mut Self {
         self.entries.push(entry);
@@ -851,61 +1474,88 @@ impl CombinedSitemapBuilder {
         self.entries.is_empty()
     }
 
+    /// Normalize all URLs and collapse entries that become duplicates
+    ///
+    /// See [`UrlNormalizer`](crate::normalize::UrlNormalizer) for the rules applied.
+    pub fn normalize_urls(&mut self, normalizer: &UrlNormalizer, policy: DedupPolicy) -> NormalizationReport {
+        let (entries, report) = normalizer.apply(std::mem::take(&mut self.entries), policy);
+        self.entries = entries;
+        report
+    }
+
+    /// Get the URLs outside the configured scope (empty if no scope is set)
+    pub fn scope_violations(&self) -> Vec<ScopeViolation> {
+        match self.scope {
+            Some(ref scope) => scope.violations(self.writer_options.base_url.as_ref(), &self.entries),
+            None => Vec::new(),
+        }
+    }
+
     /// Validate all entries
-    fn validate_entries(&self) -> Result<()> {
+    fn validate_entries(&self, entries: &[UrlWithExtensions]) -> Result<()> {
+        // Check sitemap scope, which is enforced even with validation disabled
+        if let Some(ref scope) = self.scope {
+            scope.enforce(self.writer_options.base_url.as_ref(), entries)?;
+        }
+
         if !self.validate {
             return Ok(());
         }
 
         // Check if there are any news entries (affects URL limit)
-        let has_news = self.entries.iter().any(|e| e.news.is_some());
+        let has_news = entries.iter().any(|e| e.news.is_some());
 
         // Validate URL count
         if has_news {
             // If any URL has news metadata, apply news sitemap limit (1,000)
-            Validator::validate_news_url_count(self.entries.len())?;
+            Validator::validate_news_url_count(entries.len())?;
         } else {
             // Otherwise use standard sitemap limit (50,000)
-            Validator::validate_url_count(self.entries.len())?;
+            Validator::validate_url_count(entries.len())?;
         }
 
         // Validate each entry
-        for entry in &self.entries {
-            // Validate URL
-            Validator::validate_url(&entry.url.loc)?;
-
-            // Validate URL fields
-            if let Some(ref lastmod) = entry.url.lastmod {
-                Validator::validate_date(lastmod)?;
-            }
-
-            if let Some(priority) = entry.url.priority {
-                Validator::validate_priority(priority)?;
-            }
+        validate_each(entries, |entry| {
+            self.validate_entry(&resolved(self.writer_options.base_url.as_ref(), entry))
+        })?;
+
+        // Check publication names across entries
+        self.news_checks.check_publications(
+            entries
+                .iter()
+                .enumerate()
+                .filter_map(|(index, entry)| Some((index, entry.url.loc.as_str(), entry.news.as_ref()?))),
+        )
+    }
+
+    /// Validate a single entry
+    fn validate_entry(&self, entry: &UrlWithExtensions) -> Result<()> {
+        // Validate URL
+        Validator::validate_url(&entry.url.loc)?;
+
+        // Validate URL fields
+        if let Some(ref lastmod) = entry.url.lastmod {
+            Validator::validate_date(lastmod)?;
+        }
 
-            // Validate videos
-            for video in &entry.videos {
-                Validator::validate_video_title(&video.title)?;
-                Validator::validate_video_description(&video.description)?;
+        if let Some(priority) = entry.url.priority {
+            Validator::validate_priority(priority)?;
+        }
 
-                if let Some(duration) = video.duration {
-                    Validator::validate_video_duration(duration)?;
-                }
+        // Validate videos
+        for video in &entry.videos {
+            Validator::validate_video(&entry.url.loc, video, self.length_unit)?;
+        }
 
-                if let Some(rating) = video.rating {
-                    Validator::validate_video_rating(rating)?;
-                }
-            }
+        // Validate news (if present)
+        if let Some(ref news) = entry.news {
+            Validator::validate_date_field("news:publication_date", &news.publication_date)?;
+            Validator::validate_language_code(&news.publication.language)?;
 
-            // Validate news (if present)
-            if let Some(ref news) = entry.news {
-                Validator::validate_date(&news.publication_date)?;
-                Validator::validate_language_code(&news.publication.language)?;
+            Validator::validate_keywords(&news.keywords)?;
+            Validator::validate_stock_tickers(&news.stock_tickers)?;
 
-                if let Some(ref tickers) = news.stock_tickers {
-                    Validator::validate_stock_tickers(tickers)?;
-                }
-            }
+            self.news_checks.check_entry(news)?;
         }
 
         Ok(())
@@ -913,9 +1563,9 @@ impl CombinedSitemapBuilder {
 
     /// Build the combined sitemap XML as a String
     pub fn build(&self) -> Result<String> {
-        self.validate_entries()?;
+        self.validate_entries(&self.entries)?;
 
-        let mut writer = XmlWriter::new();
+        let mut writer = XmlWriter::with_options(self.writer_options.clone());
         writer.write_combined_sitemap(&self.entries)?;
         let xml = writer.into_string()?;
 
@@ -927,6 +1577,36 @@ impl CombinedSitemapBuilder {
         Ok(xml)
     }
 
+    /// Build the XML, repairing invalid entries instead of failing
+    ///
+    /// Values with an obvious valid replacement are fixed, see
+    /// [`repair`](crate::repair) for the list. Entries that still fail
+    /// validation, or are over the URL or size limit, are left out. Validation runs
+    /// even if it is disabled for [`build`](Self::build).
+    pub fn build_repaired(&self) -> Result<RepairedSitemap> {
+        let base = self.writer_options.base_url.as_ref();
+        let entries = self.entries.clone();
+        // Any news metadata applies the news sitemap limit
+        let limit = if entries.iter().any(|e| e.news.is_some()) {
+            MAX_NEWS_URLS
+        } else {
+            MAX_URLS
+        };
+        let size = EntrySize::new(&self.writer_options, XmlWriter::write_combined_sitemap)?;
+        let (entries, report) = repair_entries(entries, self.length_unit, limit, &size, |entry| {
+            if let Some(ref scope) = self.scope {
+                scope.enforce(base, std::slice::from_ref(entry))?;
+            }
+            self.validate_entry(&resolved(base, entry))
+        });
+
+        let mut writer = XmlWriter::with_options(self.writer_options.clone());
+        writer.write_combined_sitemap(&entries)?;
+        let xml = writer.into_string()?;
+
+        Ok(RepairedSitemap { xml, report })
+    }
+
     /// Build the combined sitemap XML as bytes (Vec<u8>)
     /// Perfect for web framework responses
     pub fn build_bytes(&self) -> Result<Vec<u8>> {
@@ -939,26 +1619,31 @@ impl CombinedSitemapBuilder {
     pub fn build_compressed_bytes(&self) -> Result<Vec<u8>> {
         let xml = self.build()?;
         let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-        encoder.write_all(xml.as_bytes())?;
-        let compressed = encoder.finish()?;
-        Ok(compressed)
+        encoder
+            .write_all(xml.as_bytes())
+            .map_err(|err| Error::compression(None, err))?;
+        encoder.finish().map_err(|err| Error::compression(None, err))
     }
 
     /// Write combined sitemap to a file
     pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let xml = self.build()?;
-        let mut file = File::create(path)?;
-        file.write_all(xml.as_bytes())?;
+        let path = path.as_ref();
+        let mut file = File::create(path).with_path(path)?;
+        file.write_all(xml.as_bytes()).with_path(path)?;
         Ok(())
     }
 
     /// Write compressed combined sitemap to a file
     pub fn write_compressed<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let xml = self.build()?;
-        let file = File::create(path)?;
+        let path = path.as_ref();
+        let file = File::create(path).with_path(path)?;
         let mut encoder = GzEncoder::new(file, Compression::default());
-        encoder.write_all(xml.as_bytes())?;
-        encoder.finish()?;
+        encoder
+            .write_all(xml.as_bytes())
+            .map_err(|err| Error::compression(Some(path), err))?;
+        encoder.finish().map_err(|err| Error::compression(Some(path), err))?;
         Ok(())
     }
 }
@@ -0,0 +1,1051 @@
+//! Schema conformance checking of sitemap documents
+//!
+//! [`SitemapParser`](crate::SitemapParser) picks out the elements it knows,
+//! keeps custom-namespace children of `<url>` and ignores everything else. [`ConformanceChecker`] instead checks a whole
+//! document against the sitemaps.org 0.9 schema and the Google image, video
+//! and news extension rules, and reports every problem with its line, column
+//! and element path.
+//!
+//! Element order is checked for the sitemaps.org elements, whose schema is a
+//! strict sequence. Google does not require an order for extension elements,
+//! so for those only missing, repeated, unknown and invalid elements are
+//! reported. Elements in other namespaces may follow the standard `<url>`
+//! children and are not checked.
+
+use crate::error::{Error, IoContext, Result};
+use crate::types::StockTicker;
+use crate::validator::{parse_w3c_datetime, Validator, MAX_SIZE_BYTES, MAX_URLS};
+use crate::writer::{IMAGE_NS, NEWS_NS, SITEMAP_NS, VIDEO_NS};
+use flate2::read::GzDecoder;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::name::ResolveResult;
+use quick_xml::reader::NsReader;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+/// Maximum number of images per page
+const MAX_IMAGES_PER_URL: usize = 1_000;
+
+/// Maximum number of tags per video
+const MAX_VIDEO_TAGS: usize = 32;
+
+const URL_FIELDS: &[&str] = &["loc", "lastmod", "changefreq", "priority"];
+const SITEMAP_FIELDS: &[&str] = &["loc", "lastmod"];
+const IMAGE_FIELDS: &[&str] = &["loc", "caption", "geo_location", "title", "license"];
+const VIDEO_FIELDS: &[&str] = &[
+    "thumbnail_loc",
+    "title",
+    "description",
+    "content_loc",
+    "player_loc",
+    "duration",
+    "expiration_date",
+    "rating",
+    "view_count",
+    "publication_date",
+    "family_friendly",
+    "restriction",
+    "platform",
+    "price",
+    "requires_subscription",
+    "uploader",
+    "live",
+    "tag",
+    "category",
+    "gallery_loc",
+    "content_segment_loc",
+];
+const VIDEO_REPEATABLE: &[&str] = &["price", "tag", "content_segment_loc"];
+const NEWS_FIELDS: &[&str] = &[
+    "publication",
+    "publication_date",
+    "title",
+    "keywords",
+    "stock_tickers",
+    "genres",
+    "access",
+];
+const PUBLICATION_FIELDS: &[&str] = &["name", "language"];
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Allowed by the schema, but likely a mistake
+    Warning,
+
+    /// The document does not conform
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// What kind of problem a diagnostic reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticKind {
+    /// The document is not well-formed XML
+    Malformed,
+
+    /// The root element is not `<urlset>` or `<sitemapindex>`
+    InvalidRoot,
+
+    /// An element is not in the namespace the schema requires
+    WrongNamespace,
+
+    /// An element that is not allowed at its position
+    UnknownElement,
+
+    /// An element that appears after one it must precede
+    MisorderedElement,
+
+    /// A required element is missing
+    MissingElement,
+
+    /// An element that may appear only once is repeated
+    DuplicateElement,
+
+    /// A URL listed more than once in the document
+    DuplicateLoc,
+
+    /// An element or attribute with an invalid value
+    InvalidValue,
+
+    /// More elements than the protocol allows, or a document over 50MB
+    LimitExceeded,
+}
+
+/// A problem found in a document, with its location
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// How serious the problem is
+    pub severity: Severity,
+
+    /// What kind of problem it is
+    pub kind: DiagnosticKind,
+
+    /// Line of the offending element (1-based)
+    pub line: usize,
+
+    /// Column of the offending element, in characters (1-based)
+    pub column: usize,
+
+    /// Path of the offending element, such as `/urlset/url[3]/video:video[1]`
+    pub path: String,
+
+    /// Human-readable description
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {} ({})",
+            self.line, self.column, self.severity, self.message, self.path
+        )
+    }
+}
+
+/// Result of checking a document
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConformanceReport {
+    /// Diagnostics in document order
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ConformanceReport {
+    /// Check whether the document has no errors (warnings are allowed)
+    pub fn is_conformant(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Get the error diagnostics
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Error)
+    }
+
+    /// Get the warning diagnostics
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Warning)
+    }
+}
+
+/// Checker for sitemap and sitemap index documents
+///
+/// # Example
+/// ```
+/// use sitemap_generator::{ConformanceChecker, DiagnosticKind};
+///
+/// let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+/// <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+///   <url>
+///     <lastmod>2025-11-01</lastmod>
+///     <loc>https://example.com/</loc>
+///   </url>
+/// </urlset>"#;
+///
+/// let report = ConformanceChecker::check_string(xml);
+/// assert!(!report.is_conformant());
+///
+/// let diagnostic = &report.diagnostics[0];
+/// assert_eq!(diagnostic.kind, DiagnosticKind::MisorderedElement);
+/// assert_eq!((diagnostic.line, diagnostic.column), (5, 5));
+/// assert_eq!(diagnostic.path, "/urlset/url[1]/loc[1]");
+/// ```
+pub struct ConformanceChecker;
+
+impl ConformanceChecker {
+    /// Check a document from a string
+    pub fn check_string(xml: &str) -> ConformanceReport {
+        let mut checker = Checker::new(xml);
+        if xml.len() > MAX_SIZE_BYTES {
+            checker.report(
+                Severity::Error,
+                DiagnosticKind::LimitExceeded,
+                0,
+                "/".to_string(),
+                format!("document is {} bytes, the maximum is {}", xml.len(), MAX_SIZE_BYTES),
+            );
+        }
+        checker.run();
+        ConformanceReport {
+            diagnostics: checker.diagnostics,
+        }
+    }
+
+    /// Check a document from a file
+    pub fn check_file<P: AsRef<Path>>(path: P) -> Result<ConformanceReport> {
+        Ok(Self::check_bytes(fs::read(&path).with_path(&path)?))
+    }
+
+    /// Check a compressed document from a file
+    ///
+    /// Decompression stops just past the 50MB limit, so oversized documents
+    /// are reported without being read in full.
+    pub fn check_compressed<P: AsRef<Path>>(path: P) -> Result<ConformanceReport> {
+        let path = path.as_ref();
+        let file = File::open(path).with_path(path)?;
+        let mut bytes = Vec::new();
+        GzDecoder::new(file)
+            .take(MAX_SIZE_BYTES as u64 + 1)
+            .read_to_end(&mut bytes)
+            .map_err(|err| Error::compression(Some(path), err))?;
+
+        if bytes.len() > MAX_SIZE_BYTES {
+            let mut checker = Checker::new("");
+            checker.report(
+                Severity::Error,
+                DiagnosticKind::LimitExceeded,
+                0,
+                "/".to_string(),
+                format!("decompressed document exceeds {} bytes", MAX_SIZE_BYTES),
+            );
+            return Ok(ConformanceReport {
+                diagnostics: checker.diagnostics,
+            });
+        }
+
+        Ok(Self::check_bytes(bytes))
+    }
+
+    fn check_bytes(bytes: Vec<u8>) -> ConformanceReport {
+        match String::from_utf8(bytes) {
+            Ok(xml) => Self::check_string(&xml),
+            Err(err) => {
+                let valid = err.utf8_error().valid_up_to();
+                let prefix = String::from_utf8_lossy(&err.as_bytes()[..valid]);
+                let mut checker = Checker::new(&prefix);
+                checker.report(
+                    Severity::Error,
+                    DiagnosticKind::Malformed,
+                    valid,
+                    "/".to_string(),
+                    "document is not valid UTF-8".to_string(),
+                );
+                ConformanceReport {
+                    diagnostics: checker.diagnostics,
+                }
+            }
+        }
+    }
+}
+
+/// What an open element is, as far as the schema is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Urlset,
+    SitemapIndex,
+    Url,
+    Sitemap,
+    Image,
+    Video,
+    News,
+    Publication,
+    /// An element with text content
+    Field,
+    /// An element that is not checked, along with its children
+    Skip,
+}
+
+impl Role {
+    /// Conventional prefix of the children of this element
+    fn child_prefix(self) -> &'static str {
+        match self {
+            Role::Image => "image:",
+            Role::Video => "video:",
+            Role::News | Role::Publication => "news:",
+            _ => "",
+        }
+    }
+}
+
+/// An open element
+struct Frame {
+    role: Role,
+    parent: Option<Role>,
+    name: String,
+    local: String,
+    path: String,
+    offset: usize,
+    /// Local names of the checked children seen so far
+    children: Vec<String>,
+    /// Occurrences of each child name, for paths
+    counts: HashMap<String, usize>,
+    text: String,
+    /// Index into the sequence of standard children that may come next
+    next_field: usize,
+    seen_extension: bool,
+}
+
+struct Checker<'a> {
+    xml: &'a str,
+    line_starts: Vec<usize>,
+    diagnostics: Vec<Diagnostic>,
+    stack: Vec<Frame>,
+    root_seen: bool,
+    /// Namespace of the root element, used for the standard children
+    core_ns: Option<String>,
+    /// Line each `<loc>` was first seen on
+    locs: HashMap<String, usize>,
+}
+
+impl<'a> Checker<'a> {
+    fn new(xml: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(xml.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self {
+            xml,
+            line_starts,
+            diagnostics: Vec::new(),
+            stack: Vec::new(),
+            root_seen: false,
+            core_ns: None,
+            locs: HashMap::new(),
+        }
+    }
+
+    fn run(&mut self) {
+        let mut reader = NsReader::from_str(self.xml);
+
+        loop {
+            let offset = reader.buffer_position() as usize;
+            let (ns, event) = match reader.read_resolved_event() {
+                Ok((ns, event)) => (self.namespace(ns, offset), event),
+                Err(err) => {
+                    let path = self.current_path();
+                    self.report(
+                        Severity::Error,
+                        DiagnosticKind::Malformed,
+                        reader.error_position() as usize,
+                        path,
+                        err.to_string(),
+                    );
+                    return;
+                }
+            };
+
+            match event {
+                Event::Start(e) => self.start(ns.as_deref(), &e, offset),
+                Event::Empty(e) => {
+                    self.start(ns.as_deref(), &e, offset);
+                    self.end();
+                }
+                Event::End(_) => self.end(),
+                Event::Text(e) => match e.unescape() {
+                    Ok(text) => self.text(&text),
+                    Err(err) => {
+                        let path = self.current_path();
+                        self.report(Severity::Error, DiagnosticKind::Malformed, offset, path, err.to_string());
+                    }
+                },
+                Event::CData(e) => self.text(&String::from_utf8_lossy(&e)),
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        if let Some(frame) = self.stack.last() {
+            let (offset, path) = (frame.offset, frame.path.clone());
+            let message = format!("<{}> is not closed", frame.name);
+            self.report(Severity::Error, DiagnosticKind::Malformed, offset, path, message);
+        } else if !self.root_seen {
+            self.report(
+                Severity::Error,
+                DiagnosticKind::InvalidRoot,
+                0,
+                "/".to_string(),
+                "document has no root element".to_string(),
+            );
+        }
+    }
+
+    fn namespace(&mut self, ns: ResolveResult<'_>, offset: usize) -> Option<String> {
+        match ns {
+            ResolveResult::Bound(ns) => Some(String::from_utf8_lossy(ns.as_ref()).into_owned()),
+            ResolveResult::Unbound => None,
+            ResolveResult::Unknown(prefix) => {
+                let path = self.current_path();
+                self.report(
+                    Severity::Error,
+                    DiagnosticKind::WrongNamespace,
+                    offset,
+                    path,
+                    format!("namespace prefix `{}` is not declared", String::from_utf8_lossy(&prefix)),
+                );
+                None
+            }
+        }
+    }
+
+    fn start(&mut self, ns: Option<&str>, e: &BytesStart<'_>, offset: usize) {
+        let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+        let local = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+
+        let path = match self.stack.last_mut() {
+            Some(parent) => {
+                let count = parent.counts.entry(name.clone()).or_insert(0);
+                *count += 1;
+                format!("{}/{}[{}]", parent.path, name, count)
+            }
+            None => format!("/{}", name),
+        };
+
+        let parent = self.stack.last().map(|frame| frame.role);
+        let role = match parent {
+            None => self.root(ns, &name, &local, offset, &path),
+            Some(Role::Urlset) => self.container_child(ns, &name, &local, "url", Role::Url, offset, &path),
+            Some(Role::SitemapIndex) => {
+                self.container_child(ns, &name, &local, "sitemap", Role::Sitemap, offset, &path)
+            }
+            Some(Role::Url) => self.entry_child(URL_FIELDS, ns, &name, &local, offset, &path),
+            Some(Role::Sitemap) => self.entry_child(SITEMAP_FIELDS, ns, &name, &local, offset, &path),
+            Some(Role::Image) => self.extension_child(IMAGE_NS, IMAGE_FIELDS, &[], ns, &name, &local, offset, &path),
+            Some(Role::Video) => {
+                let role = self.extension_child(VIDEO_NS, VIDEO_FIELDS, VIDEO_REPEATABLE, ns, &name, &local, offset, &path);
+                if role == Role::Field {
+                    self.check_video_attributes(&local, e, offset, &path);
+                }
+                role
+            }
+            Some(Role::News) => {
+                let role = self.extension_child(NEWS_NS, NEWS_FIELDS, &[], ns, &name, &local, offset, &path);
+                if role == Role::Field && local == "publication" {
+                    Role::Publication
+                } else {
+                    role
+                }
+            }
+            Some(Role::Publication) => {
+                self.extension_child(NEWS_NS, PUBLICATION_FIELDS, &[], ns, &name, &local, offset, &path)
+            }
+            Some(Role::Field) => {
+                let parent_name = self.stack.last().map(|frame| frame.name.clone()).unwrap_or_default();
+                self.report(
+                    Severity::Error,
+                    DiagnosticKind::UnknownElement,
+                    offset,
+                    path.clone(),
+                    format!("<{}> cannot contain elements", parent_name),
+                );
+                Role::Skip
+            }
+            Some(Role::Skip) => Role::Skip,
+        };
+
+        self.stack.push(Frame {
+            role,
+            parent,
+            name,
+            local,
+            path,
+            offset,
+            children: Vec::new(),
+            counts: HashMap::new(),
+            text: String::new(),
+            next_field: 0,
+            seen_extension: false,
+        });
+    }
+
+    fn root(&mut self, ns: Option<&str>, name: &str, local: &str, offset: usize, path: &str) -> Role {
+        if self.root_seen {
+            return Role::Skip;
+        }
+        self.root_seen = true;
+
+        let role = match local {
+            "urlset" => Role::Urlset,
+            "sitemapindex" => Role::SitemapIndex,
+            _ => {
+                self.report(
+                    Severity::Error,
+                    DiagnosticKind::InvalidRoot,
+                    offset,
+                    path.to_string(),
+                    format!("root element must be <urlset> or <sitemapindex>, found <{}>", name),
+                );
+                return Role::Skip;
+            }
+        };
+
+        if ns != Some(SITEMAP_NS) {
+            self.report(
+                Severity::Error,
+                DiagnosticKind::WrongNamespace,
+                offset,
+                path.to_string(),
+                format!(
+                    "<{}> must be in the {} namespace, found {}",
+                    name,
+                    SITEMAP_NS,
+                    ns.unwrap_or("no namespace")
+                ),
+            );
+        }
+
+        self.core_ns = Some(ns.unwrap_or_default().to_string());
+        role
+    }
+
+    fn is_core(&self, ns: Option<&str>) -> bool {
+        self.core_ns.as_deref() == Some(ns.unwrap_or_default())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn container_child(
+        &mut self,
+        ns: Option<&str>,
+        name: &str,
+        local: &str,
+        expected: &str,
+        role: Role,
+        offset: usize,
+        path: &str,
+    ) -> Role {
+        if self.is_core(ns) && local == expected {
+            if let Some(parent) = self.stack.last_mut() {
+                parent.children.push(local.to_string());
+            }
+            return role;
+        }
+
+        let parent_name = self.stack.last().map(|frame| frame.name.clone()).unwrap_or_default();
+        self.report(
+            Severity::Error,
+            DiagnosticKind::UnknownElement,
+            offset,
+            path.to_string(),
+            format!("<{}> is not allowed in <{}>, expected <{}>", name, parent_name, expected),
+        );
+        Role::Skip
+    }
+
+    fn entry_child(
+        &mut self,
+        fields: &[&str],
+        ns: Option<&str>,
+        name: &str,
+        local: &str,
+        offset: usize,
+        path: &str,
+    ) -> Role {
+        let is_url = fields.len() == URL_FIELDS.len();
+
+        if !self.is_core(ns) {
+            let Some(parent) = self.stack.last_mut() else {
+                return Role::Skip;
+            };
+            parent.seen_extension = true;
+            if !is_url {
+                return Role::Skip;
+            }
+
+            let (role, max) = match (ns, local) {
+                (Some(IMAGE_NS), "image") => (Role::Image, MAX_IMAGES_PER_URL),
+                (Some(VIDEO_NS), "video") => (Role::Video, usize::MAX),
+                (Some(NEWS_NS), "news") => (Role::News, 1),
+                (Some(IMAGE_NS | VIDEO_NS | NEWS_NS), _) => {
+                    self.report(
+                        Severity::Error,
+                        DiagnosticKind::UnknownElement,
+                        offset,
+                        path.to_string(),
+                        format!("<{}> is not allowed in <url>", name),
+                    );
+                    return Role::Skip;
+                }
+                _ => return Role::Skip,
+            };
+
+            let seen = parent.children.iter().filter(|child| *child == local).count();
+            parent.children.push(local.to_string());
+            if seen == max {
+                let (kind, message) = if max == 1 {
+                    (DiagnosticKind::DuplicateElement, format!("<url> may contain only one <{}>", name))
+                } else {
+                    (
+                        DiagnosticKind::LimitExceeded,
+                        format!("<url> may contain at most {} <{}> elements", max, name),
+                    )
+                };
+                self.report(Severity::Error, kind, offset, path.to_string(), message);
+            }
+            return role;
+        }
+
+        let Some(index) = fields.iter().position(|field| *field == local) else {
+            let parent_name = if is_url { "url" } else { "sitemap" };
+            self.report(
+                Severity::Error,
+                DiagnosticKind::UnknownElement,
+                offset,
+                path.to_string(),
+                format!("<{}> is not allowed in <{}>", name, parent_name),
+            );
+            return Role::Skip;
+        };
+
+        let Some(parent) = self.stack.last_mut() else {
+            return Role::Skip;
+        };
+        let duplicate = parent.children.iter().any(|child| child == local);
+        let misordered = parent.seen_extension || index < parent.next_field;
+        parent.children.push(local.to_string());
+        parent.next_field = parent.next_field.max(index + 1);
+
+        if duplicate {
+            let parent_name = parent.name.clone();
+            self.report(
+                Severity::Error,
+                DiagnosticKind::DuplicateElement,
+                offset,
+                path.to_string(),
+                format!("<{}> may contain only one <{}>", parent_name, name),
+            );
+        } else if misordered {
+            let order = if is_url {
+                "loc, lastmod, changefreq, priority, then extensions"
+            } else {
+                "loc, lastmod"
+            };
+            self.report(
+                Severity::Error,
+                DiagnosticKind::MisorderedElement,
+                offset,
+                path.to_string(),
+                format!("<{}> is out of order, expected {}", name, order),
+            );
+        }
+
+        Role::Field
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn extension_child(
+        &mut self,
+        namespace: &str,
+        fields: &[&str],
+        repeatable: &[&str],
+        ns: Option<&str>,
+        name: &str,
+        local: &str,
+        offset: usize,
+        path: &str,
+    ) -> Role {
+        let Some(parent) = self.stack.last_mut() else {
+            return Role::Skip;
+        };
+        let parent_name = parent.name.clone();
+
+        if ns != Some(namespace) || !fields.contains(&local) {
+            self.report(
+                Severity::Error,
+                DiagnosticKind::UnknownElement,
+                offset,
+                path.to_string(),
+                format!("<{}> is not allowed in <{}>", name, parent_name),
+            );
+            return Role::Skip;
+        }
+
+        let duplicate = !repeatable.contains(&local) && parent.children.iter().any(|child| child == local);
+        parent.children.push(local.to_string());
+        if duplicate {
+            self.report(
+                Severity::Error,
+                DiagnosticKind::DuplicateElement,
+                offset,
+                path.to_string(),
+                format!("<{}> may contain only one <{}>", parent_name, name),
+            );
+        }
+
+        Role::Field
+    }
+
+    fn check_video_attributes(&mut self, local: &str, e: &BytesStart<'_>, offset: usize, path: &str) {
+        let attribute = |key: &str| {
+            e.attributes()
+                .flatten()
+                .find(|attr| attr.key.as_ref() == key.as_bytes())
+                .map(|attr| String::from_utf8_lossy(&attr.value).into_owned())
+        };
+
+        let problem = match local {
+            "restriction" | "platform" => match attribute("relationship").as_deref() {
+                Some("allow") | Some("deny") => None,
+                Some(other) => Some(format!("relationship must be \"allow\" or \"deny\", found \"{}\"", other)),
+                None => Some(format!("<video:{}> requires a relationship attribute", local)),
+            },
+            "price" => match attribute("currency") {
+                Some(currency) if currency.len() == 3 && currency.chars().all(|c| c.is_ascii_uppercase()) => None,
+                Some(currency) => Some(format!("currency must be an ISO 4217 code, found \"{}\"", currency)),
+                None => Some("<video:price> requires a currency attribute".to_string()),
+            },
+            _ => None,
+        };
+
+        if let Some(message) = problem {
+            self.report(Severity::Error, DiagnosticKind::InvalidValue, offset, path.to_string(), message);
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some(frame) = self.stack.last_mut() {
+            if frame.role == Role::Field {
+                frame.text.push_str(text);
+            }
+        }
+    }
+
+    fn end(&mut self) {
+        let Some(frame) = self.stack.pop() else {
+            return;
+        };
+
+        match frame.role {
+            Role::Urlset => self.check_container(&frame, "url"),
+            Role::SitemapIndex => self.check_container(&frame, "sitemap"),
+            Role::Url | Role::Sitemap | Role::Image => self.require(&frame, &["loc"]),
+            Role::Video => {
+                self.require(&frame, &["thumbnail_loc", "title", "description"]);
+                if !frame.children.iter().any(|c| c == "content_loc" || c == "player_loc") {
+                    self.report(
+                        Severity::Error,
+                        DiagnosticKind::MissingElement,
+                        frame.offset,
+                        frame.path.clone(),
+                        format!("<{}> requires <video:content_loc> or <video:player_loc>", frame.name),
+                    );
+                }
+                let tags = frame.children.iter().filter(|c| *c == "tag").count();
+                if tags > MAX_VIDEO_TAGS {
+                    self.report(
+                        Severity::Error,
+                        DiagnosticKind::LimitExceeded,
+                        frame.offset,
+                        frame.path.clone(),
+                        format!("<{}> has {} tags, the maximum is {}", frame.name, tags, MAX_VIDEO_TAGS),
+                    );
+                }
+            }
+            Role::News => self.require(&frame, &["publication", "publication_date", "title"]),
+            Role::Publication => self.require(&frame, &["name", "language"]),
+            Role::Field => self.check_value(&frame),
+            Role::Skip => {}
+        }
+    }
+
+    fn check_container(&mut self, frame: &Frame, child: &str) {
+        let count = frame.children.len();
+        if count == 0 {
+            self.report(
+                Severity::Error,
+                DiagnosticKind::MissingElement,
+                frame.offset,
+                frame.path.clone(),
+                format!("<{}> must contain at least one <{}>", frame.name, child),
+            );
+        } else if count > MAX_URLS {
+            self.report(
+                Severity::Error,
+                DiagnosticKind::LimitExceeded,
+                frame.offset,
+                frame.path.clone(),
+                format!("<{}> has {} <{}> elements, the maximum is {}", frame.name, count, child, MAX_URLS),
+            );
+        }
+    }
+
+    fn require(&mut self, frame: &Frame, required: &[&str]) {
+        for field in required {
+            if !frame.children.iter().any(|child| child == field) {
+                self.report(
+                    Severity::Error,
+                    DiagnosticKind::MissingElement,
+                    frame.offset,
+                    frame.path.clone(),
+                    format!("<{}> is missing <{}{}>", frame.name, frame.role.child_prefix(), field),
+                );
+            }
+        }
+    }
+
+    fn check_value(&mut self, frame: &Frame) {
+        let text = frame.text.trim();
+        let is_url = |text: &str| Validator::validate_url(text).is_ok();
+        let is_date = |text: &str| parse_w3c_datetime(text).is_some();
+        let is_yes_no = |text: &str| text == "yes" || text == "no";
+
+        let problem = match (frame.parent, frame.local.as_str()) {
+            (Some(Role::Url | Role::Sitemap), "loc") => {
+                if is_url(text) {
+                    self.check_duplicate_loc(frame, text);
+                    None
+                } else {
+                    Some("must be an absolute URL of at most 2048 characters")
+                }
+            }
+            (Some(Role::Url | Role::Sitemap), "lastmod") if !is_date(text) => Some("must be a W3C datetime"),
+            (Some(Role::Url), "changefreq")
+                if !["always", "hourly", "daily", "weekly", "monthly", "yearly", "never"].contains(&text) =>
+            {
+                Some("must be one of always, hourly, daily, weekly, monthly, yearly or never")
+            }
+            (Some(Role::Url), "priority")
+                if !text.parse::<f32>().is_ok_and(|p| (0.0..=1.0).contains(&p)) =>
+            {
+                Some("must be a number between 0.0 and 1.0")
+            }
+            (Some(Role::Image), "loc") if !is_url(text) => Some("must be an absolute URL"),
+            (Some(Role::Video), "thumbnail_loc" | "content_loc" | "player_loc" | "gallery_loc") if !is_url(text) => {
+                Some("must be an absolute URL")
+            }
+            (Some(Role::Video), "title") if text.is_empty() || text.chars().count() > 100 => {
+                Some("must be 1 to 100 characters")
+            }
+            (Some(Role::Video), "description") if text.is_empty() || text.chars().count() > 2048 => {
+                Some("must be 1 to 2048 characters")
+            }
+            (Some(Role::Video), "duration") if !text.parse::<u32>().is_ok_and(|d| (1..=28_800).contains(&d)) => {
+                Some("must be a whole number of seconds between 1 and 28800")
+            }
+            (Some(Role::Video), "rating") if !text.parse::<f32>().is_ok_and(|r| (0.0..=5.0).contains(&r)) => {
+                Some("must be a number between 0.0 and 5.0")
+            }
+            (Some(Role::Video), "view_count") if text.parse::<u64>().is_err() => Some("must be a whole number"),
+            (Some(Role::Video), "publication_date" | "expiration_date") if !is_date(text) => {
+                Some("must be a W3C datetime")
+            }
+            (Some(Role::Video), "family_friendly" | "requires_subscription" | "live") if !is_yes_no(text) => {
+                Some("must be \"yes\" or \"no\"")
+            }
+            (Some(Role::Video), "restriction")
+                if !text
+                    .split_whitespace()
+                    .all(|code| code.len() == 2 && code.chars().all(|c| c.is_ascii_uppercase())) =>
+            {
+                Some("must be a space-separated list of ISO 3166 country codes")
+            }
+            (Some(Role::Video), "platform")
+                if !text.split_whitespace().all(|p| ["web", "mobile", "tv"].contains(&p)) =>
+            {
+                Some("must be a space-separated list of web, mobile or tv")
+            }
+            (Some(Role::Video), "price") if !text.parse::<f64>().is_ok_and(|p| p >= 0.0) => {
+                Some("must be a non-negative number")
+            }
+            (Some(Role::News), "publication_date") if !is_date(text) => Some("must be a W3C datetime"),
+            (Some(Role::News), "title") if text.is_empty() => Some("must not be empty"),
+            (Some(Role::News), "stock_tickers")
+                if StockTicker::parse_list(text)
+                    .and_then(|tickers| Validator::validate_stock_tickers(&tickers))
+                    .is_err() =>
+            {
+                Some("must list at most 5 tickers as EXCHANGE:SYMBOL or SYMBOL")
+            }
+            (Some(Role::Publication), "name") if text.is_empty() => Some("must not be empty"),
+            (Some(Role::Publication), "language") if Validator::validate_language_code(text).is_err() => {
+                Some("must be an ISO 639 language code")
+            }
+            _ => None,
+        };
+
+        if let Some(problem) = problem {
+            self.report(
+                Severity::Error,
+                DiagnosticKind::InvalidValue,
+                frame.offset,
+                frame.path.clone(),
+                format!("<{}> {}, found \"{}\"", frame.name, problem, text),
+            );
+        }
+    }
+
+    fn check_duplicate_loc(&mut self, frame: &Frame, loc: &str) {
+        let (line, _) = self.locate(frame.offset);
+        match self.locs.get(loc) {
+            Some(first) => {
+                let message = format!("{} is already listed on line {}", loc, first);
+                self.report(Severity::Warning, DiagnosticKind::DuplicateLoc, frame.offset, frame.path.clone(), message);
+            }
+            None => {
+                self.locs.insert(loc.to_string(), line);
+            }
+        }
+    }
+
+    fn current_path(&self) -> String {
+        self.stack
+            .last()
+            .map_or_else(|| "/".to_string(), |frame| frame.path.clone())
+    }
+
+    fn report(&mut self, severity: Severity, kind: DiagnosticKind, offset: usize, path: String, message: String) {
+        let (line, column) = self.locate(offset);
+        self.diagnostics.push(Diagnostic {
+            severity,
+            kind,
+            line,
+            column,
+            path,
+            message,
+        });
+    }
+
+    /// Convert a byte offset to a 1-based line and character column
+    fn locate(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.xml.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let start = self.line_starts[line - 1];
+        let column = self
+            .xml
+            .get(start..offset)
+            .map_or(offset - start, |prefix| prefix.chars().count());
+        (line, column + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(xml: &str) -> Vec<(DiagnosticKind, usize, String)> {
+        ConformanceChecker::check_string(xml)
+            .diagnostics
+            .into_iter()
+            .map(|d| (d.kind, d.line, d.path))
+            .collect()
+    }
+
+    #[test]
+    fn test_urlset_rules() {
+        let xml = r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+<url><loc>https://example.com/</loc><priority>high</priority><changefreq>daily</changefreq></url>
+<url><loc>https://example.com/</loc><loc>https://example.com/b</loc><author>x</author></url>
+<url><lastmod>2025-11-01</lastmod></url>
+</urlset>"#;
+
+        assert_eq!(
+            kinds(xml),
+            vec![
+                (DiagnosticKind::InvalidValue, 2, "/urlset/url[1]/priority[1]".to_string()),
+                (DiagnosticKind::MisorderedElement, 2, "/urlset/url[1]/changefreq[1]".to_string()),
+                (DiagnosticKind::DuplicateLoc, 3, "/urlset/url[2]/loc[1]".to_string()),
+                (DiagnosticKind::DuplicateElement, 3, "/urlset/url[2]/loc[2]".to_string()),
+                (DiagnosticKind::UnknownElement, 3, "/urlset/url[2]/author[1]".to_string()),
+                (DiagnosticKind::MissingElement, 4, "/urlset/url[3]".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_root_and_namespace() {
+        let report = ConformanceChecker::check_string("<urlset><url><loc>https://example.com/</loc></url></urlset>");
+        assert_eq!(report.diagnostics.len(), 1);
+        assert_eq!(report.diagnostics[0].kind, DiagnosticKind::WrongNamespace);
+
+        let report = ConformanceChecker::check_string("<rss><channel/></rss>");
+        assert_eq!(report.diagnostics[0].kind, DiagnosticKind::InvalidRoot);
+
+        let report = ConformanceChecker::check_string("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\"><url>");
+        assert_eq!(report.diagnostics[0].kind, DiagnosticKind::Malformed);
+    }
+
+    #[test]
+    fn test_extension_rules() {
+        let xml = r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+        xmlns:image="http://www.google.com/schemas/sitemap-image/1.1"
+        xmlns:video="http://www.google.com/schemas/sitemap-video/1.1"
+        xmlns:news="http://www.google.com/schemas/sitemap-news/0.9">
+  <url>
+    <loc>https://example.com/a</loc>
+    <image:image><image:caption>No loc</image:caption></image:image>
+    <video:video>
+      <video:thumbnail_loc>https://example.com/t.jpg</video:thumbnail_loc>
+      <video:title>Title</video:title>
+      <video:family_friendly>maybe</video:family_friendly>
+      <video:price>1.99</video:price>
+    </video:video>
+    <news:news>
+      <news:publication><news:name>Daily</news:name><news:language>English</news:language></news:publication>
+      <news:title>Headline</news:title>
+    </news:news>
+  </url>
+</urlset>"#;
+
+        let diagnostics = ConformanceChecker::check_string(xml).diagnostics;
+        let messages: Vec<String> = diagnostics.iter().map(|d| format!("{}:{}", d.line, d.message)).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "7:<image:image> is missing <image:loc>",
+                "11:<video:family_friendly> must be \"yes\" or \"no\", found \"maybe\"",
+                "12:<video:price> requires a currency attribute",
+                "8:<video:video> is missing <video:description>",
+                "8:<video:video> requires <video:content_loc> or <video:player_loc>",
+                "15:<news:language> must be an ISO 639 language code, found \"English\"",
+                "14:<news:news> is missing <news:publication_date>",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_locate_counts_characters() {
+        let xml = "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n<url><loc>https://例え.jp/</loc><bad/></url></urlset>";
+        let report = ConformanceChecker::check_string(xml);
+        assert_eq!(report.diagnostics.len(), 1);
+        assert_eq!((report.diagnostics[0].line, report.diagnostics[0].column), (2, 31));
+    }
+}
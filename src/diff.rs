@@ -0,0 +1,350 @@
+//! Diffing between two versions of a sitemap
+
+use crate::error::Result;
+use crate::types::*;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// A single field that changed between two versions of a URL
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "field", rename_all = "snake_case")]
+pub enum FieldChange {
+    /// `<lastmod>` was added, removed or changed
+    Lastmod {
+        old: Option<String>,
+        new: Option<String>,
+    },
+
+    /// `<priority>` was added, removed or changed
+    Priority { old: Option<f32>, new: Option<f32> },
+
+    /// `<changefreq>` was added, removed or changed
+    Changefreq {
+        old: Option<ChangeFreq>,
+        new: Option<ChangeFreq>,
+    },
+
+    /// An `<image:image>` with this location was added
+    ImageAdded { loc: String },
+
+    /// An `<image:image>` with this location was removed
+    ImageRemoved { loc: String },
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn show<T: fmt::Display>(value: &Option<T>) -> String {
+            match value {
+                Some(v) => v.to_string(),
+                None => "(none)".to_string(),
+            }
+        }
+
+        match self {
+            FieldChange::Lastmod { old, new } => {
+                write!(f, "lastmod: {} -> {}", show(old), show(new))
+            }
+            FieldChange::Priority { old, new } => {
+                write!(f, "priority: {} -> {}", show(old), show(new))
+            }
+            FieldChange::Changefreq { old, new } => {
+                write!(f, "changefreq: {} -> {}", show(old), show(new))
+            }
+            FieldChange::ImageAdded { loc } => write!(f, "image added: {}", loc),
+            FieldChange::ImageRemoved { loc } => write!(f, "image removed: {}", loc),
+        }
+    }
+}
+
+/// A URL present in both versions whose metadata changed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UrlChange {
+    /// The URL of the page
+    pub loc: String,
+
+    /// Every field that differs, in document order
+    pub changes: Vec<FieldChange>,
+}
+
+/// The difference between an old and a new version of a sitemap
+///
+/// URLs are matched by `loc`. Added and modified URLs are listed in the order
+/// they appear in the new sitemap, removed URLs in the order of the old one.
+/// A `loc` listed more than once is reported once, for its first occurrence.
+///
+/// # Example
+/// ```
+/// use sitemap_generator::{SitemapDiff, UrlEntry};
+///
+/// let old = vec![
+///     UrlEntry::new("https://example.com/").lastmod("2025-11-01"),
+///     UrlEntry::new("https://example.com/old"),
+/// ];
+/// let new = vec![
+///     UrlEntry::new("https://example.com/").lastmod("2025-11-02"),
+///     UrlEntry::new("https://example.com/new"),
+/// ];
+///
+/// let diff = SitemapDiff::between(&old, &new);
+/// assert_eq!(diff.added, vec!["https://example.com/new"]);
+/// assert_eq!(diff.removed, vec!["https://example.com/old"]);
+/// assert_eq!(diff.modified.len(), 1);
+///
+/// let json = diff.to_json().unwrap();
+/// assert!(json.contains("\"field\": \"lastmod\""));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SitemapDiff {
+    /// URLs only present in the new sitemap
+    pub added: Vec<String>,
+
+    /// URLs only present in the old sitemap
+    pub removed: Vec<String>,
+
+    /// URLs present in both whose metadata changed
+    pub modified: Vec<UrlChange>,
+}
+
+impl SitemapDiff {
+    /// Diff two standard sitemaps
+    pub fn between(old: &[UrlEntry], new: &[UrlEntry]) -> Self {
+        Self::diff_views(
+            old.iter().map(|url| (url, &[][..])),
+            new.iter().map(|url| (url, &[][..])),
+        )
+    }
+
+    /// Diff two image sitemaps
+    pub fn between_images(old: &[UrlWithImages], new: &[UrlWithImages]) -> Self {
+        Self::diff_views(
+            old.iter().map(|e| (&e.url, e.images.as_slice())),
+            new.iter().map(|e| (&e.url, e.images.as_slice())),
+        )
+    }
+
+    /// Diff two combined sitemaps
+    ///
+    /// Only URL fields and images are compared; video and news metadata are ignored.
+    pub fn between_extensions(old: &[UrlWithExtensions], new: &[UrlWithExtensions]) -> Self {
+        Self::diff_views(
+            old.iter().map(|e| (&e.url, e.images.as_slice())),
+            new.iter().map(|e| (&e.url, e.images.as_slice())),
+        )
+    }
+
+    /// Check if the two versions are identical
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    /// Serialize the diff as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    fn diff_views<'a>(
+        old: impl Iterator<Item = (&'a UrlEntry, &'a [ImageEntry])>,
+        new: impl Iterator<Item = (&'a UrlEntry, &'a [ImageEntry])>,
+    ) -> Self {
+        let old: Vec<_> = old.collect();
+        let new: Vec<_> = new.collect();
+
+        let mut old_by_loc: HashMap<&str, (&UrlEntry, &[ImageEntry])> = HashMap::with_capacity(old.len());
+        for &(url, images) in &old {
+            old_by_loc.entry(url.loc.as_str()).or_insert((url, images));
+        }
+        let new_locs: HashSet<&str> = new.iter().map(|(url, _)| url.loc.as_str()).collect();
+
+        let mut diff = SitemapDiff::default();
+
+        let mut seen = HashSet::with_capacity(new.len());
+        for &(new_url, new_images) in &new {
+            if !seen.insert(new_url.loc.as_str()) {
+                continue;
+            }
+            match old_by_loc.get(new_url.loc.as_str()) {
+                None => diff.added.push(new_url.loc.clone()),
+                Some(&(old_url, old_images)) => {
+                    let changes = Self::field_changes(old_url, old_images, new_url, new_images);
+                    if !changes.is_empty() {
+                        diff.modified.push(UrlChange {
+                            loc: new_url.loc.clone(),
+                            changes,
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut seen = HashSet::with_capacity(old.len());
+        for (old_url, _) in &old {
+            if !new_locs.contains(old_url.loc.as_str()) && seen.insert(old_url.loc.as_str()) {
+                diff.removed.push(old_url.loc.clone());
+            }
+        }
+
+        diff
+    }
+
+    fn field_changes(
+        old: &UrlEntry,
+        old_images: &[ImageEntry],
+        new: &UrlEntry,
+        new_images: &[ImageEntry],
+    ) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+
+        if old.lastmod != new.lastmod {
+            changes.push(FieldChange::Lastmod {
+                old: old.lastmod.clone(),
+                new: new.lastmod.clone(),
+            });
+        }
+
+        if old.changefreq != new.changefreq {
+            changes.push(FieldChange::Changefreq {
+                old: old.changefreq,
+                new: new.changefreq,
+            });
+        }
+
+        if old.priority != new.priority {
+            changes.push(FieldChange::Priority {
+                old: old.priority,
+                new: new.priority,
+            });
+        }
+
+        let old_image_locs: HashSet<&str> = old_images.iter().map(|i| i.loc.as_str()).collect();
+        let new_image_locs: HashSet<&str> = new_images.iter().map(|i| i.loc.as_str()).collect();
+
+        for image in new_images {
+            if !old_image_locs.contains(image.loc.as_str()) {
+                changes.push(FieldChange::ImageAdded {
+                    loc: image.loc.clone(),
+                });
+            }
+        }
+
+        for image in old_images {
+            if !new_image_locs.contains(image.loc.as_str()) {
+                changes.push(FieldChange::ImageRemoved {
+                    loc: image.loc.clone(),
+                });
+            }
+        }
+
+        changes
+    }
+}
+
+impl fmt::Display for SitemapDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for loc in &self.added {
+            writeln!(f, "+ {}", loc)?;
+        }
+        for loc in &self.removed {
+            writeln!(f, "- {}", loc)?;
+        }
+        for url in &self.modified {
+            writeln!(f, "~ {}", url.loc)?;
+            for change in &url.changes {
+                writeln!(f, "    {}", change)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_identical() {
+        let urls = vec![
+            UrlEntry::new("https://example.com/").priority(1.0),
+            UrlEntry::new("https://example.com/about"),
+        ];
+
+        let diff = SitemapDiff::between(&urls, &urls);
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "");
+    }
+
+    #[test]
+    fn test_diff_url_fields() {
+        let old = vec![UrlEntry::new("https://example.com/")
+            .lastmod("2025-11-01")
+            .changefreq(ChangeFreq::Daily)
+            .priority(0.5)];
+        let new = vec![UrlEntry::new("https://example.com/")
+            .lastmod("2025-11-02")
+            .changefreq(ChangeFreq::Daily)];
+
+        let diff = SitemapDiff::between(&old, &new);
+        assert_eq!(diff.modified.len(), 1);
+        assert_eq!(
+            diff.modified[0].changes,
+            vec![
+                FieldChange::Lastmod {
+                    old: Some("2025-11-01".to_string()),
+                    new: Some("2025-11-02".to_string()),
+                },
+                FieldChange::Priority {
+                    old: Some(0.5),
+                    new: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_images() {
+        let old = vec![UrlWithImages::new(UrlEntry::new("https://example.com/gallery"))
+            .add_image(ImageEntry::new("https://example.com/a.jpg"))
+            .add_image(ImageEntry::new("https://example.com/b.jpg"))];
+        let new = vec![UrlWithImages::new(UrlEntry::new("https://example.com/gallery"))
+            .add_image(ImageEntry::new("https://example.com/b.jpg"))
+            .add_image(ImageEntry::new("https://example.com/c.jpg"))];
+
+        let diff = SitemapDiff::between_images(&old, &new);
+        assert_eq!(
+            diff.modified[0].changes,
+            vec![
+                FieldChange::ImageAdded {
+                    loc: "https://example.com/c.jpg".to_string()
+                },
+                FieldChange::ImageRemoved {
+                    loc: "https://example.com/a.jpg".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_duplicate_locs() {
+        let old = vec![UrlEntry::new("https://example.com/gone"), UrlEntry::new("https://example.com/gone")];
+        let new = vec![
+            UrlEntry::new("https://example.com/new"),
+            UrlEntry::new("https://example.com/new").priority(0.5),
+        ];
+
+        let diff = SitemapDiff::between(&old, &new);
+        assert_eq!(diff.added, vec!["https://example.com/new"]);
+        assert_eq!(diff.removed, vec!["https://example.com/gone"]);
+    }
+
+    #[test]
+    fn test_diff_text_output() {
+        let old = vec![UrlEntry::new("https://example.com/old")];
+        let new = vec![UrlEntry::new("https://example.com/new")];
+
+        let diff = SitemapDiff::between(&old, &new);
+        assert_eq!(
+            diff.to_string(),
+            "+ https://example.com/new\n- https://example.com/old\n"
+        );
+    }
+}
@@ -1,62 +1,235 @@
 //! Error types for sitemap generation
 
+use crate::scope::ScopeViolation;
 use std::fmt;
 use std::io;
+use std::path::{Path, PathBuf};
 
 /// Result type alias for sitemap operations
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// Error types that can occur during sitemap generation
+///
+/// Each variant has a stable [`code`](Error::code) that is safe to log or
+/// return from an API. New variants may be added without a major release.
+///
+/// # Example
+/// ```
+/// use sitemap_generator::{Error, SitemapBuilder, UrlEntry};
+///
+/// let mut builder = SitemapBuilder::new();
+/// builder.add_url(UrlEntry::new("https://example.com/"));
+/// builder.add_url(UrlEntry::new("https://example.com/a").lastmod("yesterday"));
+///
+/// let err = builder.build().unwrap_err();
+/// assert_eq!(err.code(), "invalid_date");
+/// match err {
+///     Error::Entry { index, source, .. } => {
+///         assert_eq!(index, 1);
+///         assert!(matches!(*source, Error::InvalidDate { field: "lastmod", .. }));
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// Invalid URL format
-    InvalidUrl(String),
+    InvalidUrl { url: String },
 
     /// URL exceeds maximum length (2048 characters)
-    UrlTooLong(String),
+    UrlTooLong { url: String, actual: usize, limit: usize },
 
-    /// Too many URLs in sitemap (>50,000)
-    TooManyUrls(usize),
+    /// Too many URLs in sitemap (>50,000, or >1,000 for news)
+    TooManyUrls { actual: usize, limit: usize },
 
     /// Sitemap size exceeds maximum (50MB uncompressed)
-    SizeExceeded(usize),
+    SizeExceeded { actual: usize, limit: usize },
 
     /// Invalid date format (must be W3C Datetime format)
-    InvalidDate(String),
+    InvalidDate { field: &'static str, value: String },
 
     /// Invalid priority value (must be 0.0-1.0)
-    InvalidPriority(f32),
+    InvalidPriority { value: f32 },
 
     /// Invalid changefreq value
-    InvalidChangeFreq(String),
+    InvalidChangeFreq { value: String },
+
+    /// Field value that breaks a protocol rule
+    InvalidField {
+        field: &'static str,
+        value: String,
+        reason: &'static str,
+    },
+
+    /// Field longer than allowed
+    FieldTooLong {
+        field: &'static str,
+        actual: usize,
+        limit: usize,
+    },
+
+    /// Field with more values than allowed
+    TooManyValues {
+        field: &'static str,
+        actual: usize,
+        limit: usize,
+    },
+
+    /// Text containing a character that XML does not allow
+    InvalidXmlChar {
+        field: &'static str,
+        character: char,
+        value: String,
+    },
+
+    /// Extension element in a namespace with no registered prefix
+    UnknownNamespace { namespace: String },
+
+    /// Validation of an entry failed
+    Entry {
+        /// Position of the entry in the builder
+        index: usize,
+        /// Page URL of the entry
+        url: String,
+        source: Box<Error>,
+    },
+
+    /// URL outside the scope of the sitemap's location
+    OutOfScope(ScopeViolation),
 
     /// IO error during file operations
-    Io(io::Error),
+    Io { path: Option<PathBuf>, source: io::Error },
 
     /// XML writing/parsing error
-    Xml(String),
+    Xml(quick_xml::Error),
+
+    /// Compression or decompression error
+    Compression { path: Option<PathBuf>, source: io::Error },
+
+    /// JSON serialization/deserialization error
+    Json(serde_json::Error),
+
+    /// Parsed document exceeds the decompressed size limit (in bytes)
+    ///
+    /// Parsing stops as soon as the limit is passed, so `actual` is the number of
+    /// bytes read by then rather than the full document size.
+    DocumentTooLarge { actual: usize, limit: usize },
 
-    /// Compression error
-    Compression(String),
+    /// Parsed document has more entries than the limit
+    ///
+    /// Parsing stops at the first entry over the limit, so `actual` is `limit + 1`.
+    TooManyEntries { actual: usize, limit: usize },
+
+    /// Text of a parsed element exceeds the length limit (in bytes)
+    TextTooLong {
+        element: String,
+        actual: usize,
+        limit: usize,
+    },
+
+    /// Parsed document contains a DOCTYPE declaration
+    DoctypeNotAllowed,
+}
+
+impl Error {
+    /// Stable, machine-readable code of the error
+    ///
+    /// For [`Error::Entry`] this is the code of the underlying error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::InvalidUrl { .. } => "invalid_url",
+            Error::UrlTooLong { .. } => "url_too_long",
+            Error::TooManyUrls { .. } => "too_many_urls",
+            Error::SizeExceeded { .. } => "size_exceeded",
+            Error::InvalidDate { .. } => "invalid_date",
+            Error::InvalidPriority { .. } => "invalid_priority",
+            Error::InvalidChangeFreq { .. } => "invalid_changefreq",
+            Error::InvalidField { .. } => "invalid_field",
+            Error::FieldTooLong { .. } => "field_too_long",
+            Error::TooManyValues { .. } => "too_many_values",
+            Error::InvalidXmlChar { .. } => "invalid_xml_char",
+            Error::UnknownNamespace { .. } => "unknown_namespace",
+            Error::Entry { source, .. } => source.code(),
+            Error::OutOfScope(_) => "out_of_scope",
+            Error::Io { .. } => "io",
+            Error::Xml(_) => "xml",
+            Error::Compression { .. } => "compression",
+            Error::Json(_) => "json",
+            Error::DocumentTooLarge { .. } => "document_too_large",
+            Error::TooManyEntries { .. } => "too_many_entries",
+            Error::TextTooLong { .. } => "text_too_long",
+            Error::DoctypeNotAllowed => "doctype_not_allowed",
+        }
+    }
+
+    /// Attach the index and page URL of the entry that failed
+    pub(crate) fn in_entry(self, index: usize, url: &str) -> Self {
+        Error::Entry {
+            index,
+            url: url.to_string(),
+            source: Box::new(self),
+        }
+    }
 
-    /// Validation error
-    Validation(String),
+    /// Wrap a compression or decompression failure
+    pub(crate) fn compression(path: Option<&Path>, source: io::Error) -> Self {
+        Error::Compression {
+            path: path.map(Path::to_path_buf),
+            source,
+        }
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Error::InvalidUrl(url) => write!(f, "Invalid URL: {}", url),
-            Error::UrlTooLong(url) => write!(f, "URL too long (max 2048 chars): {}", url),
-            Error::TooManyUrls(count) => write!(f, "Too many URLs in sitemap (max 50,000): {}", count),
-            Error::SizeExceeded(size) => write!(f, "Sitemap size exceeds 50MB: {} bytes", size),
-            Error::InvalidDate(date) => write!(f, "Invalid date format: {}", date),
-            Error::InvalidPriority(priority) => write!(f, "Invalid priority (must be 0.0-1.0): {}", priority),
-            Error::InvalidChangeFreq(freq) => write!(f, "Invalid changefreq: {}", freq),
-            Error::Io(err) => write!(f, "IO error: {}", err),
-            Error::Xml(msg) => write!(f, "XML error: {}", msg),
-            Error::Compression(msg) => write!(f, "Compression error: {}", msg),
-            Error::Validation(msg) => write!(f, "Validation error: {}", msg),
+            Error::InvalidUrl { url } => write!(f, "Invalid URL: {}", url),
+            Error::UrlTooLong { url, actual, limit } => {
+                write!(f, "URL too long ({} chars, max {}): {}", actual, limit, url)
+            }
+            Error::TooManyUrls { actual, limit } => write!(f, "Too many URLs in sitemap (max {}): {}", limit, actual),
+            Error::SizeExceeded { actual, limit } => {
+                write!(f, "Sitemap size exceeds {} bytes: {} bytes", limit, actual)
+            }
+            Error::InvalidDate { field, value } => write!(f, "Invalid date format in <{}>: {}", field, value),
+            Error::InvalidPriority { value } => write!(f, "Invalid priority (must be 0.0-1.0): {}", value),
+            Error::InvalidChangeFreq { value } => write!(f, "Invalid changefreq: {}", value),
+            Error::InvalidField { field, value, reason } => write!(f, "Invalid <{}> ({}): {}", field, reason, value),
+            Error::FieldTooLong { field, actual, limit } => {
+                write!(f, "<{}> exceeds {} characters: {}", field, limit, actual)
+            }
+            Error::TooManyValues { field, actual, limit } => {
+                write!(f, "Too many <{}> values (max {}): {}", field, limit, actual)
+            }
+            Error::InvalidXmlChar { field, character, value } => write!(
+                f,
+                "Character U+{:04X} is not allowed in XML, in <{}>: {:?}",
+                *character as u32, field, value
+            ),
+            Error::UnknownNamespace { namespace } => {
+                write!(f, "No prefix registered for extension namespace: {}", namespace)
+            }
+            Error::Entry { index, url, source } => write!(f, "Entry {} ({}): {}", index, url, source),
+            Error::OutOfScope(violation) => write!(f, "URL out of sitemap scope: {}", violation),
+            Error::Io { path: Some(path), source } => write!(f, "IO error on {}: {}", path.display(), source),
+            Error::Io { path: None, source } => write!(f, "IO error: {}", source),
+            Error::Xml(err) => write!(f, "XML error: {}", err),
+            Error::Compression { path: Some(path), source } => {
+                write!(f, "Compression error on {}: {}", path.display(), source)
+            }
+            Error::Compression { path: None, source } => write!(f, "Compression error: {}", source),
+            Error::Json(err) => write!(f, "JSON error: {}", err),
+            Error::DocumentTooLarge { actual, limit } => {
+                write!(f, "Document exceeds {} bytes: read {} bytes", limit, actual)
+            }
+            Error::TooManyEntries { actual, limit } => {
+                write!(f, "Document has more than {} entries: reached entry {}", limit, actual)
+            }
+            Error::TextTooLong { element, actual, limit } => {
+                write!(f, "Text of <{}> is {} bytes, the limit is {}", element, actual, limit)
+            }
+            Error::DoctypeNotAllowed => write!(f, "DOCTYPE declarations are not allowed"),
         }
     }
 }
@@ -64,7 +237,10 @@ impl fmt::Display for Error {
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            Error::Io(err) => Some(err),
+            Error::Entry { source, .. } => Some(source.as_ref()),
+            Error::Io { source, .. } | Error::Compression { source, .. } => Some(source),
+            Error::Xml(err) => Some(err),
+            Error::Json(err) => Some(err),
             _ => None,
         }
     }
@@ -72,12 +248,61 @@ impl std::error::Error for Error {
 
 impl From<io::Error> for Error {
     fn from(err: io::Error) -> Self {
-        Error::Io(err)
+        Error::Io { path: None, source: err }
     }
 }
 
 impl From<quick_xml::Error> for Error {
     fn from(err: quick_xml::Error) -> Self {
-        Error::Xml(err.to_string())
+        Error::Xml(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
+
+/// Attach a file path to IO errors
+pub(crate) trait IoContext<T> {
+    fn with_path(self, path: impl AsRef<Path>) -> Result<T>;
+}
+
+impl<T> IoContext<T> for io::Result<T> {
+    fn with_path(self, path: impl AsRef<Path>) -> Result<T> {
+        self.map_err(|source| Error::Io {
+            path: Some(path.as_ref().to_path_buf()),
+            source,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_source_chain() {
+        let io = io::Error::new(io::ErrorKind::UnexpectedEof, "truncated");
+        let err = Error::compression(Some(Path::new("sitemap.xml.gz")), io).in_entry(3, "https://example.com/");
+
+        assert_eq!(err.code(), "compression");
+        assert_eq!(
+            err.to_string(),
+            "Entry 3 (https://example.com/): Compression error on sitemap.xml.gz: truncated"
+        );
+
+        let source = err.source().unwrap();
+        assert!(source.to_string().starts_with("Compression error"));
+        assert_eq!(source.source().unwrap().to_string(), "truncated");
+    }
+
+    #[test]
+    fn test_with_path() {
+        let err = std::fs::read("/nonexistent/sitemap.xml").with_path("/nonexistent/sitemap.xml").unwrap_err();
+        assert!(matches!(err, Error::Io { path: Some(ref p), .. } if p == Path::new("/nonexistent/sitemap.xml")));
+        assert_eq!(err.code(), "io");
     }
 }
@@ -0,0 +1,150 @@
+//! Custom namespaced elements on URL entries
+//!
+//! For markup the crate does not model, such as PageMaps or vendor
+//! extensions, attach [`ExtensionElement`] trees to a [`UrlEntry`](crate::UrlEntry)
+//! and register the namespace prefix on the builder. The namespace is only
+//! declared in the output when an entry uses it, and the parser keeps unknown
+//! namespaced elements so they survive a round trip.
+//!
+//! # Example
+//! ```
+//! use sitemap_generator::{ExtensionElement, SitemapBuilder, UrlEntry};
+//!
+//! const PAGEMAP: &str = "http://www.google.com/schemas/sitemap-pagemap/1.0";
+//!
+//! let pagemap = ExtensionElement::new(PAGEMAP, "PageMap").child(
+//!     ExtensionElement::new(PAGEMAP, "DataObject")
+//!         .attribute("type", "document")
+//!         .child(ExtensionElement::new(PAGEMAP, "Attribute").attribute("name", "author").text("Jane Doe")),
+//! );
+//!
+//! let mut builder = SitemapBuilder::new().namespace("pagemap", PAGEMAP);
+//! builder.add_url(UrlEntry::new("https://example.com/").add_extension(pagemap));
+//!
+//! let xml = builder.build().unwrap();
+//! assert!(xml.contains(r#"xmlns:pagemap="http://www.google.com/schemas/sitemap-pagemap/1.0""#));
+//! assert!(xml.contains(r#"<pagemap:Attribute name="author">Jane Doe</pagemap:Attribute>"#));
+//! ```
+
+use crate::error::{Error, Result};
+use crate::writer::{IMAGE_NS, NEWS_NS, SITEMAP_NS, VIDEO_NS};
+use serde::{Deserialize, Serialize};
+
+/// Prefixes used by the writer or reserved by XML
+const RESERVED_PREFIXES: &[&str] = &["image", "video", "news", "xml", "xmlns"];
+
+/// An element in a custom namespace, with attributes, text and child elements
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtensionElement {
+    /// Namespace URI of the element
+    pub namespace: String,
+
+    /// Local name of the element, without a prefix
+    pub name: String,
+
+    /// Unprefixed attributes, in order
+    pub attributes: Vec<(String, String)>,
+
+    /// Text content
+    pub text: Option<String>,
+
+    /// Child elements, in order
+    pub children: Vec<ExtensionElement>,
+}
+
+impl ExtensionElement {
+    /// Create an empty element
+    pub fn new(namespace: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            namespace: namespace.into(),
+            name: name.into(),
+            attributes: Vec::new(),
+            text: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Add an attribute
+    pub fn attribute(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set the text content
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    /// Add a child element
+    pub fn child(mut self, child: ExtensionElement) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Namespace URIs used by this element and its descendants
+    pub(crate) fn collect_namespaces<'a>(&'a self, namespaces: &mut Vec<&'a str>) {
+        if !namespaces.contains(&self.namespace.as_str()) {
+            namespaces.push(&self.namespace);
+        }
+        for child in &self.children {
+            child.collect_namespaces(namespaces);
+        }
+    }
+}
+
+/// Check for a name usable as an unprefixed element or attribute name
+pub(crate) fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Check whether a namespace URI is one the crate writes itself
+pub(crate) fn is_builtin_namespace(uri: &str) -> bool {
+    [SITEMAP_NS, IMAGE_NS, VIDEO_NS, NEWS_NS].contains(&uri)
+}
+
+/// Check a prefix and URI registered for extension elements
+pub(crate) fn validate_namespace(prefix: &str, uri: &str) -> Result<()> {
+    let reason = if !is_xml_name(prefix) {
+        "prefix must be a valid XML name"
+    } else if RESERVED_PREFIXES.contains(&prefix.to_ascii_lowercase().as_str()) {
+        "prefix is reserved"
+    } else if uri.is_empty() || is_builtin_namespace(uri) {
+        "namespace must be a custom, non-empty URI"
+    } else {
+        return Ok(());
+    };
+    Err(Error::InvalidField {
+        field: "xmlns",
+        value: format!("{}={}", prefix, uri),
+        reason,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_and_namespaces() {
+        assert!(is_xml_name("PageMap"));
+        assert!(is_xml_name("data-object"));
+        assert!(!is_xml_name("pagemap:PageMap"));
+        assert!(!is_xml_name("1st"));
+        assert!(!is_xml_name(""));
+
+        assert!(validate_namespace("pagemap", "http://www.google.com/schemas/sitemap-pagemap/1.0").is_ok());
+        assert!(validate_namespace("image", "urn:example").is_err());
+        assert!(validate_namespace("XMLNS", "urn:example").is_err());
+        assert!(validate_namespace("acme", IMAGE_NS).is_err());
+        assert!(validate_namespace("acme", "").is_err());
+
+        let element = ExtensionElement::new("urn:a", "root")
+            .child(ExtensionElement::new("urn:b", "child").child(ExtensionElement::new("urn:a", "leaf")));
+        let mut namespaces = Vec::new();
+        element.collect_namespaces(&mut namespaces);
+        assert_eq!(namespaces, vec!["urn:a", "urn:b"]);
+    }
+}
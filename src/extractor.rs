@@ -0,0 +1,486 @@
+//! Extraction of image and video metadata from HTML pages
+
+use crate::error::{Error, Result};
+use crate::html::{self, Tag};
+use crate::types::*;
+use serde_json::Value;
+use std::collections::HashSet;
+use url::Url;
+
+/// Extractor that reads images and videos from a page's HTML
+///
+/// Images come from `<img src>`, `<img srcset>` / `<source srcset>` and
+/// `og:image`. Videos come from schema.org `VideoObject` JSON-LD, `og:video`
+/// and `<video>` elements. Relative URLs are resolved against the page URL.
+///
+/// # Example
+/// ```
+/// use sitemap_generator::{CombinedSitemapBuilder, HtmlExtractor, UrlEntry};
+///
+/// let html = r#"<html><head>
+///   <meta property="og:image" content="/images/cover.jpg">
+/// </head><body>
+///   <img src="photo.jpg" srcset="photo-2x.jpg 2x">
+/// </body></html>"#;
+///
+/// let url = HtmlExtractor::extract(html, UrlEntry::new("https://example.com/blog/post")).unwrap();
+/// assert_eq!(url.images.len(), 3);
+/// assert_eq!(url.images[0].loc, "https://example.com/images/cover.jpg");
+///
+/// let mut builder = CombinedSitemapBuilder::new();
+/// builder.add_url(url);
+/// ```
+pub struct HtmlExtractor;
+
+impl HtmlExtractor {
+    /// Extract images and videos for a page and attach them to its URL entry
+    pub fn extract(html: &str, url: UrlEntry) -> Result<UrlWithExtensions> {
+        let images = Self::extract_images(html, &url.loc)?;
+        let videos = Self::extract_videos(html, &url.loc)?;
+        Ok(UrlWithExtensions::new(url).add_images(images).add_videos(videos))
+    }
+
+    /// Extract the images referenced by a page, without duplicates
+    pub fn extract_images(html: &str, page_url: &str) -> Result<Vec<ImageEntry>> {
+        let base = Self::page_base(html, page_url)?;
+        let mut seen = HashSet::new();
+        let mut images = Vec::new();
+
+        let mut push = |src: &str| {
+            if let Some(loc) = resolve(&base, src) {
+                if seen.insert(loc.clone()) {
+                    images.push(ImageEntry::new(loc));
+                }
+            }
+        };
+
+        for tag in html::tags(html) {
+            match tag.name.as_str() {
+                "meta" if matches!(meta_property(&tag), Some("og:image" | "og:image:url")) => {
+                    if let Some(content) = tag.attr("content") {
+                        push(content);
+                    }
+                }
+                "img" | "source" => {
+                    if tag.name == "img" {
+                        if let Some(src) = tag.attr("src") {
+                            push(src);
+                        }
+                    }
+                    // <source> inside <video>/<audio> uses src, not srcset
+                    if let Some(srcset) = tag.attr("srcset") {
+                        for src in srcset_urls(srcset) {
+                            push(src);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(images)
+    }
+
+    /// Extract the videos embedded in a page, without duplicates
+    ///
+    /// Videos without a thumbnail are skipped, since `<video:thumbnail_loc>` is
+    /// required. Missing titles and descriptions fall back to the page's
+    /// `og:title`/`<title>` and `og:description`/`<meta name="description">`.
+    pub fn extract_videos(html: &str, page_url: &str) -> Result<Vec<VideoEntry>> {
+        let base = Self::page_base(html, page_url)?;
+        let page = PageMeta::read(html);
+
+        let mut videos = Vec::new();
+        let mut seen = HashSet::new();
+        let mut push = |video: VideoEntry| {
+            let key = video
+                .content_loc
+                .clone()
+                .or_else(|| video.player_loc.clone())
+                .unwrap_or_else(|| video.thumbnail_loc.clone());
+            if seen.insert(key) {
+                videos.push(video);
+            }
+        };
+
+        // schema.org VideoObject JSON-LD carries the richest metadata, so it goes first
+        for tag in html::tags(html) {
+            if tag.name == "script"
+                && tag
+                    .attr("type")
+                    .is_some_and(|t| t.trim().eq_ignore_ascii_case("application/ld+json"))
+            {
+                if let Ok(json) = serde_json::from_str::<Value>(html::inner_text(html, &tag)) {
+                    let mut objects = Vec::new();
+                    collect_video_objects(&json, &mut objects);
+                    for object in objects {
+                        if let Some(video) = video_from_json_ld(object, &base, &page) {
+                            push(video);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Open Graph
+        if let (Some(src), Some(thumbnail)) = (&page.og_video, &page.og_image) {
+            if let (Some(src), Some(thumbnail)) = (resolve(&base, src), resolve(&base, thumbnail)) {
+                let mut video = VideoEntry::new(thumbnail, page.title(), page.description());
+                if page.og_video_type.as_deref().is_some_and(|t| t.eq_ignore_ascii_case("text/html")) {
+                    video.player_loc = Some(src);
+                } else {
+                    video.content_loc = Some(src);
+                }
+                push(video);
+            }
+        }
+
+        // <video> elements
+        let tags: Vec<Tag> = html::tags(html).collect();
+        for (i, tag) in tags.iter().enumerate() {
+            if tag.name != "video" {
+                continue;
+            }
+
+            let src = tag.attr("src").map(str::to_string).or_else(|| {
+                tags[i + 1..]
+                    .iter()
+                    .take_while(|t| t.name == "source" || t.name == "track")
+                    .find(|t| t.name == "source")
+                    .and_then(|t| t.attr("src").map(str::to_string))
+            });
+
+            let thumbnail = tag.attr("poster").or(page.og_image.as_deref());
+
+            if let (Some(src), Some(thumbnail)) = (src, thumbnail) {
+                if let (Some(src), Some(thumbnail)) = (resolve(&base, &src), resolve(&base, thumbnail)) {
+                    let title = tag.attr("title").map(str::to_string).unwrap_or_else(|| page.title());
+                    push(VideoEntry::new(thumbnail, title, page.description()).content_loc(src));
+                }
+            }
+        }
+
+        Ok(videos)
+    }
+
+    /// Resolve the base URL of a page, honoring `<base href>`
+    fn page_base(html: &str, page_url: &str) -> Result<Url> {
+        let page = Url::parse(page_url).map_err(|_| Error::InvalidUrl {
+            url: page_url.to_string(),
+        })?;
+        let base = html::tags(html)
+            .find(|tag| tag.name == "base")
+            .and_then(|tag| tag.attr("href").and_then(|href| page.join(href.trim()).ok()));
+        Ok(base.unwrap_or(page))
+    }
+}
+
+/// Page-level metadata used to fill in missing video fields
+#[derive(Debug, Default)]
+struct PageMeta {
+    title: Option<String>,
+    og_title: Option<String>,
+    description: Option<String>,
+    og_description: Option<String>,
+    og_image: Option<String>,
+    og_video: Option<String>,
+    og_video_type: Option<String>,
+}
+
+impl PageMeta {
+    fn read(html: &str) -> Self {
+        let mut meta = PageMeta::default();
+
+        for tag in html::tags(html) {
+            match tag.name.as_str() {
+                "title" if meta.title.is_none() => {
+                    let text = html::decode_entities(html::inner_text(html, &tag).trim());
+                    if !text.is_empty() {
+                        meta.title = Some(text);
+                    }
+                }
+                "meta" => {
+                    let content = match tag.attr("content") {
+                        Some(content) => content.trim().to_string(),
+                        None => continue,
+                    };
+                    let slot = match meta_property(&tag) {
+                        Some("og:title") => &mut meta.og_title,
+                        Some("og:description") => &mut meta.og_description,
+                        Some("og:image" | "og:image:url") => &mut meta.og_image,
+                        Some("og:video" | "og:video:url" | "og:video:secure_url") => &mut meta.og_video,
+                        Some("og:video:type") => &mut meta.og_video_type,
+                        Some("description") => &mut meta.description,
+                        _ => continue,
+                    };
+                    if slot.is_none() {
+                        *slot = Some(content);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        meta
+    }
+
+    fn title(&self) -> String {
+        self.og_title.clone().or_else(|| self.title.clone()).unwrap_or_default()
+    }
+
+    fn description(&self) -> String {
+        self.og_description
+            .clone()
+            .or_else(|| self.description.clone())
+            .unwrap_or_else(|| self.title())
+    }
+}
+
+/// The `property` (Open Graph) or `name` of a `<meta>` tag
+fn meta_property(tag: &Tag) -> Option<&str> {
+    tag.attr("property").or_else(|| tag.attr("name"))
+}
+
+/// Read the candidate URLs of a `srcset` attribute
+///
+/// Follows the HTML parsing rules: a URL runs up to whitespace, so it may
+/// contain commas (as image CDN transformations often do); trailing commas end
+/// a candidate without descriptors, otherwise the descriptors run up to the
+/// next comma outside parentheses.
+fn srcset_urls(srcset: &str) -> Vec<&str> {
+    let mut urls = Vec::new();
+    let mut rest = srcset;
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+        if rest.is_empty() {
+            return urls;
+        }
+
+        let end = rest.find(|c: char| c.is_ascii_whitespace()).unwrap_or(rest.len());
+        let (candidate, after) = rest.split_at(end);
+        let url = candidate.trim_end_matches(',');
+        rest = after;
+
+        if url.len() == candidate.len() {
+            let mut depth = 0usize;
+            let descriptors_end = rest
+                .char_indices()
+                .find(|&(_, c)| match c {
+                    '(' => {
+                        depth += 1;
+                        false
+                    }
+                    ')' => {
+                        depth = depth.saturating_sub(1);
+                        false
+                    }
+                    ',' => depth == 0,
+                    _ => false,
+                })
+                .map_or(rest.len(), |(i, _)| i + 1);
+            rest = &rest[descriptors_end..];
+        }
+
+        if !url.is_empty() {
+            urls.push(url);
+        }
+    }
+}
+
+/// Resolve a possibly relative URL, keeping only http(s) results
+fn resolve(base: &Url, href: &str) -> Option<String> {
+    let href = href.trim();
+    if href.is_empty() {
+        return None;
+    }
+    base.join(href)
+        .ok()
+        .filter(|url| url.scheme() == "http" || url.scheme() == "https")
+        .map(String::from)
+}
+
+/// Find every object whose `@type` is (or includes) `VideoObject`
+fn collect_video_objects<'a>(value: &'a Value, out: &mut Vec<&'a Value>) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                collect_video_objects(item, out);
+            }
+        }
+        Value::Object(map) => {
+            let is_video = match map.get("@type") {
+                Some(Value::String(t)) => t == "VideoObject",
+                Some(Value::Array(types)) => types.iter().any(|t| t == "VideoObject"),
+                _ => false,
+            };
+            if is_video {
+                out.push(value);
+            }
+            for (key, child) in map {
+                if key != "@type" {
+                    collect_video_objects(child, out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Build a video entry from a schema.org `VideoObject`
+fn video_from_json_ld(object: &Value, base: &Url, page: &PageMeta) -> Option<VideoEntry> {
+    let text = |key: &str| object.get(key).and_then(Value::as_str).map(str::to_string);
+    let url = |key: &str| object.get(key).and_then(first_url).and_then(|u| resolve(base, u));
+
+    let thumbnail = url("thumbnailUrl")
+        .or_else(|| url("thumbnail"))
+        .or_else(|| page.og_image.as_deref().and_then(|u| resolve(base, u)))?;
+    let title = text("name").unwrap_or_else(|| page.title());
+    let description = text("description").unwrap_or_else(|| page.description());
+
+    let mut video = VideoEntry::new(thumbnail, title, description);
+    video.content_loc = url("contentUrl");
+    // Only one location is allowed; the file URL is the more useful one
+    if video.content_loc.is_none() {
+        video.player_loc = url("embedUrl");
+    }
+    video.duration = text("duration").as_deref().and_then(parse_iso8601_duration);
+    video.publication_date = text("uploadDate");
+    video.expiration_date = text("expires");
+    video.family_friendly = object.get("isFamilyFriendly").and_then(|v| match v {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) => Some(s.eq_ignore_ascii_case("true")),
+        _ => None,
+    });
+
+    if video.content_loc.is_none() && video.player_loc.is_none() {
+        return None;
+    }
+
+    Some(video)
+}
+
+/// Read a URL from a string, an array of strings, or an `ImageObject`
+fn first_url(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(s) => Some(s),
+        Value::Array(items) => items.iter().find_map(first_url),
+        Value::Object(map) => map.get("url").or_else(|| map.get("contentUrl")).and_then(first_url),
+        _ => None,
+    }
+}
+
+/// Parse an ISO 8601 duration such as `PT1H2M30S` into seconds
+fn parse_iso8601_duration(text: &str) -> Option<u32> {
+    let rest = text.trim().strip_prefix('P')?;
+    let mut seconds: f64 = 0.0;
+    let mut number = String::new();
+    let mut in_time = false;
+
+    for ch in rest.chars() {
+        match ch {
+            '0'..='9' | '.' => number.push(ch),
+            'T' => in_time = true,
+            _ => {
+                let value: f64 = number.parse().ok()?;
+                number.clear();
+                seconds += value
+                    * match (ch, in_time) {
+                        ('W', false) => 604_800.0,
+                        ('D', false) => 86_400.0,
+                        ('H', true) => 3_600.0,
+                        ('M', true) => 60.0,
+                        ('S', true) => 1.0,
+                        _ => return None,
+                    };
+            }
+        }
+    }
+
+    if !number.is_empty() {
+        return None;
+    }
+
+    Some(seconds.round() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_iso8601_duration() {
+        assert_eq!(parse_iso8601_duration("PT1M30S"), Some(90));
+        assert_eq!(parse_iso8601_duration("PT1H"), Some(3600));
+        assert_eq!(parse_iso8601_duration("P1DT1S"), Some(86_401));
+        assert_eq!(parse_iso8601_duration("1M"), None);
+        assert_eq!(parse_iso8601_duration("PT5"), None);
+    }
+
+    #[test]
+    fn test_extract_images() {
+        let html = r#"<base href="/assets/">
+<img src="a.jpg" srcset="a-1x.jpg 1x, a-2x.jpg 2x">
+<picture><source srcset="https://cdn.example.com/b.webp"><img src="a.jpg"></picture>
+<img src="data:image/png;base64,AAAA">"#;
+
+        let images = HtmlExtractor::extract_images(html, "https://example.com/page").unwrap();
+        let locs: Vec<&str> = images.iter().map(|i| i.loc.as_str()).collect();
+        assert_eq!(
+            locs,
+            vec![
+                "https://example.com/assets/a.jpg",
+                "https://example.com/assets/a-1x.jpg",
+                "https://example.com/assets/a-2x.jpg",
+                "https://cdn.example.com/b.webp",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_srcset_urls() {
+        assert_eq!(srcset_urls("a-1x.jpg 1x, a-2x.jpg 2x"), vec!["a-1x.jpg", "a-2x.jpg"]);
+        assert_eq!(
+            srcset_urls("https://cdn.example.com/c_fill,w_400/a.jpg 400w,https://cdn.example.com/c_fill,w_800/a.jpg 800w"),
+            vec!["https://cdn.example.com/c_fill,w_400/a.jpg", "https://cdn.example.com/c_fill,w_800/a.jpg"]
+        );
+        assert_eq!(srcset_urls("a.jpg, b.jpg,, c.jpg"), vec!["a.jpg", "b.jpg", "c.jpg"]);
+        assert_eq!(srcset_urls("  a.jpg 1x (odd, descriptor), b.jpg"), vec!["a.jpg", "b.jpg"]);
+        assert!(srcset_urls(" , ").is_empty());
+    }
+
+    #[test]
+    fn test_extract_videos() {
+        let html = r#"<html><head>
+<title>Launch video</title>
+<meta property="og:image" content="/og.jpg">
+<script type="application/ld+json">
+{"@context": "https://schema.org", "@graph": [{
+  "@type": "VideoObject",
+  "name": "Product launch",
+  "description": "Watch the launch",
+  "thumbnailUrl": ["/thumb.jpg"],
+  "contentUrl": "/launch.mp4",
+  "duration": "PT2M",
+  "uploadDate": "2025-11-01"
+}]}
+</script>
+</head><body>
+<video src="/launch.mp4" poster="/poster.jpg"></video>
+<video poster="/clip.jpg"><source src="clip.webm" type="video/webm"></video>
+</body></html>"#;
+
+        let videos = HtmlExtractor::extract_videos(html, "https://example.com/videos/").unwrap();
+        assert_eq!(videos.len(), 2);
+
+        assert_eq!(videos[0].title, "Product launch");
+        assert_eq!(videos[0].thumbnail_loc, "https://example.com/thumb.jpg");
+        assert_eq!(videos[0].content_loc.as_deref(), Some("https://example.com/launch.mp4"));
+        assert_eq!(videos[0].duration, Some(120));
+        assert_eq!(videos[0].publication_date.as_deref(), Some("2025-11-01"));
+
+        assert_eq!(videos[1].title, "Launch video");
+        assert_eq!(videos[1].thumbnail_loc, "https://example.com/clip.jpg");
+        assert_eq!(videos[1].content_loc.as_deref(), Some("https://example.com/videos/clip.webm"));
+    }
+}
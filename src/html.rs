@@ -0,0 +1,239 @@
+//! Minimal HTML tag scanner used by the directory scanner and HTML extractor
+//!
+//! This is not a full HTML parser. It walks start tags and their attributes,
+//! skipping comments, doctypes and the contents of `<script>`/`<style>`, which
+//! is all that is needed to read links, images and metadata from a page.
+
+/// A start (or self-closing) tag found in an HTML document
+#[derive(Debug, Clone)]
+pub(crate) struct Tag {
+    /// Lowercase tag name
+    pub name: String,
+
+    /// Attributes with lowercase names and decoded values, in document order
+    pub attrs: Vec<(String, String)>,
+
+    /// Byte offset just after the closing `>` of the tag
+    pub end: usize,
+}
+
+impl Tag {
+    /// Get the value of an attribute by (lowercase) name
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Iterate over all start tags in a document
+pub(crate) fn tags(html: &str) -> Tags<'_> {
+    Tags { html, pos: 0 }
+}
+
+/// Get the raw text content of an element such as `<script>` or `<title>`
+pub(crate) fn inner_text<'a>(html: &'a str, tag: &Tag) -> &'a str {
+    let rest = &html[tag.end..];
+    let close = format!("</{}", tag.name);
+    match find_ignore_case(rest, &close) {
+        Some(idx) => &rest[..idx],
+        None => rest,
+    }
+}
+
+/// Decode the character references that commonly appear in attribute values
+pub(crate) fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let decoded = rest.find(';').filter(|&semi| semi <= 10).and_then(|semi| {
+            let entity = &rest[1..semi];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ if entity.starts_with("#x") || entity.starts_with("#X") => {
+                    u32::from_str_radix(&entity[2..], 16).ok().and_then(char::from_u32)
+                }
+                _ if entity.starts_with('#') => entity[1..].parse().ok().and_then(char::from_u32),
+                _ => None,
+            };
+            ch.map(|c| (c, semi))
+        });
+
+        match decoded {
+            Some((ch, semi)) => {
+                out.push(ch);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    let needle = needle.as_bytes();
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle))
+}
+
+/// Iterator over the start tags of a document
+pub(crate) struct Tags<'a> {
+    html: &'a str,
+    pos: usize,
+}
+
+impl Iterator for Tags<'_> {
+    type Item = Tag;
+
+    fn next(&mut self) -> Option<Tag> {
+        let bytes = self.html.as_bytes();
+
+        loop {
+            let lt = self.html[self.pos..].find('<')? + self.pos;
+            let rest = &self.html[lt..];
+
+            if rest.starts_with("<!--") {
+                self.pos = match rest.find("-->") {
+                    Some(idx) => lt + idx + 3,
+                    None => self.html.len(),
+                };
+                continue;
+            }
+
+            // Doctypes, processing instructions and end tags
+            if rest.starts_with("<!") || rest.starts_with("<?") || rest.starts_with("</") {
+                self.pos = match rest.find('>') {
+                    Some(idx) => lt + idx + 1,
+                    None => self.html.len(),
+                };
+                continue;
+            }
+
+            let mut i = lt + 1;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'-') {
+                i += 1;
+            }
+            if i == lt + 1 {
+                self.pos = lt + 1;
+                continue;
+            }
+            let name = self.html[lt + 1..i].to_ascii_lowercase();
+
+            let mut attrs = Vec::new();
+            loop {
+                while i < bytes.len() && (bytes[i].is_ascii_whitespace() || bytes[i] == b'/') {
+                    i += 1;
+                }
+                if i >= bytes.len() {
+                    break;
+                }
+                if bytes[i] == b'>' {
+                    i += 1;
+                    break;
+                }
+
+                let name_start = i;
+                while i < bytes.len()
+                    && !bytes[i].is_ascii_whitespace()
+                    && !matches!(bytes[i], b'=' | b'>' | b'/')
+                {
+                    i += 1;
+                }
+                let attr_name = self.html[name_start..i].to_ascii_lowercase();
+
+                while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                    i += 1;
+                }
+
+                let mut value = String::new();
+                if i < bytes.len() && bytes[i] == b'=' {
+                    i += 1;
+                    while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                        i += 1;
+                    }
+                    if i < bytes.len() && (bytes[i] == b'"' || bytes[i] == b'\'') {
+                        let quote = bytes[i];
+                        let value_start = i + 1;
+                        i = value_start;
+                        while i < bytes.len() && bytes[i] != quote {
+                            i += 1;
+                        }
+                        value = decode_entities(&self.html[value_start..i]);
+                        i = (i + 1).min(bytes.len());
+                    } else {
+                        let value_start = i;
+                        while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' {
+                            i += 1;
+                        }
+                        value = decode_entities(&self.html[value_start..i]);
+                    }
+                }
+
+                if !attr_name.is_empty() {
+                    attrs.push((attr_name, value));
+                }
+            }
+
+            let tag = Tag { name, attrs, end: i };
+
+            // Skip raw text so markup inside scripts and styles is not reported
+            self.pos = if tag.name == "script" || tag.name == "style" {
+                tag.end + inner_text(self.html, &tag).len()
+            } else {
+                tag.end
+            };
+
+            return Some(tag);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tags_and_attributes() {
+        let html = r#"<!DOCTYPE html><!-- <img src="hidden.jpg"> -->
+<html><head>
+<META NAME="robots" content='noindex, follow'>
+<script>var s = "<img src='fake.jpg'>";</script>
+</head><body><img src=a.jpg alt="A &amp; B"><br/></body></html>"#;
+
+        let found: Vec<Tag> = tags(html).collect();
+        let names: Vec<&str> = found.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["html", "head", "meta", "script", "body", "img", "br"]);
+
+        assert_eq!(found[2].attr("name"), Some("robots"));
+        assert_eq!(found[2].attr("content"), Some("noindex, follow"));
+        assert_eq!(inner_text(html, &found[3]), r#"var s = "<img src='fake.jpg'>";"#);
+        assert_eq!(found[5].attr("src"), Some("a.jpg"));
+        assert_eq!(found[5].attr("alt"), Some("A & B"));
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(decode_entities("a &lt;b&gt; &#39;c&#x27; &unknown; &"), "a <b> 'c' &unknown; &");
+    }
+}
@@ -0,0 +1,431 @@
+//! Human-readable HTML site map pages
+//!
+//! Renders the same entries used for the XML sitemap as a browsable HTML page
+//! for visitors and assistive technology, so both always list the same URLs.
+
+use crate::error::{IoContext, Result};
+use crate::types::*;
+use percent_encoding::percent_decode_str;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use url::Url;
+
+/// Default number of links per HTML page
+pub const DEFAULT_HTML_PAGE_SIZE: usize = 500;
+
+/// A single rendered HTML page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlSitemapPage {
+    /// File name of the page (`sitemap.html`, `sitemap-2.html`, ...)
+    pub file_name: String,
+
+    /// The complete HTML document
+    pub html: String,
+}
+
+/// A link listed on the HTML site map
+#[derive(Debug, Clone)]
+struct HtmlLink {
+    loc: String,
+    path: Vec<String>,
+    title: Option<String>,
+}
+
+/// Node of the URL path tree rendered on each page
+///
+/// URLs that differ only in query, trailing slash, scheme or port share a node.
+#[derive(Debug, Default)]
+struct PathNode<'a> {
+    links: Vec<&'a HtmlLink>,
+    children: BTreeMap<&'a str, PathNode<'a>>,
+}
+
+/// Renderer for HTML site map pages grouped by URL path
+///
+/// # Example
+/// ```
+/// use sitemap_generator::{HtmlSitemapRenderer, UrlEntry};
+///
+/// let mut renderer = HtmlSitemapRenderer::new().title("Example Site Map");
+/// renderer.add_url(UrlEntry::new("https://example.com/"));
+/// renderer.add_titled_url(UrlEntry::new("https://example.com/blog/hello"), "Hello <World>");
+///
+/// let pages = renderer.render();
+/// assert_eq!(pages.len(), 1);
+/// assert!(pages[0].html.contains("Hello &lt;World&gt;"));
+/// ```
+#[derive(Debug)]
+pub struct HtmlSitemapRenderer {
+    links: Vec<HtmlLink>,
+    title: String,
+    page_size: usize,
+    file_prefix: String,
+}
+
+impl HtmlSitemapRenderer {
+    /// Create a new renderer
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Create a new renderer with pre-allocated capacity
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            links: Vec::with_capacity(capacity),
+            title: "Site Map".to_string(),
+            page_size: DEFAULT_HTML_PAGE_SIZE,
+            file_prefix: "sitemap".to_string(),
+        }
+    }
+
+    /// Set the page heading and `<title>` (default: `Site Map`)
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Set the maximum number of links per page (default: 500)
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Set the file name prefix of rendered pages (default: `sitemap`)
+    pub fn file_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.file_prefix = prefix.into();
+        self
+    }
+
+    /// Add a URL, labelled by its last path segment
+    pub fn add_url(&mut self, entry: UrlEntry) -> &mut Self {
+        self.push(entry.loc, None);
+        self
+    }
+
+    /// Add a URL with a human-readable title
+    pub fn add_titled_url(&mut self, entry: UrlEntry, title: impl Into<String>) -> &mut Self {
+        self.push(entry.loc, Some(title.into()));
+        self
+    }
+
+    /// Add multiple URLs
+    pub fn add_urls(&mut self, entries: Vec<UrlEntry>) -> &mut Self {
+        for entry in entries {
+            self.add_url(entry);
+        }
+        self
+    }
+
+    /// Add a URL with extensions, titled by its news headline or first video title
+    pub fn add_url_with_extensions(&mut self, entry: UrlWithExtensions) -> &mut Self {
+        let title = entry
+            .news
+            .map(|news| news.title)
+            .or_else(|| entry.videos.into_iter().next().map(|video| video.title));
+        self.push(entry.url.loc, title);
+        self
+    }
+
+    /// Get the number of links
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    /// Check if there are no links
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+
+    /// Get the number of pages that will be rendered
+    pub fn page_count(&self) -> usize {
+        self.links.len().div_ceil(self.page_size).max(1)
+    }
+
+    /// Render all pages
+    ///
+    /// Links are sorted by URL path and split into pages of `page_size`; each
+    /// page shows its links as a tree following the URL path hierarchy.
+    pub fn render(&self) -> Vec<HtmlSitemapPage> {
+        let mut links: Vec<&HtmlLink> = self.links.iter().collect();
+        links.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.loc.cmp(&b.loc)));
+
+        let page_count = self.page_count();
+        let mut pages = Vec::with_capacity(page_count);
+
+        for number in 1..=page_count {
+            let start = (number - 1) * self.page_size;
+            let end = (start + self.page_size).min(links.len());
+            pages.push(HtmlSitemapPage {
+                file_name: self.page_file_name(number),
+                html: self.render_page(&links[start..end], number, page_count),
+            });
+        }
+
+        pages
+    }
+
+    /// Render all pages and write them into a directory
+    pub fn write_to_dir<P: AsRef<Path>>(&self, dir: P) -> Result<Vec<HtmlSitemapPage>> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir).with_path(dir)?;
+        let pages = self.render();
+        for page in &pages {
+            let path = dir.join(&page.file_name);
+            fs::write(&path, &page.html).with_path(&path)?;
+        }
+        Ok(pages)
+    }
+
+    fn push(&mut self, loc: String, title: Option<String>) {
+        let path = match Url::parse(&loc) {
+            Ok(url) => {
+                let mut path = vec![url.host_str().unwrap_or_default().to_string()];
+                path.extend(
+                    url.path()
+                        .split('/')
+                        .filter(|s| !s.is_empty())
+                        .map(|s| percent_decode_str(s).decode_utf8_lossy().into_owned()),
+                );
+                path
+            }
+            Err(_) => vec![loc.clone()],
+        };
+        self.links.push(HtmlLink { loc, path, title });
+    }
+
+    fn page_file_name(&self, number: usize) -> String {
+        if number == 1 {
+            format!("{}.html", self.file_prefix)
+        } else {
+            format!("{}-{}.html", self.file_prefix, number)
+        }
+    }
+
+    fn render_page(&self, links: &[&HtmlLink], number: usize, page_count: usize) -> String {
+        let mut root = PathNode::default();
+        for link in links {
+            let mut node = &mut root;
+            for segment in &link.path {
+                node = node.children.entry(segment.as_str()).or_default();
+            }
+            node.links.push(link);
+        }
+
+        // Only show the host level when a page mixes several hosts
+        let mut top = &root;
+        if top.children.len() == 1 && top.links.is_empty() {
+            top = top.children.values().next().unwrap_or(top);
+        }
+
+        let title = escape_html(&self.title);
+        let mut html = String::with_capacity(256 + links.len() * 96);
+        html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"UTF-8\">\n");
+        let _ = writeln!(html, "<title>{}</title>", title);
+        html.push_str("</head>\n<body>\n");
+        let _ = writeln!(html, "<h1>{}</h1>", title);
+
+        if page_count > 1 {
+            self.render_pagination(&mut html, number, page_count);
+        }
+
+        html.push_str("<nav>\n");
+        if !top.links.is_empty() {
+            render_links(&mut html, &top.links, "/");
+            html.push('\n');
+        }
+        render_children(&mut html, top);
+        html.push_str("</nav>\n");
+
+        if page_count > 1 {
+            self.render_pagination(&mut html, number, page_count);
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    fn render_pagination(&self, html: &mut String, number: usize, page_count: usize) {
+        html.push_str("<p class=\"pagination\">");
+        if number > 1 {
+            let _ = write!(
+                html,
+                "<a href=\"{}\" rel=\"prev\">Previous</a> ",
+                escape_html(&self.page_file_name(number - 1))
+            );
+        }
+        for n in 1..=page_count {
+            if n == number {
+                let _ = write!(html, "<strong aria-current=\"page\">{}</strong> ", n);
+            } else {
+                let _ = write!(html, "<a href=\"{}\">{}</a> ", escape_html(&self.page_file_name(n)), n);
+            }
+        }
+        if number < page_count {
+            let _ = write!(
+                html,
+                "<a href=\"{}\" rel=\"next\">Next</a>",
+                escape_html(&self.page_file_name(number + 1))
+            );
+        }
+        html.push_str("</p>\n");
+    }
+}
+
+impl Default for HtmlSitemapRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn render_children(html: &mut String, node: &PathNode<'_>) {
+    if node.children.is_empty() {
+        return;
+    }
+
+    html.push_str("<ul>\n");
+    for (segment, child) in &node.children {
+        html.push_str("<li>");
+        if child.links.is_empty() {
+            html.push_str(&escape_html(segment));
+        } else {
+            render_links(html, &child.links, segment);
+        }
+        if !child.children.is_empty() {
+            html.push('\n');
+            render_children(html, child);
+        }
+        html.push_str("</li>\n");
+    }
+    html.push_str("</ul>\n");
+}
+
+/// Render the links of a node, labelling variants of one path by their full URL
+fn render_links(html: &mut String, links: &[&HtmlLink], fallback: &str) {
+    for (index, link) in links.iter().enumerate() {
+        if index > 0 {
+            html.push_str(", ");
+        }
+        let fallback = if links.len() > 1 { link.loc.as_str() } else { fallback };
+        render_link(html, link, fallback);
+    }
+}
+
+fn render_link(html: &mut String, link: &HtmlLink, fallback: &str) {
+    let label = link.title.as_deref().unwrap_or(fallback);
+    let _ = write!(
+        html,
+        "<a href=\"{}\">{}</a>",
+        escape_html(&link.loc),
+        escape_html(label)
+    );
+}
+
+/// Escape text for use in HTML content and quoted attribute values
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html(r#"<a href="x">Tom & Jerry's</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_path_hierarchy() {
+        let mut renderer = HtmlSitemapRenderer::new();
+        renderer.add_urls(vec![
+            UrlEntry::new("https://example.com/blog/b-post"),
+            UrlEntry::new("https://example.com/"),
+            UrlEntry::new("https://example.com/blog/"),
+            UrlEntry::new("https://example.com/blog/a-post"),
+            UrlEntry::new("https://example.com/docs/guide/install"),
+        ]);
+
+        let html = &renderer.render()[0].html;
+        let nav = &html[html.find("<nav>").unwrap()..];
+        assert_eq!(
+            nav,
+            "<nav>
+<a href=\"https://example.com/\">/</a>
+<ul>
+<li><a href=\"https://example.com/blog/\">blog</a>
+<ul>
+<li><a href=\"https://example.com/blog/a-post\">a-post</a></li>
+<li><a href=\"https://example.com/blog/b-post\">b-post</a></li>
+</ul>
+</li>
+<li>docs
+<ul>
+<li>guide
+<ul>
+<li><a href=\"https://example.com/docs/guide/install\">install</a></li>
+</ul>
+</li>
+</ul>
+</li>
+</ul>
+</nav>
+</body>
+</html>
+"
+        );
+    }
+
+    #[test]
+    fn test_path_variants() {
+        let locs = [
+            "https://example.com/list?page=1",
+            "https://example.com/list?page=2",
+            "https://example.com/list",
+            "https://example.com/blog",
+            "https://example.com/blog/",
+            "http://example.com:8080/blog",
+        ];
+        let mut renderer = HtmlSitemapRenderer::new();
+        renderer.add_urls(locs.iter().map(|loc| UrlEntry::new(*loc)).collect());
+        renderer.add_titled_url(UrlEntry::new("https://example.com/list?page=3"), "Page 3");
+
+        let html = &renderer.render()[0].html;
+        for loc in locs {
+            assert!(html.contains(&format!("<a href=\"{}\">{}</a>", loc, loc)), "{} missing", loc);
+        }
+        assert!(html.contains("<a href=\"https://example.com/list?page=3\">Page 3</a>"));
+    }
+
+    #[test]
+    fn test_pagination() {
+        let mut renderer = HtmlSitemapRenderer::new().page_size(2);
+        for i in 0..5 {
+            renderer.add_url(UrlEntry::new(format!("https://example.com/p{}", i)));
+        }
+
+        let pages = renderer.render();
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[0].file_name, "sitemap.html");
+        assert_eq!(pages[2].file_name, "sitemap-3.html");
+        assert!(pages[1].html.contains("<a href=\"sitemap.html\" rel=\"prev\">Previous</a>"));
+        assert!(pages[1].html.contains("<a href=\"sitemap-3.html\" rel=\"next\">Next</a>"));
+        assert!(pages[2].html.contains("https://example.com/p4"));
+        assert!(!pages[2].html.contains("https://example.com/p0"));
+    }
+}
@@ -0,0 +1,543 @@
+//! Incremental regeneration of large, sharded sitemap sets
+//!
+//! A JSON manifest stored next to the sitemap index records which URLs live in
+//! which shard and a content hash of every shard. On the next run, URLs keep
+//! their shard, new URLs fill free space, and only shards whose rendered
+//! content changed are rewritten and get a fresh `lastmod` in the index.
+
+use crate::builder::{SitemapBuilder, SitemapIndexBuilder};
+use crate::error::{Error, IoContext, Result};
+use crate::resolve::resolved_url;
+use crate::types::*;
+use crate::validator::MAX_URLS;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// Default file name of the manifest sidecar
+pub const DEFAULT_MANIFEST_NAME: &str = "sitemap-manifest.json";
+
+/// Record of a single shard in the manifest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShardRecord {
+    /// Stable numeric identifier of the shard
+    pub id: usize,
+
+    /// File name of the shard, relative to the output directory
+    pub file: String,
+
+    /// Hex-encoded FNV-1a hash of the uncompressed shard XML
+    pub hash: String,
+
+    /// Date the shard content last changed (W3C Datetime format)
+    pub lastmod: String,
+
+    /// URLs contained in the shard, in document order
+    pub urls: Vec<String>,
+}
+
+/// Persisted state of a sharded sitemap set
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShardManifest {
+    /// Identifier to use for the next new shard
+    pub next_id: usize,
+
+    /// Current shards, in index order
+    pub shards: Vec<ShardRecord>,
+}
+
+impl ShardManifest {
+    /// Load a manifest from a JSON file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let json = fs::read_to_string(&path).with_path(&path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Save the manifest as pretty-printed JSON
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(&path, json).with_path(&path)?;
+        Ok(())
+    }
+}
+
+/// Outcome of an incremental regeneration run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IncrementalReport {
+    /// Shard files that were (re)written
+    pub written: Vec<String>,
+
+    /// Shard files left untouched because their content did not change
+    pub unchanged: Vec<String>,
+
+    /// Shard files deleted because all their URLs were removed
+    pub removed: Vec<String>,
+}
+
+/// An entry assigned to a shard, with its resolved `loc`
+#[derive(Debug)]
+struct ShardUrl<'a> {
+    loc: Cow<'a, str>,
+    entry: &'a UrlEntry,
+}
+
+/// Writer that regenerates a sharded sitemap set, rewriting only changed shards
+///
+/// # Example
+/// ```no_run
+/// use sitemap_generator::{IncrementalSitemapWriter, UrlEntry};
+///
+/// let urls: Vec<UrlEntry> = (0..120_000)
+///     .map(|i| UrlEntry::new(format!("https://example.com/page{}", i)))
+///     .collect();
+///
+/// let writer = IncrementalSitemapWriter::new("public/sitemaps", "https://example.com/sitemaps/")
+///     .compress(true);
+///
+/// let report = writer.write(&urls).unwrap();
+/// println!("rewrote {} shards", report.written.len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct IncrementalSitemapWriter {
+    dir: PathBuf,
+    base_url: String,
+    file_prefix: String,
+    index_name: String,
+    manifest_name: String,
+    urls_per_shard: usize,
+    compress: bool,
+    lastmod: Option<String>,
+    page_base_url: Option<Url>,
+    namespaces: Vec<(String, String)>,
+    validate: bool,
+}
+
+impl IncrementalSitemapWriter {
+    /// Create a writer for the given output directory
+    ///
+    /// `base_url` is the public URL of that directory and is used for the
+    /// `<loc>` of each shard in the sitemap index.
+    pub fn new(dir: impl Into<PathBuf>, base_url: impl Into<String>) -> Self {
+        Self {
+            dir: dir.into(),
+            base_url: base_url.into(),
+            file_prefix: "sitemap".to_string(),
+            index_name: "sitemap_index.xml".to_string(),
+            manifest_name: DEFAULT_MANIFEST_NAME.to_string(),
+            urls_per_shard: MAX_URLS,
+            compress: false,
+            lastmod: None,
+            page_base_url: None,
+            namespaces: Vec::new(),
+            validate: true,
+        }
+    }
+
+    /// Set the shard file name prefix (default: `sitemap`)
+    pub fn file_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.file_prefix = prefix.into();
+        self
+    }
+
+    /// Set the sitemap index file name (default: `sitemap_index.xml`)
+    pub fn index_name(mut self, name: impl Into<String>) -> Self {
+        self.index_name = name.into();
+        self
+    }
+
+    /// Set the manifest file name (default: `sitemap-manifest.json`)
+    pub fn manifest_name(mut self, name: impl Into<String>) -> Self {
+        self.manifest_name = name.into();
+        self
+    }
+
+    /// Set the maximum number of URLs per shard (default: 50,000)
+    pub fn urls_per_shard(mut self, count: usize) -> Self {
+        self.urls_per_shard = count.clamp(1, MAX_URLS);
+        self
+    }
+
+    /// Write gzip-compressed shards (disabled by default)
+    pub fn compress(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// Set the `lastmod` recorded for shards changed in this run
+    ///
+    /// Defaults to today's date (UTC).
+    pub fn lastmod(mut self, lastmod: impl Into<String>) -> Self {
+        self.lastmod = Some(lastmod.into());
+        self
+    }
+
+    /// Resolve relative page URLs against a base URL before sharding
+    ///
+    /// The manifest records the resolved URLs.
+    pub fn page_base_url(mut self, base: Url) -> Self {
+        self.page_base_url = Some(base);
+        self
+    }
+
+    /// Register a prefix for extension elements in a custom namespace
+    ///
+    /// See [`extension`](crate::extension) for attaching elements to entries.
+    pub fn namespace(mut self, prefix: impl Into<String>, uri: impl Into<String>) -> Self {
+        self.namespaces.push((prefix.into(), uri.into()));
+        self
+    }
+
+    /// Enable or disable validation (enabled by default)
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    /// Path of the manifest file
+    pub fn manifest_path(&self) -> PathBuf {
+        self.dir.join(&self.manifest_name)
+    }
+
+    /// Path of the sitemap index file
+    pub fn index_path(&self) -> PathBuf {
+        self.dir.join(&self.index_name)
+    }
+
+    /// Public URL of the sitemap index file
+    pub fn index_url(&self) -> String {
+        self.shard_url(&self.index_name)
+    }
+
+    /// Regenerate the sitemap set from the full list of URLs
+    ///
+    /// Duplicate `loc` values are ignored after their first occurrence. The
+    /// manifest and index are saved only after every shard is written, so a
+    /// failed run is repaired by the next one.
+    pub fn write(&self, entries: &[UrlEntry]) -> Result<IncrementalReport> {
+        let manifest_path = self.manifest_path();
+        let previous = if manifest_path.exists() {
+            ShardManifest::load(&manifest_path)?
+        } else {
+            ShardManifest::default()
+        };
+
+        let lastmod = self
+            .lastmod
+            .clone()
+            .unwrap_or_else(|| chrono::Utc::now().format("%Y-%m-%d").to_string());
+
+        let shards = self.assign_shards(&previous, entries);
+
+        fs::create_dir_all(&self.dir).with_path(&self.dir)?;
+
+        let mut report = IncrementalReport::default();
+        let mut manifest = ShardManifest {
+            next_id: previous.next_id,
+            shards: Vec::with_capacity(shards.len()),
+        };
+        let previous_by_id: HashMap<usize, &ShardRecord> =
+            previous.shards.iter().map(|s| (s.id, s)).collect();
+
+        // Render a batch of shards at a time and write it before rendering the
+        // next, so only one batch of shard documents is held in memory
+        let mut shards = shards.into_iter();
+        loop {
+            let batch: Vec<(usize, Vec<ShardUrl>)> = shards.by_ref().take(render_batch_size()).collect();
+            if batch.is_empty() {
+                break;
+            }
+            let rendered = self.render_shards(&batch, &previous_by_id)?;
+
+            for ((id, urls), rendered) in batch.into_iter().zip(rendered) {
+                let old = previous_by_id.get(&id);
+
+                let Some(rendered) = rendered else {
+                    if let Some(old) = old {
+                        Self::remove_file(&self.dir.join(&old.file))?;
+                        report.removed.push(old.file.clone());
+                    }
+                    continue;
+                };
+
+                let file = self.shard_file_name(id);
+
+                let shard_lastmod = match rendered.content {
+                    None => {
+                        report.unchanged.push(file.clone());
+                        old.map(|o| o.lastmod.clone()).unwrap_or_else(|| lastmod.clone())
+                    }
+                    Some(content) => {
+                        let path = self.dir.join(&file);
+                        fs::write(&path, content).with_path(&path)?;
+                        if let Some(old) = old {
+                            if old.file != file {
+                                Self::remove_file(&self.dir.join(&old.file))?;
+                            }
+                        }
+                        report.written.push(file.clone());
+                        lastmod.clone()
+                    }
+                };
+
+                manifest.next_id = manifest.next_id.max(id + 1);
+                manifest.shards.push(ShardRecord {
+                    id,
+                    file,
+                    hash: rendered.hash,
+                    lastmod: shard_lastmod,
+                    urls: urls.into_iter().map(|u| u.loc.into_owned()).collect(),
+                });
+            }
+        }
+
+        let mut index = SitemapIndexBuilder::with_capacity(manifest.shards.len()).validate(self.validate);
+        for shard in &manifest.shards {
+            index.add_sitemap(
+                SitemapIndexEntry::new(self.shard_url(&shard.file)).lastmod(shard.lastmod.clone()),
+            );
+        }
+        index.write(self.index_path())?;
+
+        manifest.save(&manifest_path)?;
+
+        Ok(report)
+    }
+
+    /// Assign every entry to a shard, keeping URLs in the shard they were in last time
+    fn assign_shards<'a>(
+        &self,
+        previous: &ShardManifest,
+        entries: &'a [UrlEntry],
+    ) -> Vec<(usize, Vec<ShardUrl<'a>>)> {
+        let mut shard_of: HashMap<&str, usize> = HashMap::new();
+        for (pos, shard) in previous.shards.iter().enumerate() {
+            for loc in &shard.urls {
+                shard_of.insert(loc.as_str(), pos);
+            }
+        }
+
+        let mut shards: Vec<(usize, Vec<ShardUrl>)> =
+            previous.shards.iter().map(|s| (s.id, Vec::new())).collect();
+        let mut seen = HashSet::with_capacity(entries.len());
+        let mut pending = Vec::new();
+
+        for entry in entries {
+            let loc = resolved_url(self.page_base_url.as_ref(), &entry.loc);
+            if !seen.insert(loc.clone()) {
+                continue;
+            }
+            let url = ShardUrl { loc, entry };
+            match shard_of.get(url.loc.as_ref()) {
+                Some(&pos) if shards[pos].1.len() < self.urls_per_shard => {
+                    shards[pos].1.push(url);
+                }
+                _ => pending.push(url),
+            }
+        }
+
+        let mut next_id = previous.next_id;
+        let mut pending = pending.into_iter().peekable();
+
+        for (_, urls) in shards.iter_mut() {
+            while urls.len() < self.urls_per_shard {
+                match pending.next() {
+                    Some(entry) => urls.push(entry),
+                    None => break,
+                }
+            }
+        }
+
+        while pending.peek().is_some() {
+            let urls: Vec<ShardUrl> = pending.by_ref().take(self.urls_per_shard).collect();
+            shards.push((next_id, urls));
+            next_id += 1;
+        }
+
+        shards
+    }
+
+    fn shard_file_name(&self, id: usize) -> String {
+        let ext = if self.compress { "xml.gz" } else { "xml" };
+        format!("{}-{}.{}", self.file_prefix, id, ext)
+    }
+
+    fn shard_url(&self, file: &str) -> String {
+        format!("{}/{}", self.base_url.trim_end_matches('/'), file)
+    }
+
+    /// Render a batch of shards (in parallel with the `rayon` feature)
+    ///
+    /// Empty shards render to `None`.
+    fn render_shards(
+        &self,
+        shards: &[(usize, Vec<ShardUrl>)],
+        previous: &HashMap<usize, &ShardRecord>,
+    ) -> Result<Vec<Option<RenderedShard>>> {
+        let render = |(id, urls): &(usize, Vec<ShardUrl>)| {
+            if urls.is_empty() {
+                return Ok(None);
+            }
+            self.render_shard(*id, urls, previous.get(id).copied()).map(Some)
+        };
+
+        #[cfg(feature = "rayon")]
+        let results: Vec<Result<Option<RenderedShard>>> = {
+            use rayon::prelude::*;
+            shards.par_iter().map(render).collect()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let results: Vec<Result<Option<RenderedShard>>> = shards.iter().map(render).collect();
+
+        results.into_iter().collect()
+    }
+
+    /// Render a shard and compare it with the previous run
+    fn render_shard(&self, id: usize, urls: &[ShardUrl], old: Option<&ShardRecord>) -> Result<RenderedShard> {
+        let mut builder = SitemapBuilder::with_capacity(urls.len()).validate(self.validate);
+        if let Some(ref base) = self.page_base_url {
+            builder = builder.base_url(base.clone());
+        }
+        for (prefix, uri) in &self.namespaces {
+            builder = builder.namespace(prefix, uri);
+        }
+        builder.add_urls(urls.iter().map(|u| u.entry.clone()).collect());
+        let xml = builder.build()?;
+        let hash = content_hash(xml.as_bytes());
+
+        let file = self.shard_file_name(id);
+        let unchanged = matches!(old, Some(old) if old.hash == hash && old.file == file)
+            && self.dir.join(&file).exists();
+
+        let content = if unchanged {
+            None
+        } else if self.compress {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder
+                .write_all(xml.as_bytes())
+                .map_err(|err| Error::compression(None, err))?;
+            Some(encoder.finish().map_err(|err| Error::compression(None, err))?)
+        } else {
+            Some(xml.into_bytes())
+        };
+
+        Ok(RenderedShard { hash, content })
+    }
+
+    fn remove_file(path: &Path) -> Result<()> {
+        match fs::remove_file(path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(Error::Io {
+                path: Some(path.to_path_buf()),
+                source: err,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Number of shards rendered before they are written
+///
+/// Shards are rendered one at a time, or one per thread with the `rayon` feature.
+fn render_batch_size() -> usize {
+    #[cfg(feature = "rayon")]
+    return rayon::current_num_threads();
+    #[cfg(not(feature = "rayon"))]
+    return 1;
+}
+
+/// A shard rendered in memory
+struct RenderedShard {
+    hash: String,
+
+    /// File content, or `None` if the shard is unchanged on disk
+    content: Option<Vec<u8>>,
+}
+
+/// 64-bit FNV-1a hash, stable across platforms and Rust versions
+pub(crate) fn content_hash(bytes: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls(range: std::ops::Range<usize>) -> Vec<UrlEntry> {
+        range
+            .map(|i| UrlEntry::new(format!("https://example.com/page{}", i)))
+            .collect()
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        assert_eq!(content_hash(b""), "cbf29ce484222325");
+        assert_eq!(content_hash(b"a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn test_new_urls_fill_existing_shards() {
+        let writer = IncrementalSitemapWriter::new("unused", "https://example.com/").urls_per_shard(3);
+        let previous = ShardManifest {
+            next_id: 2,
+            shards: vec![ShardRecord {
+                id: 1,
+                file: "sitemap-1.xml".to_string(),
+                hash: String::new(),
+                lastmod: "2025-11-01".to_string(),
+                urls: vec![
+                    "https://example.com/page2".to_string(),
+                    "https://example.com/page3".to_string(),
+                ],
+            }],
+        };
+
+        let entries = urls(0..5);
+        let shards = writer.assign_shards(&previous, &entries);
+
+        let locs: Vec<(usize, Vec<&str>)> = shards
+            .iter()
+            .map(|(id, urls)| (*id, urls.iter().map(|u| u.loc.as_ref()).collect()))
+            .collect();
+        assert_eq!(
+            locs,
+            vec![
+                (
+                    1,
+                    vec![
+                        "https://example.com/page2",
+                        "https://example.com/page3",
+                        "https://example.com/page0",
+                    ]
+                ),
+                (2, vec!["https://example.com/page1", "https://example.com/page4"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rendered_shards_match_builder_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let writer = IncrementalSitemapWriter::new(dir.path(), "https://example.com/")
+            .urls_per_shard(100)
+            .compress(true);
+        writer.write(&urls(0..1_000)).unwrap();
+
+        for (i, chunk) in urls(0..1_000).chunks(100).enumerate() {
+            let mut builder = SitemapBuilder::new();
+            builder.add_urls(chunk.to_vec());
+            let expected = builder.build_compressed_bytes().unwrap();
+            let written = fs::read(dir.path().join(format!("sitemap-{}.xml.gz", i))).unwrap();
+            assert_eq!(written, expected);
+        }
+    }
+}
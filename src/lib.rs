@@ -12,11 +12,28 @@
 //! - **Combined Sitemaps**: Combine multiple extensions (image + video + news) in one sitemap
 //! - **Sitemap Index**: Manage multiple sitemap files for large websites (>50k URLs)
 //! - **Validation**: Automatic validation of URLs, dates, size limits, and protocol compliance
+//! - **Repair Mode**: Fix out-of-range values, drop invalid entries and report both instead of failing
 //! - **Compression**: Built-in gzip compression (96-98% bandwidth savings)
 //! - **Web Framework Support**: Direct bytes output for Axum, Actix-web, Rocket, etc.
-//! - **Parsing**: Read and parse existing sitemap files
+//! - **Parsing**: Read and parse existing sitemap files, with limits for untrusted input and a lenient mode for messy ones
+//! - **Incremental Regeneration**: Rewrite only the shards of a large sitemap set that changed
+//! - **Rolling News Sitemaps**: Keep a news sitemap to the articles of the last two days across runs
+//! - **Static Sites**: Build a sitemap by scanning a directory of generated HTML files
+//! - **HTML Extraction**: Collect images and videos from page HTML for combined sitemaps
+//! - **robots.txt**: Manage `Sitemap:` directives and flag URLs blocked by `Disallow` rules
+//! - **Browser Friendly**: Optional XSL stylesheet (bundled default included) for human-readable sitemaps
+//! - **HTML Site Maps**: Paginated, path-grouped HTML pages generated from the same entries
+//! - **URL Normalization**: Opt-in canonicalization and de-duplication of builder entries
+//! - **Custom Extensions**: Attach elements in your own namespaces (PageMaps, vendor tags) to any URL
+//! - **URL Encoding**: Optional percent-encoding and punycode output, decoded back by the parser
+//! - **Relative URLs**: Resolve route paths against a base URL at build time
+//! - **Scope Rules**: Reject or report URLs outside the sitemap's host and directory
+//! - **Conformance Checking**: Check documents against the sitemaps.org schema and Google extension rules, with line/column diagnostics
+//! - **Diffing**: Report added, removed and modified URLs between two sitemap versions
 //! - **Memory Efficient**: ~140 bytes/URL during generation, 0 bytes after (proven)
 //! - **High Performance**: ~830K URLs/second, immediate memory cleanup
+//! - **Parallel Generation**: Optional `rayon` feature for multi-core shard rendering and validation
+//! - **Zero-Copy Writing**: Serialize your own row types through borrowed entry traits
 //! - **Optimized Builders**: Pre-allocate capacity with `with_capacity()` for better performance
 //!
 //! ## Quick Start Examples
@@ -138,6 +155,7 @@
 //!     "AI Demo",
 //!     "Watch the demo"
 //! )
+//! .content_loc("https://example.com/demo.mp4")
 //! .duration(180);
 //!
 //! builder.add_url(
@@ -217,10 +235,48 @@ pub mod writer;
 pub mod validator;
 pub mod parser;
 pub mod error;
+pub mod diff;
+pub mod incremental;
+pub mod scanner;
+pub mod extractor;
+pub mod robots;
+pub mod html_sitemap;
+pub mod borrowed;
+pub mod normalize;
+pub mod scope;
+pub mod conformance;
+pub mod text;
+pub mod repair;
+pub mod news_checks;
+pub mod extension;
+pub mod news_rolling;
+
+mod html;
+mod iso;
+mod resolve;
 
 // Re-export commonly used types
 pub use types::*;
+pub use borrowed::{ImageEntryRef, ImagesRef, UrlEntryRef, VideoEntryRef, VideosRef};
 pub use builder::*;
 pub use error::{Error, Result};
 pub use validator::Validator;
-pub use parser::SitemapParser;
+pub use text::LengthUnit;
+pub use repair::{DroppedEntry, RepairReport, RepairedField, RepairedSitemap};
+pub use writer::{InvalidCharPolicy, UrlEncoding};
+pub use parser::{LenientParse, ParseIssue, ParseLimits, SitemapParser};
+pub use conformance::{ConformanceChecker, ConformanceReport, Diagnostic, DiagnosticKind, Severity};
+pub use diff::{FieldChange, SitemapDiff, UrlChange};
+pub use incremental::{IncrementalReport, IncrementalSitemapWriter, ShardManifest, ShardRecord};
+pub use news_checks::NewsChecks;
+pub use extension::ExtensionElement;
+pub use news_rolling::{Clock, NewsArticleRecord, RollingNewsReport, RollingNewsSitemap, RollingNewsState, SystemClock};
+pub use scanner::{DirectoryScanner, LastmodSource};
+pub use extractor::HtmlExtractor;
+pub use robots::{RobotsGroup, RobotsRule, RobotsTxt};
+pub use html_sitemap::{HtmlSitemapPage, HtmlSitemapRenderer};
+pub use scope::{ScopeViolation, ScopeViolationKind, SitemapScope};
+pub use url::Url;
+pub use normalize::{
+    CollapsedUrl, DedupPolicy, NormalizationReport, RewrittenUrl, TrailingSlash, UrlNormalizer,
+};
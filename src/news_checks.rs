@@ -0,0 +1,381 @@
+//! Opt-in editorial checks for Google News entries
+//!
+//! The news builders only enforce the protocol by default. [`NewsChecks`]
+//! adds Google News' publisher guidelines: fresh articles, one spelling per
+//! publication name, clean titles and well-formed keywords and tickers.
+
+use crate::error::{Error, Result};
+use crate::types::{NewsEntry, StockTicker};
+use crate::validator::parse_w3c_datetime;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, HashSet};
+
+const MONTHS: &[&str] = &[
+    "january", "february", "march", "april", "may", "june", "july", "august", "september", "october",
+    "november", "december", "jan", "feb", "mar", "apr", "jun", "jul", "aug", "sep", "sept", "oct", "nov",
+    "dec",
+];
+
+/// Editorial checks applied to news entries by the news and combined builders
+///
+/// All checks are off by default. Checks run as part of builder validation;
+/// `build_repaired()` drops entries failing the per-entry checks but does not
+/// compare publication names across entries.
+///
+/// # Example
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use sitemap_generator::{NewsChecks, NewsEntry, NewsPublication, NewsSitemapBuilder, UrlEntry, UrlWithNews};
+///
+/// let checks = NewsChecks::new()
+///     .max_age_days(2)
+///     .clean_titles(true)
+///     .now(Utc.with_ymd_and_hms(2025, 11, 3, 12, 0, 0).unwrap());
+///
+/// let mut builder = NewsSitemapBuilder::new().news_checks(checks);
+/// builder.add_url(UrlWithNews::new(
+///     UrlEntry::new("https://example.com/news/archive"),
+///     NewsEntry::new(NewsPublication::new("Daily", "en"), "2015-06-01", "Archive"),
+/// ));
+///
+/// assert_eq!(builder.build().unwrap_err().code(), "invalid_field");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NewsChecks {
+    max_age: Option<Duration>,
+    consistent_publications: bool,
+    clean_titles: bool,
+    keyword_format: bool,
+    now: Option<DateTime<Utc>>,
+}
+
+impl NewsChecks {
+    /// Create a set of checks with every check disabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable every check, with the two-day Google News window
+    pub fn all() -> Self {
+        Self::new()
+            .max_age_days(2)
+            .consistent_publications(true)
+            .clean_titles(true)
+            .keyword_format(true)
+    }
+
+    /// Reject articles published more than `days` days ago or in the future
+    pub fn max_age_days(mut self, days: u32) -> Self {
+        self.max_age = Some(Duration::days(days.into()));
+        self
+    }
+
+    /// Reject spellings of a publication name that differ only in case,
+    /// spacing or a leading "The" from the first one seen
+    pub fn consistent_publications(mut self, enabled: bool) -> Self {
+        self.consistent_publications = enabled;
+        self
+    }
+
+    /// Reject titles that contain the publication name or a date
+    ///
+    /// The name counts when it stands apart from the headline, as in
+    /// `Markets rally - The Daily` or `(AP)`, not as a word of it.
+    pub fn clean_titles(mut self, enabled: bool) -> Self {
+        self.clean_titles = enabled;
+        self
+    }
+
+    /// Reject empty or duplicate keywords and tickers not in `EXCHANGE:SYMBOL` form
+    pub fn keyword_format(mut self, enabled: bool) -> Self {
+        self.keyword_format = enabled;
+        self
+    }
+
+    /// Use a fixed time instead of the system time for the freshness check
+    pub fn now(mut self, now: DateTime<Utc>) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// Apply the per-entry checks to one news entry
+    pub fn check_entry(&self, news: &NewsEntry) -> Result<()> {
+        if let Some(max_age) = self.max_age {
+            self.check_freshness(&news.publication_date, max_age)?;
+        }
+
+        if self.clean_titles {
+            check_title(news)?;
+        }
+
+        if self.keyword_format {
+            check_keywords(&news.keywords)?;
+            news.stock_tickers.iter().try_for_each(check_ticker)?;
+        }
+
+        Ok(())
+    }
+
+    /// Check that each publication is spelled the same way in all entries
+    ///
+    /// Takes the index, page URL and news metadata of each entry. Errors carry
+    /// the index and URL of the first entry with a different spelling.
+    pub fn check_publications<'a, I>(&self, entries: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (usize, &'a str, &'a NewsEntry)>,
+    {
+        if !self.consistent_publications {
+            return Ok(());
+        }
+
+        let mut seen: HashMap<String, &str> = HashMap::new();
+        for (index, url, news) in entries {
+            let name = news.publication.name.as_str();
+            let first = *seen.entry(publication_key(name)).or_insert(name);
+            if first != name {
+                return Err(Error::InvalidField {
+                    field: "news:name",
+                    value: name.to_string(),
+                    reason: "spelled differently in an earlier entry",
+                }
+                .in_entry(index, url));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_freshness(&self, date: &str, max_age: Duration) -> Result<()> {
+        // Malformed dates are reported by the protocol validation
+        let Some(published) = parse_w3c_datetime(date) else {
+            return Ok(());
+        };
+        let now = self.now.unwrap_or_else(Utc::now);
+
+        let reason = if published > now {
+            "publication date is in the future"
+        } else if published < now - max_age {
+            "article is older than the news window"
+        } else {
+            return Ok(());
+        };
+        Err(Error::InvalidField {
+            field: "news:publication_date",
+            value: date.to_string(),
+            reason,
+        })
+    }
+}
+
+/// Publication name with case, spacing and a leading "The" ignored
+fn publication_key(name: &str) -> String {
+    let words: Vec<String> = name.split_whitespace().map(str::to_lowercase).collect();
+    match words.split_first() {
+        Some((first, rest)) if first == "the" && !rest.is_empty() => rest.join(" "),
+        _ => words.join(" "),
+    }
+}
+
+fn check_title(news: &NewsEntry) -> Result<()> {
+    let reason = if contains_publication(&news.title, &news.publication.name) {
+        "title contains the publication name"
+    } else if contains_date(&news.title) {
+        "title contains a date"
+    } else {
+        return Ok(());
+    };
+    Err(Error::InvalidField {
+        field: "news:title",
+        value: news.title.clone(),
+        reason,
+    })
+}
+
+/// Detect the publication name set off as its own part of the title, ignoring
+/// case (`Markets rally - The Daily`, `Markets rally (AP)`, `TIME: Person of the Year`)
+///
+/// A name that is only a word of the headline, like `Time` in `Time to act`, is not
+/// reported.
+fn contains_publication(title: &str, name: &str) -> bool {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return false;
+    }
+
+    let mut title = title.to_lowercase();
+    for separator in [" - ", " – ", " — ", " | ", ": ", "(", ")", "[", "]"] {
+        title = title.replace(separator, "\n");
+    }
+    title.split('\n').any(|part| part.trim() == name)
+}
+
+/// Detect numeric dates (`2025-11-03`, `03/11/2025`, `3.11.25`) and English
+/// month names next to a day number (`Nov 3`, `3rd November`)
+///
+/// Numeric dates need a plausible day and month and a four-digit year
+/// (1900-2099) first or last, or a two-digit year last, so version numbers
+/// (`1.82.0`) and scores (`3-1-2`) are not dates.
+fn contains_date(title: &str) -> bool {
+    let words: Vec<&str> = title
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .collect();
+
+    let is_numeric_date = |word: &str| {
+        let parts: Vec<&str> = word.split(['-', '/', '.']).collect();
+        if parts.len() != 3
+            || !parts
+                .iter()
+                .all(|p| (1..=4).contains(&p.len()) && p.bytes().all(|b| b.is_ascii_digit()))
+        {
+            return false;
+        }
+        let value = |i: usize| parts[i].parse::<u32>().unwrap_or(0);
+        let is_year = |i: usize| match parts[i].len() {
+            4 => (1900..=2099).contains(&value(i)),
+            len => len == 2,
+        };
+        let is_month = |i: usize| parts[i].len() <= 2 && (1..=12).contains(&value(i));
+        let is_day = |i: usize| parts[i].len() <= 2 && (1..=31).contains(&value(i));
+
+        (parts[0].len() == 4 && is_year(0) && is_month(1) && is_day(2))
+            || (is_year(2) && ((is_day(0) && is_month(1)) || (is_month(0) && is_day(1))))
+    };
+    let is_day = |word: &str| {
+        let digits = ["st", "nd", "rd", "th"]
+            .iter()
+            .find_map(|suffix| word.strip_suffix(suffix))
+            .unwrap_or(word);
+        (1..=2).contains(&digits.len())
+            && digits.bytes().all(|b| b.is_ascii_digit())
+            && (1..=31).contains(&digits.parse::<u32>().unwrap_or(0))
+    };
+    let is_month = |word: &str| MONTHS.contains(&word.to_lowercase().as_str());
+
+    words.iter().any(|word| is_numeric_date(word))
+        || words
+            .windows(2)
+            .any(|pair| (is_month(pair[0]) && is_day(pair[1])) || (is_day(pair[0]) && is_month(pair[1])))
+}
+
+fn check_keywords(keywords: &[String]) -> Result<()> {
+    let mut seen = HashSet::new();
+    for keyword in keywords {
+        let reason = if keyword.trim().is_empty() {
+            "empty keyword"
+        } else if !seen.insert(keyword.trim().to_lowercase()) {
+            "duplicate keyword"
+        } else {
+            continue;
+        };
+        return Err(Error::InvalidField {
+            field: "news:keywords",
+            value: keyword.clone(),
+            reason,
+        });
+    }
+    Ok(())
+}
+
+fn check_ticker(ticker: &StockTicker) -> Result<()> {
+    let is_exchange = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit());
+    let is_symbol = !ticker.symbol.is_empty()
+        && ticker
+            .symbol
+            .bytes()
+            .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'.' || b == b'-');
+
+    if is_symbol && ticker.exchange.as_deref().is_some_and(is_exchange) {
+        Ok(())
+    } else {
+        Err(Error::InvalidField {
+            field: "news:stock_tickers",
+            value: ticker.to_string(),
+            reason: "tickers must be EXCHANGE:SYMBOL",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::NewsPublication;
+    use chrono::TimeZone;
+
+    fn news(name: &str, date: &str, title: &str) -> NewsEntry {
+        NewsEntry::new(NewsPublication::new(name, "en"), date, title)
+    }
+
+    #[test]
+    fn test_freshness() {
+        let checks = NewsChecks::new()
+            .max_age_days(2)
+            .now(Utc.with_ymd_and_hms(2025, 11, 3, 12, 0, 0).unwrap());
+
+        assert!(checks.check_entry(&news("Daily", "2025-11-03T11:00:00Z", "Fresh")).is_ok());
+        assert!(checks.check_entry(&news("Daily", "2025-11-02", "Yesterday")).is_ok());
+        assert!(checks.check_entry(&news("Daily", "2025-11-01T11:00:00Z", "Stale")).is_err());
+        assert!(checks.check_entry(&news("Daily", "2015-06-01", "Archive")).is_err());
+        assert!(checks.check_entry(&news("Daily", "2025-11-03T14:00:00+01:00", "Future")).is_err());
+
+        // Disabled by default
+        assert!(NewsChecks::new().check_entry(&news("Daily", "2015-06-01", "Archive")).is_ok());
+    }
+
+    #[test]
+    fn test_publications() {
+        let checks = NewsChecks::new().consistent_publications(true);
+        let a = news("The Daily Times", "2025-11-03", "A");
+        let b = news("Daily  times", "2025-11-03", "B");
+        let c = news("Evening Post", "2025-11-03", "C");
+
+        assert!(checks.check_publications([(0, "https://example.com/a", &a), (1, "https://example.com/c", &c)]).is_ok());
+        let err = checks
+            .check_publications([
+                (0, "https://example.com/a", &a),
+                (1, "https://example.com/c", &c),
+                (2, "https://example.com/b", &b),
+            ])
+            .unwrap_err();
+        assert!(matches!(err, Error::Entry { index: 2, .. }));
+    }
+
+    #[test]
+    fn test_titles() {
+        assert!(!contains_date("Budget 2025 passes the senate"));
+        assert!(!contains_date("Top 10 tips for March madness"));
+        assert!(!contains_date("Version 1.2 released"));
+        assert!(!contains_date("Rust 1.82.0 released"));
+        assert!(!contains_date("United win 3-1-2 on aggregate"));
+        assert!(!contains_date("Build 2025.13.40 ships"));
+        assert!(contains_date("Markets on 2025-11-03"));
+        assert!(contains_date("Weather report, 03/11/2025"));
+        assert!(contains_date("Election results (Nov 3)"));
+        assert!(contains_date("Recap of 3rd November"));
+        assert!(contains_date("Forecast for 3.11.25"));
+
+        assert!(contains_publication("Markets rally - daily", "Daily"));
+        assert!(contains_publication("Markets rally (AP)", "AP"));
+        assert!(contains_publication("TIME: Person of the Year", "Time"));
+        assert!(!contains_publication("Apple earnings beat estimates", "AP"));
+        assert!(!contains_publication("Time to act on climate", "Time"));
+
+        let checks = NewsChecks::new().clean_titles(true);
+        assert!(checks.check_entry(&news("Daily", "2025-11-03", "Markets rally")).is_ok());
+        assert!(checks.check_entry(&news("Daily", "2025-11-03", "Markets rally - daily")).is_err());
+    }
+
+    #[test]
+    fn test_keywords_and_tickers() {
+        let checks = NewsChecks::new().keyword_format(true);
+        let entry = |keywords: &str, tickers: &str| {
+            news("Daily", "2025-11-03", "Markets").keywords(keywords).stock_tickers(tickers)
+        };
+
+        assert!(checks.check_entry(&entry("business, markets", "NASDAQ:AAPL, NYSE:BRK.B")).is_ok());
+        assert!(checks.check_entry(&entry("business, markets", "NASDAQ:AAPL").add_keyword(" ")).is_err());
+        assert!(checks.check_entry(&entry("markets, Markets", "NASDAQ:AAPL")).is_err());
+        assert!(checks.check_entry(&entry("business", "AAPL")).is_err());
+        assert!(checks.check_entry(&entry("business", "nasdaq:aapl")).is_err());
+    }
+}
@@ -0,0 +1,422 @@
+//! Rolling Google News sitemap maintained across runs
+//!
+//! Google News only reads articles published in the last two days, and at most
+//! 1,000 of them per sitemap. [`RollingNewsSitemap`] keeps the current articles
+//! in a JSON state file, so each run only passes the articles that are new or
+//! changed; expired articles are evicted and the sitemap is rewritten.
+
+use crate::builder::NewsSitemapBuilder;
+use crate::error::{Error, IoContext, Result};
+use crate::extension::ExtensionElement;
+use crate::types::*;
+use crate::validator::{parse_w3c_datetime, MAX_NEWS_URLS};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Default age after which articles leave the news sitemap
+pub const DEFAULT_NEWS_WINDOW: Duration = Duration::hours(48);
+
+/// Source of the current time
+///
+/// Implemented for [`SystemClock`] and for closures, so tests can pin the time.
+pub trait Clock {
+    /// The current time
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// [`Clock`] reading the system time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+impl<F: Fn() -> DateTime<Utc>> Clock for F {
+    fn now(&self) -> DateTime<Utc> {
+        self()
+    }
+}
+
+/// A news article as stored in the state file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewsArticleRecord {
+    /// Page URL of the article
+    pub loc: String,
+
+    /// Last modification date of the page (W3C Datetime format)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lastmod: Option<String>,
+
+    /// How frequently the page is likely to change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changefreq: Option<ChangeFreq>,
+
+    /// Priority of the page (0.0 to 1.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<f32>,
+
+    /// Elements in custom namespaces on the page entry
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<ExtensionElement>,
+
+    /// Name of the publication
+    pub publication_name: String,
+
+    /// Language of the publication
+    pub language: String,
+
+    /// Publication date (W3C Datetime format)
+    pub publication_date: String,
+
+    /// Article headline
+    pub title: String,
+
+    /// Keywords
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+
+    /// Stock tickers as `EXCHANGE:SYMBOL`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stock_tickers: Vec<String>,
+}
+
+impl NewsArticleRecord {
+    /// Record of an article entry
+    pub fn from_entry(entry: &UrlWithNews) -> Self {
+        Self {
+            loc: entry.url.loc.clone(),
+            lastmod: entry.url.lastmod.clone(),
+            changefreq: entry.url.changefreq,
+            priority: entry.url.priority,
+            extensions: entry.url.extensions.clone(),
+            publication_name: entry.news.publication.name.clone(),
+            language: entry.news.publication.language.clone(),
+            publication_date: entry.news.publication_date.clone(),
+            title: entry.news.title.clone(),
+            keywords: entry.news.keywords.clone(),
+            stock_tickers: entry.news.stock_tickers.iter().map(StockTicker::to_string).collect(),
+        }
+    }
+
+    /// Article entry for the news sitemap builder
+    pub fn to_entry(&self) -> UrlWithNews {
+        let mut news = NewsEntry::new(
+            NewsPublication::new(&self.publication_name, &self.language),
+            &self.publication_date,
+            &self.title,
+        );
+        news.keywords = self.keywords.clone();
+        news.stock_tickers = self.stock_tickers.iter().map(|t| StockTicker::parse_lenient(t)).collect();
+        let url = UrlEntry {
+            loc: self.loc.clone(),
+            lastmod: self.lastmod.clone(),
+            changefreq: self.changefreq,
+            priority: self.priority,
+            extensions: self.extensions.clone(),
+        };
+        UrlWithNews::new(url, news)
+    }
+}
+
+/// Persisted articles of a rolling news sitemap, newest first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RollingNewsState {
+    /// Articles currently in the sitemap
+    pub articles: Vec<NewsArticleRecord>,
+}
+
+impl RollingNewsState {
+    /// Load the state from a JSON file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let json = fs::read_to_string(&path).with_path(&path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Save the state as pretty-printed JSON
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(&path, json).with_path(&path)?;
+        Ok(())
+    }
+}
+
+/// Outcome of a rolling news sitemap update
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RollingNewsReport {
+    /// Articles added in this run
+    pub added: Vec<String>,
+
+    /// Articles already present whose metadata was replaced
+    pub updated: Vec<String>,
+
+    /// Articles removed, oldest first, because they are older than the window
+    pub expired: Vec<String>,
+
+    /// Articles removed, oldest first, to stay within the article limit
+    pub evicted: Vec<String>,
+
+    /// Number of articles in the rewritten sitemap
+    pub total: usize,
+}
+
+/// Manager of a news sitemap holding the articles of the last two days
+///
+/// # Example
+/// ```no_run
+/// use sitemap_generator::{NewsEntry, NewsPublication, RollingNewsSitemap, UrlEntry, UrlWithNews};
+///
+/// let sitemap = RollingNewsSitemap::new("public/news-sitemap.xml", "state/news-sitemap.json");
+///
+/// let article = UrlWithNews::new(
+///     UrlEntry::new("https://example.com/news/election-results"),
+///     NewsEntry::new(NewsPublication::new("Daily", "en"), "2025-11-01T09:00:00Z", "Election results"),
+/// );
+///
+/// let report = sitemap.update(vec![article]).unwrap();
+/// println!("{} articles, {} expired", report.total, report.expired.len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct RollingNewsSitemap<C: Clock = SystemClock> {
+    sitemap_path: PathBuf,
+    state_path: PathBuf,
+    window: Duration,
+    max_articles: usize,
+    compress: bool,
+    namespaces: Vec<(String, String)>,
+    clock: C,
+}
+
+impl RollingNewsSitemap {
+    /// Create a manager writing the sitemap to `sitemap_path` and its state to `state_path`
+    pub fn new(sitemap_path: impl Into<PathBuf>, state_path: impl Into<PathBuf>) -> Self {
+        Self {
+            sitemap_path: sitemap_path.into(),
+            state_path: state_path.into(),
+            window: DEFAULT_NEWS_WINDOW,
+            max_articles: MAX_NEWS_URLS,
+            compress: false,
+            namespaces: Vec::new(),
+            clock: SystemClock,
+        }
+    }
+}
+
+impl<C: Clock> RollingNewsSitemap<C> {
+    /// Set how long articles stay in the sitemap (default: 48 hours)
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Set the maximum number of articles (default and maximum: 1,000)
+    pub fn max_articles(mut self, count: usize) -> Self {
+        self.max_articles = count.clamp(1, MAX_NEWS_URLS);
+        self
+    }
+
+    /// Write a gzip-compressed sitemap (disabled by default)
+    pub fn compress(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// Register a prefix for extension elements in a custom namespace
+    pub fn namespace(mut self, prefix: impl Into<String>, uri: impl Into<String>) -> Self {
+        self.namespaces.push((prefix.into(), uri.into()));
+        self
+    }
+
+    /// Use another clock, e.g. a fixed time in tests
+    pub fn clock<C2: Clock>(self, clock: C2) -> RollingNewsSitemap<C2> {
+        RollingNewsSitemap {
+            sitemap_path: self.sitemap_path,
+            state_path: self.state_path,
+            window: self.window,
+            max_articles: self.max_articles,
+            compress: self.compress,
+            namespaces: self.namespaces,
+            clock,
+        }
+    }
+
+    /// Path of the sitemap file
+    pub fn sitemap_path(&self) -> &Path {
+        &self.sitemap_path
+    }
+
+    /// Path of the state file
+    pub fn state_path(&self) -> &Path {
+        &self.state_path
+    }
+
+    /// Load the current state (empty if the state file does not exist yet)
+    pub fn state(&self) -> Result<RollingNewsState> {
+        if self.state_path.exists() {
+            RollingNewsState::load(&self.state_path)
+        } else {
+            Ok(RollingNewsState::default())
+        }
+    }
+
+    /// Add new or changed articles, evict old ones and rewrite the sitemap
+    ///
+    /// An article whose URL is already present replaces the stored one. Pass
+    /// an empty list to only evict expired articles. Articles are kept newest
+    /// first; over the article limit, the oldest are dropped. The state file is
+    /// only saved once the sitemap was written.
+    #[allow(clippy::unnecessary_map_or)] // `Option::is_none_or` needs Rust 1.82
+    pub fn update(&self, articles: Vec<UrlWithNews>) -> Result<RollingNewsReport> {
+        let mut state = self.state()?;
+        let mut report = RollingNewsReport::default();
+
+        for (index, article) in articles.iter().enumerate() {
+            if parse_w3c_datetime(&article.news.publication_date).is_none() {
+                return Err(Error::InvalidDate {
+                    field: "news:publication_date",
+                    value: article.news.publication_date.clone(),
+                }
+                .in_entry(index, &article.url.loc));
+            }
+        }
+
+        let mut position: HashMap<String, usize> = state
+            .articles
+            .iter()
+            .enumerate()
+            .map(|(pos, a)| (a.loc.clone(), pos))
+            .collect();
+
+        for article in &articles {
+            let record = NewsArticleRecord::from_entry(article);
+            match position.get(&record.loc) {
+                Some(&pos) => {
+                    if state.articles[pos] != record {
+                        state.articles[pos] = record;
+                        report.updated.push(article.url.loc.clone());
+                    }
+                }
+                None => {
+                    position.insert(record.loc.clone(), state.articles.len());
+                    report.added.push(record.loc.clone());
+                    state.articles.push(record);
+                }
+            }
+        }
+
+        // Unparseable dates in a hand-edited state file sort as oldest and expire
+        let cutoff = self.clock.now() - self.window;
+        let mut dated: Vec<(Option<DateTime<Utc>>, NewsArticleRecord)> = state
+            .articles
+            .into_iter()
+            .map(|a| (parse_w3c_datetime(&a.publication_date).map(|d| d.with_timezone(&Utc)), a))
+            .collect();
+        dated.sort_by_key(|(published, _)| std::cmp::Reverse(*published));
+
+        let mut articles = Vec::with_capacity(dated.len().min(self.max_articles));
+        for (published, article) in dated {
+            if published.map_or(true, |published| published < cutoff) {
+                report.expired.push(article.loc);
+            } else if articles.len() >= self.max_articles {
+                report.evicted.push(article.loc);
+            } else {
+                articles.push(article);
+            }
+        }
+        report.expired.reverse();
+        report.evicted.reverse();
+        report.total = articles.len();
+
+        let mut builder = NewsSitemapBuilder::with_capacity(articles.len());
+        for (prefix, uri) in &self.namespaces {
+            builder = builder.namespace(prefix, uri);
+        }
+        builder.add_urls(articles.iter().map(NewsArticleRecord::to_entry).collect());
+        if self.compress {
+            builder.write_compressed(&self.sitemap_path)?;
+        } else {
+            builder.write(&self.sitemap_path)?;
+        }
+
+        RollingNewsState { articles }.save(&self.state_path)?;
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn article(slug: &str, date: &str) -> UrlWithNews {
+        UrlWithNews::new(
+            UrlEntry::new(format!("https://example.com/news/{}", slug)),
+            NewsEntry::new(NewsPublication::new("Daily", "en"), date, slug),
+        )
+    }
+
+    #[test]
+    fn test_rolling_window_and_limit() {
+        let dir = std::env::temp_dir().join(format!("sitemap-rolling-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let now = Utc.with_ymd_and_hms(2025, 11, 3, 12, 0, 0).unwrap();
+
+        let sitemap = RollingNewsSitemap::new(dir.join("news.xml"), dir.join("news.json"))
+            .max_articles(2)
+            .namespace("site", "https://example.com/ns")
+            .clock(move || now);
+
+        let report = sitemap
+            .update(vec![
+                article("old", "2025-11-01T11:59:00Z"),
+                article("a", "2025-11-02T08:00:00Z"),
+                article("b", "2025-11-03T08:00:00+07:00"),
+                article("c", "2025-11-03T10:00:00Z"),
+            ])
+            .unwrap();
+
+        assert_eq!(report.added.len(), 4);
+        assert_eq!(report.expired, vec!["https://example.com/news/old"]);
+        assert_eq!(report.evicted, vec!["https://example.com/news/a"]);
+        assert_eq!(report.total, 2);
+
+        let state = sitemap.state().unwrap();
+        let locs: Vec<&str> = state.articles.iter().map(|a| a.loc.as_str()).collect();
+        assert_eq!(locs, vec!["https://example.com/news/c", "https://example.com/news/b"]);
+
+        // Re-submitting an unchanged article is a no-op; a changed title is an update
+        let mut changed = article("c", "2025-11-03T10:00:00Z");
+        changed.news.title = "c, updated".to_string();
+        let report = sitemap.update(vec![article("b", "2025-11-03T08:00:00+07:00"), changed]).unwrap();
+        assert!(report.added.is_empty());
+        assert_eq!(report.updated, vec!["https://example.com/news/c"]);
+
+        // Page fields and extensions survive a round trip through the state file
+        let mut detailed = article("d", "2025-11-03T11:00:00Z");
+        detailed.url = UrlEntry::new("https://example.com/news/d")
+            .lastmod("2025-11-03T11:30:00Z")
+            .changefreq(ChangeFreq::Hourly)
+            .priority(0.8)
+            .add_extension(ExtensionElement::new("https://example.com/ns", "section").text("Politics"));
+        sitemap.update(vec![detailed]).unwrap();
+
+        let state = sitemap.state().unwrap();
+        let stored = state.articles[0].to_entry();
+        assert_eq!(stored.url.lastmod.as_deref(), Some("2025-11-03T11:30:00Z"));
+        assert_eq!(stored.url.changefreq, Some(ChangeFreq::Hourly));
+        assert_eq!(stored.url.priority, Some(0.8));
+        assert_eq!(stored.url.extensions[0].text.as_deref(), Some("Politics"));
+        assert_eq!(state.articles.len(), 2);
+
+        assert!(sitemap.update(vec![article("bad", "yesterday")]).is_err());
+        assert_eq!(sitemap.state().unwrap().articles.len(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
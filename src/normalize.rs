@@ -0,0 +1,359 @@
+//! URL normalization and de-duplication of sitemap entries
+
+use crate::types::AsUrlEntry;
+use crate::validator::parse_w3c_datetime;
+use std::collections::HashMap;
+use url::Url;
+
+/// How to treat trailing slashes in URL paths
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrailingSlash {
+    /// Leave paths as they are
+    #[default]
+    Keep,
+
+    /// Add a trailing slash to paths whose last segment has no file extension
+    Add,
+
+    /// Remove trailing slashes (except for the root path)
+    Remove,
+}
+
+/// Which entry survives when several normalize to the same URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DedupPolicy {
+    /// Keep the first entry
+    #[default]
+    KeepFirst,
+
+    /// Keep the last entry
+    KeepLast,
+
+    /// Keep the entry with the newest `lastmod` (the first one on ties)
+    NewestLastmod,
+}
+
+/// A URL that was rewritten by normalization
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewrittenUrl {
+    /// URL as it was added
+    pub original: String,
+
+    /// URL after normalization
+    pub normalized: String,
+}
+
+/// A group of entries that collapsed into one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollapsedUrl {
+    /// The normalized URL that was kept
+    pub loc: String,
+
+    /// Original URLs of every entry in the group, in input order
+    pub originals: Vec<String>,
+
+    /// Index (within `originals`) of the entry that was kept
+    pub kept: usize,
+}
+
+/// Report of what normalization and de-duplication changed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NormalizationReport {
+    /// URLs rewritten by normalization
+    pub rewritten: Vec<RewrittenUrl>,
+
+    /// Groups of duplicate entries collapsed into a single entry
+    pub collapsed: Vec<CollapsedUrl>,
+}
+
+impl NormalizationReport {
+    /// Number of entries removed as duplicates
+    pub fn removed_count(&self) -> usize {
+        self.collapsed.iter().map(|c| c.originals.len() - 1).sum()
+    }
+}
+
+/// Configurable URL normalization pipeline built on `url::Url`
+///
+/// Parsing through `url::Url` always lowercases the scheme and host, removes
+/// default ports and resolves `.`/`..` path segments. The remaining rules are
+/// configurable; by default only fragments are removed.
+///
+/// # Example
+/// ```
+/// use sitemap_generator::{DedupPolicy, SitemapBuilder, TrailingSlash, UrlEntry, UrlNormalizer};
+///
+/// let mut builder = SitemapBuilder::new();
+/// builder.add_url(UrlEntry::new("https://example.com/about/"));
+/// builder.add_url(UrlEntry::new("HTTP://Example.com:443/about#team"));
+///
+/// let normalizer = UrlNormalizer::new()
+///     .force_https(true)
+///     .trailing_slash(TrailingSlash::Remove);
+/// let report = builder.normalize_urls(&normalizer, DedupPolicy::KeepFirst);
+///
+/// assert_eq!(builder.len(), 1);
+/// assert_eq!(report.collapsed[0].loc, "https://example.com/about");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlNormalizer {
+    remove_fragment: bool,
+    force_https: bool,
+    trailing_slash: TrailingSlash,
+    sort_query: bool,
+    remove_empty_query: bool,
+}
+
+impl UrlNormalizer {
+    /// Create a normalizer with the default rules
+    pub fn new() -> Self {
+        Self {
+            remove_fragment: true,
+            force_https: false,
+            trailing_slash: TrailingSlash::Keep,
+            sort_query: false,
+            remove_empty_query: true,
+        }
+    }
+
+    /// Remove `#fragment`s (enabled by default)
+    pub fn remove_fragment(mut self, remove: bool) -> Self {
+        self.remove_fragment = remove;
+        self
+    }
+
+    /// Rewrite `http` URLs to `https` (disabled by default)
+    ///
+    /// An explicit port 443 becomes the default port and is dropped.
+    pub fn force_https(mut self, force: bool) -> Self {
+        self.force_https = force;
+        self
+    }
+
+    /// Set the trailing slash rule (default: [`TrailingSlash::Keep`])
+    pub fn trailing_slash(mut self, rule: TrailingSlash) -> Self {
+        self.trailing_slash = rule;
+        self
+    }
+
+    /// Sort query parameters by name (disabled by default)
+    pub fn sort_query(mut self, sort: bool) -> Self {
+        self.sort_query = sort;
+        self
+    }
+
+    /// Remove an empty `?` (enabled by default)
+    pub fn remove_empty_query(mut self, remove: bool) -> Self {
+        self.remove_empty_query = remove;
+        self
+    }
+
+    /// Normalize a single URL
+    ///
+    /// Returns `None` if the URL cannot be parsed.
+    pub fn normalize(&self, url: &str) -> Option<String> {
+        let mut url = Url::parse(url.trim()).ok()?;
+
+        if self.remove_fragment {
+            url.set_fragment(None);
+        }
+
+        if self.force_https && url.scheme() == "http" {
+            url.set_scheme("https").ok()?;
+            if url.port() == Some(443) {
+                url.set_port(None).ok()?;
+            }
+        }
+
+        if self.sort_query {
+            if let Some(query) = url.query() {
+                let mut pairs: Vec<&str> = query.split('&').filter(|p| !p.is_empty()).collect();
+                pairs.sort_by_key(|pair| pair.split('=').next().unwrap_or(""));
+                let sorted = pairs.join("&");
+                url.set_query(Some(&sorted));
+            }
+        }
+
+        if self.remove_empty_query && url.query() == Some("") {
+            url.set_query(None);
+        }
+
+        match self.trailing_slash {
+            TrailingSlash::Keep => {}
+            TrailingSlash::Add => {
+                let path = url.path();
+                let last = path.rsplit('/').next().unwrap_or("");
+                if !path.ends_with('/') && !last.contains('.') {
+                    let path = format!("{}/", path);
+                    url.set_path(&path);
+                }
+            }
+            TrailingSlash::Remove => {
+                let path = url.path();
+                if path.len() > 1 && path.ends_with('/') {
+                    let path = path.trim_end_matches('/').to_string();
+                    url.set_path(if path.is_empty() { "/" } else { &path });
+                }
+            }
+        }
+
+        Some(url.into())
+    }
+
+    /// Normalize the URLs of a list of entries and collapse duplicates
+    ///
+    /// Surviving entries stay at the position of the first entry in their
+    /// group. Entries whose URL cannot be parsed are left untouched and are
+    /// only de-duplicated against identical strings.
+    pub fn apply<T: AsUrlEntry>(&self, entries: Vec<T>, policy: DedupPolicy) -> (Vec<T>, NormalizationReport) {
+        let mut report = NormalizationReport::default();
+        let mut slots: Vec<Option<T>> = Vec::with_capacity(entries.len());
+        let mut groups: HashMap<String, (usize, CollapsedUrl)> = HashMap::new();
+        let mut group_order = Vec::new();
+
+        for mut entry in entries {
+            let original = entry.url_entry().loc.clone();
+            if let Some(normalized) = self.normalize(&original) {
+                if normalized != original {
+                    report.rewritten.push(RewrittenUrl {
+                        original: original.clone(),
+                        normalized: normalized.clone(),
+                    });
+                    entry.url_entry_mut().loc = normalized;
+                }
+            }
+
+            let loc = entry.url_entry().loc.clone();
+            match groups.get_mut(&loc) {
+                None => {
+                    groups.insert(
+                        loc.clone(),
+                        (
+                            slots.len(),
+                            CollapsedUrl {
+                                loc: loc.clone(),
+                                originals: vec![original],
+                                kept: 0,
+                            },
+                        ),
+                    );
+                    group_order.push(loc);
+                    slots.push(Some(entry));
+                }
+                Some((slot, group)) => {
+                    group.originals.push(original);
+                    let kept = slots[*slot].as_ref().map(|e| e.url_entry());
+                    let replace = match policy {
+                        DedupPolicy::KeepFirst => false,
+                        DedupPolicy::KeepLast => true,
+                        DedupPolicy::NewestLastmod => is_newer(
+                            entry.url_entry().lastmod.as_deref(),
+                            kept.and_then(|k| k.lastmod.as_deref()),
+                        ),
+                    };
+                    if replace {
+                        group.kept = group.originals.len() - 1;
+                        slots[*slot] = Some(entry);
+                    }
+                }
+            }
+        }
+
+        for loc in group_order {
+            if let Some((_, group)) = groups.remove(&loc) {
+                if group.originals.len() > 1 {
+                    report.collapsed.push(group);
+                }
+            }
+        }
+
+        (slots.into_iter().flatten().collect(), report)
+    }
+}
+
+impl Default for UrlNormalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compare two `lastmod` values; unparseable or missing dates count as oldest
+fn is_newer(candidate: Option<&str>, current: Option<&str>) -> bool {
+    let candidate = candidate.and_then(parse_w3c_datetime);
+    let current = current.and_then(parse_w3c_datetime);
+    match (candidate, current) {
+        (Some(candidate), Some(current)) => candidate > current,
+        (Some(_), None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::UrlEntry;
+
+    #[test]
+    fn test_normalize_rules() {
+        let default = UrlNormalizer::new();
+        assert_eq!(
+            default.normalize("HTTPS://Example.COM:443/a/./b/../c?#top").as_deref(),
+            Some("https://example.com/a/c")
+        );
+        assert_eq!(default.normalize("not a url"), None);
+
+        let strict = UrlNormalizer::new()
+            .force_https(true)
+            .sort_query(true)
+            .trailing_slash(TrailingSlash::Add);
+        assert_eq!(
+            strict.normalize("http://example.com:443/docs?b=2&a=1").as_deref(),
+            Some("https://example.com/docs/?a=1&b=2")
+        );
+        assert_eq!(
+            strict.normalize("http://example.com/file.pdf").as_deref(),
+            Some("https://example.com/file.pdf")
+        );
+
+        let remove = UrlNormalizer::new().trailing_slash(TrailingSlash::Remove);
+        assert_eq!(remove.normalize("https://example.com/").as_deref(), Some("https://example.com/"));
+        assert_eq!(remove.normalize("https://example.com/a//").as_deref(), Some("https://example.com/a"));
+    }
+
+    #[test]
+    fn test_dedup_policies() {
+        let entries = || {
+            vec![
+                UrlEntry::new("https://example.com/a").lastmod("2025-11-02"),
+                UrlEntry::new("https://example.com/b"),
+                UrlEntry::new("https://example.com/a#x").lastmod("2025-11-03T00:00:00+09:00"),
+                UrlEntry::new("https://example.com/a").lastmod("2025-11-01"),
+            ]
+        };
+        let normalizer = UrlNormalizer::new();
+
+        let (kept, report) = normalizer.apply(entries(), DedupPolicy::KeepFirst);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].lastmod.as_deref(), Some("2025-11-02"));
+        assert_eq!(kept[1].loc, "https://example.com/b");
+        assert_eq!(report.removed_count(), 2);
+        assert_eq!(report.rewritten.len(), 1);
+        assert_eq!(
+            report.collapsed[0].originals,
+            vec![
+                "https://example.com/a",
+                "https://example.com/a#x",
+                "https://example.com/a",
+            ]
+        );
+
+        let (kept, report) = normalizer.apply(entries(), DedupPolicy::KeepLast);
+        assert_eq!(kept[0].lastmod.as_deref(), Some("2025-11-01"));
+        assert_eq!(report.collapsed[0].kept, 2);
+
+        // 2025-11-03T00:00+09:00 is 2025-11-02T15:00Z, still newer than 2025-11-02
+        let (kept, report) = normalizer.apply(entries(), DedupPolicy::NewestLastmod);
+        assert_eq!(kept[0].lastmod.as_deref(), Some("2025-11-03T00:00:00+09:00"));
+        assert_eq!(report.collapsed[0].kept, 1);
+    }
+}
@@ -1,136 +1,632 @@
 //! Parser for reading sitemap XML files
 
-use crate::error::{Error, Result};
+use crate::error::{Error, IoContext, Result};
+use crate::extension::{is_builtin_namespace, ExtensionElement};
 use crate::types::*;
+use crate::validator::{MAX_SIZE_BYTES, MAX_URLS};
+use crate::writer::IMAGE_NS;
 use flate2::read::GzDecoder;
-use quick_xml::events::Event;
+use quick_xml::errors::IllFormedError;
+use quick_xml::events::{BytesStart, Event};
+use percent_encoding::percent_decode;
+use quick_xml::name::ResolveResult;
+use quick_xml::reader::NsReader;
 use quick_xml::Reader;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::fmt;
+use std::io::{self, BufReader, Read};
 use std::path::Path;
+use url::{Position, Url};
+
+/// Default maximum length of the text of a single element (in bytes)
+pub const DEFAULT_MAX_TEXT_LEN: usize = 8192;
+
+/// Limits applied while parsing untrusted sitemaps
+///
+/// The defaults follow the protocol: 50MB of (decompressed) XML and 50,000
+/// entries per file. DOCTYPE declarations are rejected, since sitemaps never
+/// need them and they are the vehicle for entity expansion attacks.
+///
+/// # Example
+/// ```
+/// use sitemap_generator::{Error, ParseLimits, SitemapParser};
+///
+/// let xml = r#"<urlset><url><loc>https://example.com/1</loc></url>
+///     <url><loc>https://example.com/2</loc></url></urlset>"#;
+///
+/// let limits = ParseLimits::default().max_entries(1);
+/// let result = SitemapParser::parse_string_with_limits(xml, &limits);
+/// assert!(matches!(result, Err(Error::TooManyEntries { actual: 2, limit: 1 })));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLimits {
+    max_bytes: usize,
+    max_entries: usize,
+    max_text_len: usize,
+    allow_doctype: bool,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: MAX_SIZE_BYTES,
+            max_entries: MAX_URLS,
+            max_text_len: DEFAULT_MAX_TEXT_LEN,
+            allow_doctype: false,
+        }
+    }
+}
+
+impl ParseLimits {
+    /// Create limits with the protocol defaults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of (decompressed) bytes to read
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Set the maximum number of `<url>` or `<sitemap>` entries
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Set the maximum length of the text of a single element (in bytes)
+    pub fn max_text_len(mut self, max_text_len: usize) -> Self {
+        self.max_text_len = max_text_len;
+        self
+    }
+
+    /// Accept documents with a DOCTYPE declaration
+    ///
+    /// Entities declared in the DOCTYPE are never expanded.
+    pub fn allow_doctype(mut self, allow: bool) -> Self {
+        self.allow_doctype = allow;
+        self
+    }
+}
+
+/// Reader that fails once more than `limit` bytes have been read
+struct LimitedReader<R> {
+    inner: R,
+    remaining: usize,
+    exceeded: bool,
+    read: usize,
+    /// Offsets of every newline read so far, when line numbers are needed
+    newlines: Option<Vec<usize>>,
+}
+
+impl<R: Read> LimitedReader<R> {
+    fn new(inner: R, limit: usize) -> Self {
+        Self {
+            inner,
+            remaining: limit,
+            exceeded: false,
+            read: 0,
+            newlines: None,
+        }
+    }
+
+    fn track_lines(mut self) -> Self {
+        self.newlines = Some(Vec::new());
+        self
+    }
+
+    /// Convert a byte offset to a 1-based line and byte column
+    fn locate(&self, offset: usize) -> (usize, usize) {
+        let newlines = self.newlines.as_deref().unwrap_or_default();
+        let line = newlines.partition_point(|&newline| newline < offset);
+        let line_start = if line == 0 { 0 } else { newlines[line - 1] + 1 };
+        (line + 1, offset - line_start + 1)
+    }
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // Allow one byte past the limit so a document of exactly `limit` bytes still reaches EOF
+        let max = buf.len().min(self.remaining.saturating_add(1));
+        let n = self.inner.read(&mut buf[..max])?;
+        if n > self.remaining {
+            self.exceeded = true;
+            self.read += n;
+            return Err(io::Error::other("document size limit exceeded"));
+        }
+        if let Some(ref mut newlines) = self.newlines {
+            let start = self.read;
+            newlines.extend(buf[..n].iter().enumerate().filter(|(_, b)| **b == b'\n').map(|(i, _)| start + i));
+        }
+        self.remaining -= n;
+        self.read += n;
+        Ok(n)
+    }
+}
+
+/// A problem skipped over by lenient parsing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIssue {
+    /// Byte offset of the element (after decompression)
+    pub offset: usize,
+
+    /// Line of the element (1-based)
+    pub line: usize,
+
+    /// Column of the element, in bytes (1-based)
+    pub column: usize,
+
+    /// Name of the element the problem was found in
+    pub element: String,
+
+    /// Description of the problem
+    pub message: String,
+}
+
+impl fmt::Display for ParseIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: <{}>: {}", self.line, self.column, self.element, self.message)
+    }
+}
+
+/// Entries recovered by lenient parsing, with the problems that were skipped
+#[derive(Debug, Clone, Default)]
+pub struct LenientParse {
+    /// Entries that could be read
+    pub entries: Vec<UrlEntry>,
+
+    /// Problems found, in document order
+    pub issues: Vec<ParseIssue>,
+}
 
 /// Parser for sitemap XML files
+///
+/// All functions apply [`ParseLimits::default()`]; use the `_with_limits`
+/// variants to configure them.
 pub struct SitemapParser;
 
 impl SitemapParser {
     /// Parse a sitemap from a string
     pub fn parse_string(xml: &str) -> Result<Vec<UrlEntry>> {
-        Self::parse_reader(xml.as_bytes())
+        Self::parse_string_with_limits(xml, &ParseLimits::default())
     }
 
     /// Parse a sitemap from a file
     pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Vec<UrlEntry>> {
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-        Self::parse_reader(reader)
+        Self::parse_file_with_limits(path, &ParseLimits::default())
     }
 
     /// Parse a compressed sitemap from a file
     pub fn parse_compressed<P: AsRef<Path>>(path: P) -> Result<Vec<UrlEntry>> {
-        let file = File::open(path)?;
-        let decoder = GzDecoder::new(file);
-        let reader = BufReader::new(decoder);
-        Self::parse_reader(reader)
+        Self::parse_compressed_with_limits(path, &ParseLimits::default())
+    }
+
+    /// Parse a sitemap from a string with custom limits
+    pub fn parse_string_with_limits(xml: &str, limits: &ParseLimits) -> Result<Vec<UrlEntry>> {
+        Self::parse_reader(xml.as_bytes(), limits)
+    }
+
+    /// Parse a sitemap from a file with custom limits
+    pub fn parse_file_with_limits<P: AsRef<Path>>(path: P, limits: &ParseLimits) -> Result<Vec<UrlEntry>> {
+        let file = File::open(&path).with_path(&path)?;
+        Self::parse_reader(file, limits)
+    }
+
+    /// Parse a compressed sitemap from a file with custom limits
+    ///
+    /// The size limit applies to the decompressed data.
+    pub fn parse_compressed_with_limits<P: AsRef<Path>>(path: P, limits: &ParseLimits) -> Result<Vec<UrlEntry>> {
+        let file = File::open(&path).with_path(&path)?;
+        Self::parse_reader(GzDecoder::new(file), limits)
+    }
+
+    /// Parse an image sitemap from a string
+    ///
+    /// Reads the `loc`, `caption`, `title` and `license` of each `<image:image>`.
+    pub fn parse_image_string(xml: &str) -> Result<Vec<UrlWithImages>> {
+        Self::parse_image_reader(xml.as_bytes(), &ParseLimits::default())
+    }
+
+    /// Parse an image sitemap from a file
+    pub fn parse_image_file<P: AsRef<Path>>(path: P) -> Result<Vec<UrlWithImages>> {
+        let file = File::open(&path).with_path(&path)?;
+        Self::parse_image_reader(file, &ParseLimits::default())
+    }
+
+    /// Parse a compressed image sitemap from a file
+    pub fn parse_image_compressed<P: AsRef<Path>>(path: P) -> Result<Vec<UrlWithImages>> {
+        let file = File::open(&path).with_path(&path)?;
+        Self::parse_image_reader(GzDecoder::new(file), &ParseLimits::default())
+    }
+
+    /// Parse a sitemap from a string, skipping over problems
+    ///
+    /// Invalid values are dropped from their entry, entries without a `<loc>`
+    /// are dropped, `<changefreq>` is matched case-insensitively, and parsing
+    /// stops at malformed or truncated XML keeping the entries read so far.
+    /// Each problem is recorded as a [`ParseIssue`]. Breaching a
+    /// [`ParseLimits`] limit still fails the whole parse.
+    ///
+    /// # Example
+    /// ```
+    /// use sitemap_generator::{ChangeFreq, SitemapParser};
+    ///
+    /// let xml = r#"<urlset>
+    ///   <url><loc>https://example.com/</loc><changefreq>Weekly</changefreq></url>
+    ///   <url><loc>https://example.com/about</loc><priority>high</priority></url>
+    /// </urlset>"#;
+    ///
+    /// let parsed = SitemapParser::parse_string_lenient(xml).unwrap();
+    /// assert_eq!(parsed.entries.len(), 2);
+    /// assert_eq!(parsed.entries[0].changefreq, Some(ChangeFreq::Weekly));
+    /// assert_eq!(parsed.entries[1].priority, None);
+    /// assert_eq!(parsed.issues[0].to_string(), "3:44: <priority>: invalid priority \"high\"");
+    /// ```
+    pub fn parse_string_lenient(xml: &str) -> Result<LenientParse> {
+        Self::parse_string_lenient_with_limits(xml, &ParseLimits::default())
+    }
+
+    /// Parse a sitemap from a file, skipping over problems
+    pub fn parse_file_lenient<P: AsRef<Path>>(path: P) -> Result<LenientParse> {
+        Self::parse_file_lenient_with_limits(path, &ParseLimits::default())
+    }
+
+    /// Parse a compressed sitemap from a file, skipping over problems
+    ///
+    /// A truncated gzip stream is recorded as an issue, keeping the entries
+    /// decompressed before the cut.
+    pub fn parse_compressed_lenient<P: AsRef<Path>>(path: P) -> Result<LenientParse> {
+        Self::parse_compressed_lenient_with_limits(path, &ParseLimits::default())
+    }
+
+    /// Parse a sitemap from a string with custom limits, skipping over problems
+    pub fn parse_string_lenient_with_limits(xml: &str, limits: &ParseLimits) -> Result<LenientParse> {
+        Self::parse_reader_lenient(xml.as_bytes(), limits)
+    }
+
+    /// Parse a sitemap from a file with custom limits, skipping over problems
+    pub fn parse_file_lenient_with_limits<P: AsRef<Path>>(path: P, limits: &ParseLimits) -> Result<LenientParse> {
+        let file = File::open(&path).with_path(&path)?;
+        Self::parse_reader_lenient(file, limits)
+    }
+
+    /// Parse a compressed sitemap from a file with custom limits, skipping over problems
+    pub fn parse_compressed_lenient_with_limits<P: AsRef<Path>>(
+        path: P,
+        limits: &ParseLimits,
+    ) -> Result<LenientParse> {
+        let file = File::open(&path).with_path(&path)?;
+        Self::parse_reader_lenient(GzDecoder::new(file), limits)
+    }
+
+    /// Convert a URL to its human-readable display form
+    ///
+    /// Reverses [`UrlEncoding::Normalized`](crate::writer::UrlEncoding::Normalized):
+    /// punycoded hostnames are converted back to unicode and percent-encoded
+    /// UTF-8 sequences are decoded. Encoded ASCII (such as `%2F` or `%20`) is
+    /// kept so the URL still means the same thing.
+    ///
+    /// # Example
+    /// ```
+    /// use sitemap_generator::SitemapParser;
+    ///
+    /// assert_eq!(
+    ///     SitemapParser::display_url("https://xn--bcher-kva.de/tin-t%E1%BB%A9c?q=a%26b"),
+    ///     "https://bücher.de/tin-tức?q=a%26b"
+    /// );
+    /// ```
+    pub fn display_url(url: &str) -> String {
+        let parsed = match Url::parse(url) {
+            Ok(parsed) => parsed,
+            Err(_) => return url.to_string(),
+        };
+
+        let host = match parsed.domain() {
+            Some(domain) => idna::domain_to_unicode(domain).0,
+            None => parsed[Position::BeforeHost..Position::AfterHost].to_string(),
+        };
+
+        format!(
+            "{}{}{}",
+            &parsed[..Position::BeforeHost],
+            host,
+            decode_non_ascii(&parsed[Position::AfterHost..])
+        )
+    }
+
+    /// Convert the `loc` of parsed entries to their display form
+    pub fn decode_urls(entries: &mut [UrlEntry]) {
+        for entry in entries {
+            entry.loc = Self::display_url(&entry.loc);
+        }
     }
 
     /// Parse a sitemap from a reader
-    fn parse_reader<R: BufRead>(reader: R) -> Result<Vec<UrlEntry>> {
-        let mut xml_reader = Reader::from_reader(reader);
+    fn parse_reader<R: Read>(reader: R, limits: &ParseLimits) -> Result<Vec<UrlEntry>> {
+        let entries = Self::parse_urlset(LimitedReader::new(reader, limits.max_bytes), limits, None)?;
+        Ok(entries.into_iter().map(|entry| entry.url).collect())
+    }
+
+    /// Parse an image sitemap from a reader
+    fn parse_image_reader<R: Read>(reader: R, limits: &ParseLimits) -> Result<Vec<UrlWithImages>> {
+        Self::parse_urlset(LimitedReader::new(reader, limits.max_bytes), limits, None)
+    }
+
+    /// Parse a sitemap from a reader, recording problems instead of failing
+    fn parse_reader_lenient<R: Read>(reader: R, limits: &ParseLimits) -> Result<LenientParse> {
+        let mut issues = Vec::new();
+        let reader = LimitedReader::new(reader, limits.max_bytes).track_lines();
+        let entries = Self::parse_urlset(reader, limits, Some(&mut issues))?;
+        let entries = entries.into_iter().map(|entry| entry.url).collect();
+        Ok(LenientParse { entries, issues })
+    }
+
+    /// Parse a `<urlset>`, failing on the first problem unless `issues` is given
+    ///
+    /// Children of `<url>` in namespaces other than the sitemap, image, video
+    /// and news ones are kept as [`UrlEntry::extensions`].
+    fn parse_urlset<R: Read>(
+        reader: LimitedReader<R>,
+        limits: &ParseLimits,
+        mut issues: Option<&mut Vec<ParseIssue>>,
+    ) -> Result<Vec<UrlWithImages>> {
+        let mut xml_reader = NsReader::from_reader(BufReader::new(reader));
         xml_reader.config_mut().trim_text(true);
 
         let mut entries = Vec::new();
         let mut current_url: Option<UrlEntry> = None;
+        // Open elements inside the current <url>, and the extension elements being read
+        let mut depth = 0;
+        let mut extensions: Vec<ExtensionElement> = Vec::new();
+        // Images of the current <url>, and the <image:image> child being read
+        let mut images: Vec<ImageEntry> = Vec::new();
+        let mut current_image: Option<ImageEntry> = None;
+        let mut image_field: Option<String> = None;
         let mut current_element = String::new();
+        let mut url_offset = 0;
+        let mut element_offset = 0;
         let mut buf = Vec::new();
 
+        let lenient = issues.is_some();
+
+        // Record a problem in lenient mode, or fail with the strict error
+        let mut report = |reader: &LimitedReader<R>, offset: usize, element: &str, message: String, error: Error| {
+            match issues.as_deref_mut() {
+                Some(issues) => {
+                    let (line, column) = reader.locate(offset);
+                    issues.push(ParseIssue {
+                        offset,
+                        line,
+                        column,
+                        element: element.to_string(),
+                        message,
+                    });
+                    Ok(())
+                }
+                None => Err(error),
+            }
+        };
+
         loop {
-            match xml_reader.read_event_into(&mut buf) {
+            let (namespace, event) = match xml_reader.read_resolved_event_into(&mut buf) {
+                Ok((ResolveResult::Bound(ns), event)) => {
+                    (Some(String::from_utf8_lossy(ns.as_ref()).into_owned()), Ok(event))
+                }
+                Ok((_, event)) => (None, Ok(event)),
+                Err(err) => (None, Err(err)),
+            };
+            // Start an extension element for a custom-namespaced child of <url>
+            let extension = |e: &BytesStart, extensions: &[ExtensionElement], depth: usize| {
+                let custom = namespace.as_deref().is_some_and(|ns| !is_builtin_namespace(ns));
+                let inside = !extensions.is_empty() || (depth == 0 && custom);
+                inside.then(|| extension_element(e, namespace.clone().unwrap_or_default()))
+            };
+
+            match event {
+                Ok(Event::Start(ref e)) if current_url.is_some() => {
+                    if let Some(element) = extension(e, &extensions, depth) {
+                        extensions.push(element);
+                    } else if namespace.as_deref() == Some(IMAGE_NS) {
+                        let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                        match depth {
+                            0 if name == "image" => current_image = Some(ImageEntry::new(String::new())),
+                            1 if current_image.is_some() => image_field = Some(name),
+                            _ => {}
+                        }
+                    }
+                    depth += 1;
+                    element_offset = xml_reader.buffer_position() as usize - (e.len() + 2);
+                    current_element = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                }
+                Ok(Event::Empty(ref e)) if current_url.is_some() => {
+                    if let Some(element) = extension(e, &extensions, depth) {
+                        attach_extension(element, &mut extensions, current_url.as_mut());
+                    }
+                }
                 Ok(Event::Start(ref e)) => {
                     let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                    element_offset = xml_reader.buffer_position() as usize - (e.len() + 2);
                     if name == "url" {
-                        current_url = Some(UrlEntry {
-                            loc: String::new(),
-                            lastmod: None,
-                            changefreq: None,
-                            priority: None,
-                        });
+                        url_offset = element_offset;
+                        depth = 0;
+                        current_url = Some(UrlEntry::new(String::new()));
+                        images.clear();
                     }
                     current_element = name;
                 }
                 Ok(Event::Text(e)) => {
-                    let text = e.unescape().map_err(|e| Error::Xml(e.to_string()))?;
-                    let text = text.to_string();
+                    if e.len() > limits.max_text_len {
+                        return Err(Error::TextTooLong {
+                            element: current_element,
+                            actual: e.len(),
+                            limit: limits.max_text_len,
+                        });
+                    }
+                    let text = match e.unescape() {
+                        Ok(text) => Some(text.to_string()),
+                        Err(err) => {
+                            let reader = xml_reader.get_ref().get_ref();
+                            let message = err.to_string();
+                            report(reader, element_offset, &current_element, message, Error::Xml(err))?;
+                            None
+                        }
+                    };
 
-                    if let Some(ref mut url) = current_url {
+                    if let (Some(text), Some(element)) = (text.as_ref(), extensions.last_mut()) {
+                        element.text.get_or_insert_with(String::new).push_str(text);
+                    } else if let (Some(text), Some(image), Some(field)) =
+                        (text.as_ref(), current_image.as_mut(), image_field.as_deref())
+                    {
+                        match field {
+                            "loc" => image.loc = text.clone(),
+                            "caption" => image.caption = Some(text.clone()),
+                            "title" => image.title = Some(text.clone()),
+                            "license" => image.license = Some(text.clone()),
+                            _ => {}
+                        }
+                    } else if let (Some(text), Some(url)) = (text, current_url.as_mut()) {
+                        let reader = xml_reader.get_ref().get_ref();
                         match current_element.as_str() {
                             "loc" => url.loc = text,
                             "lastmod" => url.lastmod = Some(text),
                             "changefreq" => {
-                                url.changefreq = Some(match text.as_str() {
-                                    "always" => ChangeFreq::Always,
-                                    "hourly" => ChangeFreq::Hourly,
-                                    "daily" => ChangeFreq::Daily,
-                                    "weekly" => ChangeFreq::Weekly,
-                                    "monthly" => ChangeFreq::Monthly,
-                                    "yearly" => ChangeFreq::Yearly,
-                                    "never" => ChangeFreq::Never,
-                                    _ => {
-                                        return Err(Error::InvalidChangeFreq(text));
+                                let parsed = if lenient {
+                                    text.to_ascii_lowercase().parse::<ChangeFreq>()
+                                } else {
+                                    text.parse::<ChangeFreq>()
+                                };
+                                match parsed {
+                                    Ok(changefreq) => url.changefreq = Some(changefreq),
+                                    Err(err) => {
+                                        let message = format!("invalid changefreq \"{}\"", text);
+                                        report(reader, element_offset, &current_element, message, err)?;
                                     }
-                                });
-                            }
-                            "priority" => {
-                                url.priority = Some(
-                                    text.parse::<f32>()
-                                        .map_err(|_| Error::Validation(format!("Invalid priority: {}", text)))?,
-                                );
+                                }
                             }
+                            "priority" => match text.parse::<f32>() {
+                                Ok(priority) => url.priority = Some(priority),
+                                Err(_) => {
+                                    let message = format!("invalid priority \"{}\"", text);
+                                    let error = Error::InvalidField {
+                                        field: "priority",
+                                        value: text.clone(),
+                                        reason: "must be a number",
+                                    };
+                                    report(reader, element_offset, &current_element, message, error)?;
+                                }
+                            },
                             _ => {}
                         }
                     }
                 }
+                Ok(Event::End(_)) if depth > 0 => {
+                    depth -= 1;
+                    if let Some(element) = extensions.pop() {
+                        attach_extension(element, &mut extensions, current_url.as_mut());
+                    } else if depth == 1 {
+                        image_field = None;
+                    } else if let (0, Some(image)) = (depth, current_image.take()) {
+                        images.push(image);
+                    }
+                }
                 Ok(Event::End(ref e)) => {
                     let name = e.name();
                     let name_str = String::from_utf8_lossy(name.as_ref());
                     if name_str == "url" {
                         if let Some(url) = current_url.take() {
-                            entries.push(url);
+                            if lenient && url.loc.is_empty() {
+                                let reader = xml_reader.get_ref().get_ref();
+                                let message = "missing <loc>, entry skipped".to_string();
+                                report(reader, url_offset, "url", message, Error::InvalidUrl { url: String::new() })?;
+                            } else {
+                                if entries.len() == limits.max_entries {
+                                    return Err(Error::TooManyEntries {
+                                        actual: entries.len() + 1,
+                                        limit: limits.max_entries,
+                                    });
+                                }
+                                entries.push(UrlWithImages {
+                                    url,
+                                    images: std::mem::take(&mut images),
+                                });
+                            }
                         }
                     }
                 }
+                Ok(Event::DocType(_)) if !limits.allow_doctype => return Err(Error::DoctypeNotAllowed),
                 Ok(Event::Eof) => break,
-                Err(e) => return Err(Error::Xml(e.to_string())),
+                Err(_) if xml_reader.get_ref().get_ref().exceeded => {
+                    return Err(Error::DocumentTooLarge {
+                        actual: xml_reader.get_ref().get_ref().read,
+                        limit: limits.max_bytes,
+                    });
+                }
+                Err(e) => {
+                    let reader = xml_reader.get_ref().get_ref();
+                    let offset = xml_reader.error_position() as usize;
+                    let message = format!("stopped reading: {}", e);
+                    report(reader, offset, &current_element, message, Error::Xml(e))?;
+                    break;
+                }
                 _ => {}
             }
             buf.clear();
         }
 
+        if lenient && current_url.is_some() {
+            let reader = xml_reader.get_ref().get_ref();
+            let message = "document ends inside <url>, entry skipped".to_string();
+            let error = Error::Xml(IllFormedError::MissingEndTag("url".to_string()).into());
+            report(reader, url_offset, "url", message, error)?;
+        }
+
         Ok(entries)
     }
 
     /// Parse a sitemap index from a string
     pub fn parse_index_string(xml: &str) -> Result<Vec<SitemapIndexEntry>> {
-        Self::parse_index_reader(xml.as_bytes())
+        Self::parse_index_string_with_limits(xml, &ParseLimits::default())
     }
 
     /// Parse a sitemap index from a file
     pub fn parse_index_file<P: AsRef<Path>>(path: P) -> Result<Vec<SitemapIndexEntry>> {
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-        Self::parse_index_reader(reader)
+        Self::parse_index_file_with_limits(path, &ParseLimits::default())
     }
 
     /// Parse a compressed sitemap index from a file
     pub fn parse_index_compressed<P: AsRef<Path>>(path: P) -> Result<Vec<SitemapIndexEntry>> {
-        let file = File::open(path)?;
-        let decoder = GzDecoder::new(file);
-        let reader = BufReader::new(decoder);
-        Self::parse_index_reader(reader)
+        Self::parse_index_compressed_with_limits(path, &ParseLimits::default())
+    }
+
+    /// Parse a sitemap index from a string with custom limits
+    pub fn parse_index_string_with_limits(xml: &str, limits: &ParseLimits) -> Result<Vec<SitemapIndexEntry>> {
+        Self::parse_index_reader(xml.as_bytes(), limits)
+    }
+
+    /// Parse a sitemap index from a file with custom limits
+    pub fn parse_index_file_with_limits<P: AsRef<Path>>(
+        path: P,
+        limits: &ParseLimits,
+    ) -> Result<Vec<SitemapIndexEntry>> {
+        let file = File::open(&path).with_path(&path)?;
+        Self::parse_index_reader(file, limits)
+    }
+
+    /// Parse a compressed sitemap index from a file with custom limits
+    ///
+    /// The size limit applies to the decompressed data.
+    pub fn parse_index_compressed_with_limits<P: AsRef<Path>>(
+        path: P,
+        limits: &ParseLimits,
+    ) -> Result<Vec<SitemapIndexEntry>> {
+        let file = File::open(&path).with_path(&path)?;
+        Self::parse_index_reader(GzDecoder::new(file), limits)
     }
 
     /// Parse a sitemap index from a reader
-    fn parse_index_reader<R: BufRead>(reader: R) -> Result<Vec<SitemapIndexEntry>> {
-        let mut xml_reader = Reader::from_reader(reader);
+    fn parse_index_reader<R: Read>(reader: R, limits: &ParseLimits) -> Result<Vec<SitemapIndexEntry>> {
+        let mut xml_reader = Reader::from_reader(BufReader::new(LimitedReader::new(reader, limits.max_bytes)));
         xml_reader.config_mut().trim_text(true);
 
         let mut entries = Vec::new();
@@ -151,7 +647,14 @@ impl SitemapParser {
                     current_element = name;
                 }
                 Ok(Event::Text(e)) => {
-                    let text = e.unescape().map_err(|e| Error::Xml(e.to_string()))?;
+                    if e.len() > limits.max_text_len {
+                        return Err(Error::TextTooLong {
+                            element: current_element,
+                            actual: e.len(),
+                            limit: limits.max_text_len,
+                        });
+                    }
+                    let text = e.unescape()?;
                     let text = text.to_string();
 
                     if let Some(ref mut sitemap) = current_sitemap {
@@ -167,12 +670,25 @@ impl SitemapParser {
                     let name_str = String::from_utf8_lossy(name.as_ref());
                     if name_str == "sitemap" {
                         if let Some(sitemap) = current_sitemap.take() {
+                            if entries.len() == limits.max_entries {
+                                return Err(Error::TooManyEntries {
+                                    actual: entries.len() + 1,
+                                    limit: limits.max_entries,
+                                });
+                            }
                             entries.push(sitemap);
                         }
                     }
                 }
+                Ok(Event::DocType(_)) if !limits.allow_doctype => return Err(Error::DoctypeNotAllowed),
                 Ok(Event::Eof) => break,
-                Err(e) => return Err(Error::Xml(e.to_string())),
+                Err(_) if xml_reader.get_ref().get_ref().exceeded => {
+                    return Err(Error::DocumentTooLarge {
+                        actual: xml_reader.get_ref().get_ref().read,
+                        limit: limits.max_bytes,
+                    });
+                }
+                Err(e) => return Err(Error::Xml(e)),
                 _ => {}
             }
             buf.clear();
@@ -182,6 +698,69 @@ impl SitemapParser {
     }
 }
 
+/// Read the local name and attributes of an extension element
+fn extension_element(e: &BytesStart, namespace: String) -> ExtensionElement {
+    let mut element = ExtensionElement::new(namespace, String::from_utf8_lossy(e.local_name().as_ref()));
+    for attribute in e.attributes().flatten() {
+        if attribute.key.as_namespace_binding().is_some() {
+            continue;
+        }
+        let value = match attribute.unescape_value() {
+            Ok(value) => value.into_owned(),
+            Err(_) => String::from_utf8_lossy(&attribute.value).into_owned(),
+        };
+        let name = String::from_utf8_lossy(attribute.key.local_name().as_ref()).into_owned();
+        element.attributes.push((name, value));
+    }
+    element
+}
+
+/// Add a finished extension element to its parent, or to the URL entry
+fn attach_extension(element: ExtensionElement, open: &mut [ExtensionElement], url: Option<&mut UrlEntry>) {
+    match open.last_mut() {
+        Some(parent) => parent.children.push(element),
+        None => {
+            if let Some(url) = url {
+                url.extensions.push(element);
+            }
+        }
+    }
+}
+
+/// Decode runs of percent-encoded bytes that form non-ASCII UTF-8 characters
+fn decode_non_ascii(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        // Collect a run of %XX escapes with the high bit set
+        let start = i;
+        while i + 2 < bytes.len()
+            && bytes[i] == b'%'
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit()
+            && bytes[i + 1] >= b'8'
+        {
+            i += 3;
+        }
+
+        if i > start {
+            let run = &text[start..i];
+            match percent_decode(run.as_bytes()).decode_utf8() {
+                Ok(decoded) => out.push_str(&decoded),
+                Err(_) => out.push_str(run),
+            }
+        } else {
+            let ch = text[i..].chars().next().unwrap_or_default();
+            out.push(ch);
+            i += ch.len_utf8();
+        }
+    }
+
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -215,6 +794,71 @@ mod tests {
         assert_eq!(entries[1].priority, Some(0.8));
     }
 
+    #[test]
+    fn test_parse_extensions() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+        xmlns:image="http://www.google.com/schemas/sitemap-image/1.1"
+        xmlns:pm="http://www.google.com/schemas/sitemap-pagemap/1.0">
+  <url>
+    <loc>https://example.com/</loc>
+    <image:image><image:loc>https://example.com/a.jpg</image:loc></image:image>
+    <pm:PageMap>
+      <pm:DataObject type="document">
+        <pm:Attribute name="author">Jane &amp; John</pm:Attribute>
+        <pm:Attribute name="draft"/>
+      </pm:DataObject>
+    </pm:PageMap>
+    <acme:tag xmlns:acme="urn:acme">internal</acme:tag>
+    <lastmod>2025-11-01</lastmod>
+  </url>
+</urlset>"#;
+
+        let entries = SitemapParser::parse_string(xml).unwrap();
+        assert_eq!(entries[0].lastmod.as_deref(), Some("2025-11-01"));
+
+        let extensions = &entries[0].extensions;
+        assert_eq!(extensions.len(), 2);
+        assert_eq!(extensions[0].namespace, "http://www.google.com/schemas/sitemap-pagemap/1.0");
+        assert_eq!(extensions[0].name, "PageMap");
+
+        let data = &extensions[0].children[0];
+        assert_eq!(data.attributes, vec![("type".to_string(), "document".to_string())]);
+        assert_eq!(data.children[0].text.as_deref(), Some("Jane & John"));
+        assert_eq!(data.children[1].text, None);
+        assert_eq!(data.children[1].attributes[0].1, "draft");
+
+        assert_eq!(extensions[1].namespace, "urn:acme");
+        assert_eq!(extensions[1].text.as_deref(), Some("internal"));
+    }
+
+    #[test]
+    fn test_parse_images() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+        xmlns:image="http://www.google.com/schemas/sitemap-image/1.1">
+  <url>
+    <loc>https://example.com/gallery</loc>
+    <image:image>
+      <image:loc>https://example.com/a.jpg</image:loc>
+      <image:title>Sunset</image:title>
+    </image:image>
+    <image:image><image:loc>https://example.com/b.jpg</image:loc></image:image>
+  </url>
+  <url><loc>https://example.com/</loc></url>
+</urlset>"#;
+
+        let entries = SitemapParser::parse_image_string(xml).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].url.loc, "https://example.com/gallery");
+        let locs: Vec<&str> = entries[0].images.iter().map(|i| i.loc.as_str()).collect();
+        assert_eq!(locs, vec!["https://example.com/a.jpg", "https://example.com/b.jpg"]);
+        assert_eq!(entries[0].images[0].title.as_deref(), Some("Sunset"));
+        assert!(entries[1].images.is_empty());
+
+        assert_eq!(SitemapParser::parse_string(xml).unwrap()[0].loc, "https://example.com/gallery");
+    }
+
     #[test]
     fn test_parse_sitemap_index() {
         let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -237,4 +881,75 @@ mod tests {
         assert_eq!(entries[1].loc, "https://example.com/sitemap2.xml");
         assert_eq!(entries[1].lastmod, Some("2025-11-02".to_string()));
     }
+
+    #[test]
+    fn test_display_url() {
+        assert_eq!(
+            SitemapParser::display_url("https://xn--bcher-kva.de/b%C3%BCcher/a%20b%2Fc#t%C3%AAn"),
+            "https://bücher.de/bücher/a%20b%2Fc#tên"
+        );
+        assert_eq!(SitemapParser::display_url("https://[::1]:8080/%E1%BB"), "https://[::1]:8080/%E1%BB");
+        assert_eq!(SitemapParser::display_url("not a url"), "not a url");
+    }
+
+    #[test]
+    fn test_parse_limits() {
+        let xml = r#"<urlset><url><loc>https://example.com/1</loc></url><url><loc>https://example.com/2</loc></url></urlset>"#;
+
+        assert_eq!(SitemapParser::parse_string_with_limits(xml, &ParseLimits::new().max_entries(2)).unwrap().len(), 2);
+        assert!(matches!(
+            SitemapParser::parse_string_with_limits(xml, &ParseLimits::new().max_entries(1)),
+            Err(Error::TooManyEntries { actual: 2, limit: 1 })
+        ));
+        assert!(matches!(
+            SitemapParser::parse_string_with_limits(xml, &ParseLimits::new().max_text_len(10)),
+            Err(Error::TextTooLong { ref element, .. }) if element == "loc"
+        ));
+        assert!(SitemapParser::parse_string_with_limits(xml, &ParseLimits::new().max_bytes(xml.len())).is_ok());
+        assert!(matches!(
+            SitemapParser::parse_string_with_limits(xml, &ParseLimits::new().max_bytes(xml.len() - 1)),
+            Err(Error::DocumentTooLarge { actual, limit }) if actual == xml.len() && limit == xml.len() - 1
+        ));
+    }
+
+    #[test]
+    fn test_doctype_rejected() {
+        let xml = r#"<?xml version="1.0"?>
+<!DOCTYPE urlset [<!ENTITY a "aaaaaaaaaa"><!ENTITY b "&a;&a;&a;&a;&a;&a;&a;&a;&a;&a;">]>
+<urlset><url><loc>https://example.com/&b;</loc></url></urlset>"#;
+
+        assert!(matches!(SitemapParser::parse_string(xml), Err(Error::DoctypeNotAllowed)));
+        assert!(matches!(SitemapParser::parse_index_string(xml), Err(Error::DoctypeNotAllowed)));
+
+        // Entities are never expanded, even when the DOCTYPE is allowed
+        let limits = ParseLimits::new().allow_doctype(true);
+        assert!(matches!(SitemapParser::parse_string_with_limits(xml, &limits), Err(Error::Xml(_))));
+    }
+
+    #[test]
+    fn test_parse_lenient() {
+        let xml = "<urlset>\n<url><loc>https://example.com/a</loc><changefreq>DAILY</changefreq></url>\n\
+<url><loc>https://example.com/b</loc><changefreq>sometimes</changefreq><priority>0.5</priority></url>\n\
+<url><lastmod>2025-11-01</lastmod></url>\n\
+<url><loc>https://example.com/&bad;</loc></url>\n\
+<url><loc>https://example.com/c</loc>";
+
+        assert!(matches!(SitemapParser::parse_string(xml), Err(Error::InvalidChangeFreq { .. })));
+
+        let parsed = SitemapParser::parse_string_lenient(xml).unwrap();
+        let locs: Vec<&str> = parsed.entries.iter().map(|e| e.loc.as_str()).collect();
+        assert_eq!(locs, vec!["https://example.com/a", "https://example.com/b"]);
+        assert_eq!(parsed.entries[0].changefreq, Some(ChangeFreq::Daily));
+        assert_eq!(parsed.entries[1].changefreq, None);
+        assert_eq!(parsed.entries[1].priority, Some(0.5));
+
+        let issues: Vec<String> = parsed.issues.iter().map(|i| i.to_string()).collect();
+        assert_eq!(issues.len(), 5);
+        assert_eq!(issues[0], "3:38: <changefreq>: invalid changefreq \"sometimes\"");
+        assert_eq!(issues[1], "4:1: <url>: missing <loc>, entry skipped");
+        assert!(issues[2].starts_with("5:6: <loc>: "));
+        assert_eq!(issues[3], "5:1: <url>: missing <loc>, entry skipped");
+        assert_eq!(issues[4], "6:1: <url>: document ends inside <url>, entry skipped");
+        assert_eq!(parsed.issues[4].offset, xml.rfind("<url>").unwrap());
+    }
 }
@@ -0,0 +1,402 @@
+//! Best-effort repair of builder entries
+//!
+//! `build_repaired()` on the builders fixes values that have an obvious valid
+//! replacement, drops entries that still fail validation, and reports both.
+//!
+//! Repairs applied:
+//! - priorities outside 0.0-1.0 and video ratings outside 0.0-5.0 are clamped
+//! - video titles, descriptions and categories over the limit are truncated
+//! - `player_loc` is removed from videos that also have a `content_loc`
+//! - more than 32 video tags or 5 stock tickers are cut to the limit
+//! - uppercase news language codes are lowercased
+//!
+//! Entries are also dropped if they contain characters XML does not allow
+//! under [`InvalidCharPolicy::Reject`](crate::writer::InvalidCharPolicy::Reject),
+//! and once the document would grow past the 50MB size limit.
+//!
+//! # Example
+//! ```
+//! use sitemap_generator::{SitemapBuilder, UrlEntry};
+//!
+//! let mut builder = SitemapBuilder::new();
+//! builder.add_url(UrlEntry::new("https://example.com/").priority(2.0));
+//! builder.add_url(UrlEntry::new("https://example.com/old").lastmod("last week"));
+//!
+//! let repaired = builder.build_repaired().unwrap();
+//! assert!(repaired.xml.contains("<priority>1</priority>"));
+//! assert!(!repaired.xml.contains("/old"));
+//! assert_eq!(repaired.report.repaired[0].field, "priority");
+//! assert_eq!(repaired.report.dropped[0].code, "invalid_date");
+//! ```
+
+use crate::error::{Error, Result};
+use crate::text::{truncate, LengthUnit};
+use crate::types::*;
+use crate::validator::{
+    EntryUrl, MAX_SIZE_BYTES, MAX_STOCK_TICKERS, MAX_VIDEO_CATEGORY_LEN, MAX_VIDEO_DESCRIPTION_LEN,
+    MAX_VIDEO_TAGS, MAX_VIDEO_TITLE_LEN,
+};
+use crate::writer::{WriterOptions, XmlWriter};
+
+/// A field value changed by repair
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepairedField {
+    /// Position of the entry in the builder
+    pub index: usize,
+
+    /// Page URL of the entry
+    pub url: String,
+
+    /// Element name of the field, e.g. `priority` or `video:title`
+    pub field: &'static str,
+
+    /// Value before repair
+    pub original: String,
+
+    /// Value after repair, or `None` if the field was removed
+    pub repaired: Option<String>,
+}
+
+/// An entry left out of the repaired sitemap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DroppedEntry {
+    /// Position of the entry in the builder
+    pub index: usize,
+
+    /// Page URL of the entry
+    pub url: String,
+
+    /// [`Error::code`] of the check that failed
+    pub code: &'static str,
+
+    /// Description of the check that failed
+    pub reason: String,
+}
+
+/// Report of every change made by a repaired build
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepairReport {
+    /// Field values that were changed, in entry order
+    pub repaired: Vec<RepairedField>,
+
+    /// Entries that could not be repaired and were left out, in entry order
+    pub dropped: Vec<DroppedEntry>,
+}
+
+impl RepairReport {
+    /// Whether the sitemap was built without any change
+    pub fn is_clean(&self) -> bool {
+        self.repaired.is_empty() && self.dropped.is_empty()
+    }
+}
+
+/// Sitemap XML from a repaired build, with the report of what was changed
+#[derive(Debug, Clone)]
+pub struct RepairedSitemap {
+    /// The rendered sitemap
+    pub xml: String,
+
+    /// What repair changed and dropped
+    pub report: RepairReport,
+}
+
+/// Field changes collected while repairing one entry
+pub(crate) struct Changes {
+    fields: Vec<(&'static str, String, Option<String>)>,
+    unit: LengthUnit,
+}
+
+impl Changes {
+    fn record(&mut self, field: &'static str, original: impl Into<String>, repaired: Option<String>) {
+        self.fields.push((field, original.into(), repaired));
+    }
+}
+
+/// Entry types that can fix their own invalid values
+pub(crate) trait Repair {
+    fn repair(&mut self, changes: &mut Changes);
+}
+
+impl Repair for UrlEntry {
+    fn repair(&mut self, changes: &mut Changes) {
+        if let Some(priority) = self.priority {
+            if priority.is_nan() {
+                self.priority = None;
+                changes.record("priority", priority.to_string(), None);
+            } else if !(0.0..=1.0).contains(&priority) {
+                let clamped = priority.clamp(0.0, 1.0);
+                self.priority = Some(clamped);
+                changes.record("priority", priority.to_string(), Some(clamped.to_string()));
+            }
+        }
+    }
+}
+
+impl Repair for SitemapIndexEntry {
+    fn repair(&mut self, _changes: &mut Changes) {}
+}
+
+impl Repair for UrlWithImages {
+    fn repair(&mut self, changes: &mut Changes) {
+        self.url.repair(changes);
+    }
+}
+
+impl Repair for UrlWithVideos {
+    fn repair(&mut self, changes: &mut Changes) {
+        self.url.repair(changes);
+        for video in &mut self.videos {
+            video.repair(changes);
+        }
+    }
+}
+
+impl Repair for UrlWithNews {
+    fn repair(&mut self, changes: &mut Changes) {
+        self.url.repair(changes);
+        self.news.repair(changes);
+    }
+}
+
+impl Repair for UrlWithExtensions {
+    fn repair(&mut self, changes: &mut Changes) {
+        self.url.repair(changes);
+        for video in &mut self.videos {
+            video.repair(changes);
+        }
+        if let Some(ref mut news) = self.news {
+            news.repair(changes);
+        }
+    }
+}
+
+impl Repair for VideoEntry {
+    fn repair(&mut self, changes: &mut Changes) {
+        let unit = changes.unit;
+        let mut shorten = |field: &'static str, text: &mut String, limit: usize| {
+            if unit.count(text) > limit {
+                let short = truncate(text, limit, unit);
+                changes.record(field, std::mem::replace(text, short.clone()), Some(short));
+            }
+        };
+
+        shorten("video:title", &mut self.title, MAX_VIDEO_TITLE_LEN);
+        shorten("video:description", &mut self.description, MAX_VIDEO_DESCRIPTION_LEN);
+        if let Some(ref mut category) = self.category {
+            shorten("video:category", category, MAX_VIDEO_CATEGORY_LEN);
+        }
+
+        // Only one location is allowed; keep the file URL
+        if self.content_loc.is_some() {
+            if let Some(player_loc) = self.player_loc.take() {
+                changes.record("video:player_loc", player_loc, None);
+            }
+        }
+
+        if let Some(rating) = self.rating {
+            if !rating.is_nan() && !(0.0..=5.0).contains(&rating) {
+                let clamped = rating.clamp(0.0, 5.0);
+                self.rating = Some(clamped);
+                changes.record("video:rating", rating.to_string(), Some(clamped.to_string()));
+            }
+        }
+
+        if self.tags.len() > MAX_VIDEO_TAGS {
+            let original = self.tags.join(", ");
+            self.tags.truncate(MAX_VIDEO_TAGS);
+            changes.record("video:tag", original, Some(self.tags.join(", ")));
+        }
+    }
+}
+
+impl Repair for NewsEntry {
+    fn repair(&mut self, changes: &mut Changes) {
+        let language = &mut self.publication.language;
+        if language.chars().any(|c| c.is_ascii_uppercase()) {
+            let lower = language.to_ascii_lowercase();
+            changes.record("news:language", std::mem::replace(language, lower.clone()), Some(lower));
+        }
+
+        if self.stock_tickers.len() > MAX_STOCK_TICKERS {
+            let original = self.stock_tickers_text();
+            self.stock_tickers.truncate(MAX_STOCK_TICKERS);
+            changes.record("news:stock_tickers", original, Some(self.stock_tickers_text()));
+        }
+    }
+}
+
+/// Measures the bytes an entry adds to a rendered document
+///
+/// An entry is rendered on its own with the writer's options, so text the
+/// [`InvalidCharPolicy`](crate::writer::InvalidCharPolicy) rejects fails here.
+/// Namespace declarations are counted for every entry that uses them, so the
+/// sum over entries never underestimates the document size.
+pub(crate) struct EntrySize<'a, T> {
+    options: &'a WriterOptions,
+    render: fn(&mut XmlWriter, &[T]) -> Result<()>,
+    empty: usize,
+    limit: usize,
+}
+
+impl<'a, T> EntrySize<'a, T> {
+    pub(crate) fn new(options: &'a WriterOptions, render: fn(&mut XmlWriter, &[T]) -> Result<()>) -> Result<Self> {
+        let mut size = Self {
+            options,
+            render,
+            empty: 0,
+            limit: MAX_SIZE_BYTES,
+        };
+        size.empty = size.render_len(&[])?;
+        Ok(size)
+    }
+
+    fn render_len(&self, entries: &[T]) -> Result<usize> {
+        let mut writer = XmlWriter::with_options(self.options.clone());
+        (self.render)(&mut writer, entries)?;
+        Ok(writer.into_string()?.len())
+    }
+
+    fn of(&self, entry: &T) -> Result<usize> {
+        Ok(self.render_len(std::slice::from_ref(entry))? - self.empty)
+    }
+}
+
+/// Repair every entry, keeping those that pass `check` up to `limit` entries
+///
+/// Once the document would exceed the size limit (50MB), that entry and every
+/// entry after it are dropped. Changes to dropped entries are not reported,
+/// only the reason they were dropped.
+pub(crate) fn repair_entries<T, F>(
+    entries: Vec<T>,
+    unit: LengthUnit,
+    limit: usize,
+    size: &EntrySize<T>,
+    check: F,
+) -> (Vec<T>, RepairReport)
+where
+    T: Repair + EntryUrl,
+    F: Fn(&T) -> Result<()>,
+{
+    let total = entries.len();
+    let mut kept = Vec::with_capacity(total.min(limit));
+    let mut report = RepairReport::default();
+    let mut bytes = size.empty;
+    let mut full = false;
+
+    for (index, mut entry) in entries.into_iter().enumerate() {
+        let mut changes = Changes {
+            fields: Vec::new(),
+            unit,
+        };
+        entry.repair(&mut changes);
+
+        let result = check(&entry).and_then(|()| {
+            if kept.len() >= limit {
+                return Err(Error::TooManyUrls { actual: total, limit });
+            }
+            let actual = bytes + size.of(&entry)?;
+            if full || actual > size.limit {
+                full = true;
+                return Err(Error::SizeExceeded {
+                    actual,
+                    limit: size.limit,
+                });
+            }
+            bytes = actual;
+            Ok(())
+        });
+
+        let url = entry.entry_url().to_string();
+        match result {
+            Ok(()) => {
+                report.repaired.extend(changes.fields.into_iter().map(|(field, original, repaired)| {
+                    RepairedField {
+                        index,
+                        url: url.clone(),
+                        field,
+                        original,
+                        repaired,
+                    }
+                }));
+                kept.push(entry);
+            }
+            Err(err) => {
+                // Drop the entry context added by per-entry checks
+                let err = match err {
+                    Error::Entry { source, .. } => *source,
+                    err => err,
+                };
+                report.dropped.push(DroppedEntry {
+                    index,
+                    url,
+                    code: err.code(),
+                    reason: err.to_string(),
+                });
+            }
+        }
+    }
+
+    (kept, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator::Validator;
+
+    #[test]
+    fn test_repair_entries() {
+        let entries = vec![
+            UrlEntry::new("https://example.com/a").priority(1.5),
+            UrlEntry::new("not a url"),
+            UrlEntry::new("https://example.com/b").lastmod("yesterday"),
+            UrlEntry::new("https://example.com/c").priority(-0.2),
+            UrlEntry::new("https://example.com/d"),
+        ];
+        let check = |entry: &UrlEntry| {
+            Validator::validate_url(&entry.loc)?;
+            if let Some(ref lastmod) = entry.lastmod {
+                Validator::validate_date(lastmod)?;
+            }
+            Ok(())
+        };
+
+        let options = WriterOptions::default();
+        let size = EntrySize::new(&options, XmlWriter::write_sitemap).unwrap();
+        let (kept, report) = repair_entries(entries, LengthUnit::Chars, 2, &size, check);
+
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].priority, Some(1.0));
+        assert_eq!(kept[1].priority, Some(0.0));
+
+        assert_eq!(report.repaired.len(), 2);
+        assert_eq!(report.repaired[0].field, "priority");
+        assert_eq!(report.repaired[0].original, "1.5");
+        assert_eq!(report.repaired[0].repaired.as_deref(), Some("1"));
+        assert_eq!(report.repaired[1].index, 3);
+
+        let dropped: Vec<(usize, &str)> = report.dropped.iter().map(|d| (d.index, d.code)).collect();
+        assert_eq!(dropped, vec![(1, "invalid_url"), (2, "invalid_date"), (4, "too_many_urls")]);
+    }
+
+    #[test]
+    fn test_repair_entries_size_limit() {
+        let entries: Vec<UrlEntry> = (0..10)
+            .map(|i| UrlEntry::new(format!("https://example.com/page{}", i)))
+            .collect();
+        let options = WriterOptions::default();
+        let mut size = EntrySize::new(&options, XmlWriter::write_sitemap).unwrap();
+        let per_entry = size.of(&entries[0]).unwrap();
+        size.limit = size.empty + 3 * per_entry;
+
+        let (kept, report) = repair_entries(entries, LengthUnit::Chars, 10, &size, |_| Ok(()));
+
+        assert_eq!(kept.len(), 3);
+        let mut writer = XmlWriter::new();
+        writer.write_sitemap(&kept).unwrap();
+        assert!(writer.into_string().unwrap().len() <= size.limit);
+
+        let dropped: Vec<(usize, &str)> = report.dropped.iter().map(|d| (d.index, d.code)).collect();
+        assert_eq!(dropped, (3..10).map(|i| (i, "size_exceeded")).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,138 @@
+//! Resolution of relative URLs against a base URL at build time
+
+use crate::types::*;
+use std::borrow::Cow;
+use url::{ParseError, Url};
+
+/// Entry types whose URLs can be resolved against a base URL
+pub(crate) trait ResolveUrls {
+    fn resolve_urls(&mut self, base: &Url);
+}
+
+/// Resolve a relative URL in place; absolute and unresolvable URLs are kept
+pub(crate) fn resolve_url(base: &Url, url: &mut String) {
+    if let Err(ParseError::RelativeUrlWithoutBase) = Url::parse(url) {
+        if let Ok(resolved) = base.join(url) {
+            *url = resolved.into();
+        }
+    }
+}
+
+/// Resolve a relative URL, borrowing it if there is no base or it is absolute
+pub(crate) fn resolved_url<'a>(base: Option<&Url>, url: &'a str) -> Cow<'a, str> {
+    match base {
+        Some(base) if matches!(Url::parse(url), Err(ParseError::RelativeUrlWithoutBase)) => match base.join(url) {
+            Ok(resolved) => Cow::Owned(resolved.into()),
+            Err(_) => Cow::Borrowed(url),
+        },
+        _ => Cow::Borrowed(url),
+    }
+}
+
+/// Resolve the URLs of a single entry, borrowing it if there is no base
+pub(crate) fn resolved<'a, T: ResolveUrls + Clone>(base: Option<&Url>, entry: &'a T) -> Cow<'a, T> {
+    match base {
+        Some(base) => {
+            let mut entry = entry.clone();
+            entry.resolve_urls(base);
+            Cow::Owned(entry)
+        }
+        None => Cow::Borrowed(entry),
+    }
+}
+
+impl ResolveUrls for UrlEntry {
+    fn resolve_urls(&mut self, base: &Url) {
+        resolve_url(base, &mut self.loc);
+    }
+}
+
+impl ResolveUrls for ImageEntry {
+    fn resolve_urls(&mut self, base: &Url) {
+        resolve_url(base, &mut self.loc);
+    }
+}
+
+impl ResolveUrls for VideoEntry {
+    fn resolve_urls(&mut self, base: &Url) {
+        resolve_url(base, &mut self.thumbnail_loc);
+        for url in [&mut self.content_loc, &mut self.player_loc, &mut self.gallery_loc]
+            .into_iter()
+            .flatten()
+        {
+            resolve_url(base, url);
+        }
+    }
+}
+
+impl ResolveUrls for UrlWithImages {
+    fn resolve_urls(&mut self, base: &Url) {
+        self.url.resolve_urls(base);
+        self.images.iter_mut().for_each(|image| image.resolve_urls(base));
+    }
+}
+
+impl ResolveUrls for UrlWithVideos {
+    fn resolve_urls(&mut self, base: &Url) {
+        self.url.resolve_urls(base);
+        self.videos.iter_mut().for_each(|video| video.resolve_urls(base));
+    }
+}
+
+impl ResolveUrls for UrlWithNews {
+    fn resolve_urls(&mut self, base: &Url) {
+        self.url.resolve_urls(base);
+    }
+}
+
+impl ResolveUrls for UrlWithExtensions {
+    fn resolve_urls(&mut self, base: &Url) {
+        self.url.resolve_urls(base);
+        self.images.iter_mut().for_each(|image| image.resolve_urls(base));
+        self.videos.iter_mut().for_each(|video| video.resolve_urls(base));
+    }
+}
+
+impl ResolveUrls for SitemapIndexEntry {
+    fn resolve_urls(&mut self, base: &Url) {
+        resolve_url(base, &mut self.loc);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_url() {
+        let base = Url::parse("https://staging.example.com/app/").unwrap();
+        let resolve = |url: &str| {
+            let mut url = url.to_string();
+            resolve_url(&base, &mut url);
+            url
+        };
+
+        assert_eq!(resolve("/blog/post-1"), "https://staging.example.com/blog/post-1");
+        assert_eq!(resolve("about"), "https://staging.example.com/app/about");
+        assert_eq!(resolve("https://cdn.example.net/a.jpg"), "https://cdn.example.net/a.jpg");
+        assert_eq!(resolve("//cdn.example.net/a.jpg"), "https://cdn.example.net/a.jpg");
+    }
+
+    #[test]
+    fn test_resolved() {
+        let base = Url::parse("https://example.com").unwrap();
+        let entry = UrlWithVideos::new(UrlEntry::new("/videos/1")).add_video(
+            VideoEntry::new("/thumbs/1.jpg", "Title", "Description").player_loc("/player?id=1"),
+        );
+
+        assert!(matches!(resolved(None, &entry), Cow::Borrowed(_)));
+        assert!(matches!(resolved_url(Some(&base), "https://example.com/a"), Cow::Borrowed(_)));
+        assert_eq!(resolved_url(Some(&base), "/a"), "https://example.com/a");
+
+        let resolved = resolved(Some(&base), &entry);
+        assert_eq!(resolved.url.loc, "https://example.com/videos/1");
+        assert_eq!(resolved.videos[0].thumbnail_loc, "https://example.com/thumbs/1.jpg");
+        assert_eq!(resolved.videos[0].player_loc.as_deref(), Some("https://example.com/player?id=1"));
+        assert_eq!(entry.url.loc, "/videos/1");
+    }
+}
@@ -0,0 +1,416 @@
+//! robots.txt parsing, `Sitemap:` directive management and URL blocking checks
+
+use crate::error::{IoContext, Result};
+use crate::types::UrlEntry;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use url::Url;
+
+/// An `Allow` or `Disallow` rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RobotsRule {
+    /// `true` for `Allow`, `false` for `Disallow`
+    pub allow: bool,
+
+    /// Path pattern; supports `*` wildcards and a trailing `$` anchor
+    pub pattern: String,
+}
+
+/// A group of rules that applies to one or more user agents
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RobotsGroup {
+    /// User agents this group applies to (lowercase)
+    pub user_agents: Vec<String>,
+
+    /// Rules in file order
+    pub rules: Vec<RobotsRule>,
+}
+
+/// A parsed robots.txt file
+///
+/// Lines other than `Sitemap:` are kept verbatim, so a file can be read,
+/// have its sitemap directives replaced, and be written back without
+/// disturbing comments or formatting. `Sitemap:` lines are always written at
+/// the end of the file.
+///
+/// Rule matching follows RFC 9309: the most specific matching user-agent
+/// group applies (falling back to `*`), the longest matching rule wins, and
+/// `Allow` wins ties. Patterns and paths are compared percent-encoded, with
+/// escaped unreserved characters decoded, so `/café` matches `/caf%C3%A9`.
+///
+/// # Example
+/// ```
+/// use sitemap_generator::{RobotsTxt, UrlEntry};
+///
+/// let mut robots = RobotsTxt::parse("User-agent: *\nDisallow: /admin/\n");
+/// robots.set_sitemaps(["https://example.com/sitemap_index.xml"]);
+///
+/// assert!(robots.to_string().ends_with("Sitemap: https://example.com/sitemap_index.xml\n"));
+///
+/// let entries = vec![
+///     UrlEntry::new("https://example.com/"),
+///     UrlEntry::new("https://example.com/admin/users"),
+/// ];
+/// let blocked = robots.blocked_entries("Googlebot", &entries);
+/// assert_eq!(blocked.len(), 1);
+/// assert_eq!(blocked[0].loc, "https://example.com/admin/users");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RobotsTxt {
+    lines: Vec<String>,
+    groups: Vec<RobotsGroup>,
+    sitemaps: Vec<String>,
+}
+
+impl RobotsTxt {
+    /// Create an empty robots.txt
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse robots.txt content
+    pub fn parse(text: &str) -> Self {
+        let mut robots = RobotsTxt::new();
+        let mut current: Option<RobotsGroup> = None;
+        let mut in_agent_lines = false;
+
+        for raw in text.lines() {
+            let line = raw.split('#').next().unwrap_or("").trim();
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim().to_ascii_lowercase(), value.trim()),
+                None => {
+                    robots.lines.push(raw.to_string());
+                    continue;
+                }
+            };
+
+            match key.as_str() {
+                "sitemap" => {
+                    if !value.is_empty() {
+                        robots.sitemaps.push(value.to_string());
+                    }
+                    continue;
+                }
+                "user-agent" => {
+                    if !in_agent_lines {
+                        if let Some(group) = current.take() {
+                            robots.groups.push(group);
+                        }
+                        current = Some(RobotsGroup::default());
+                    }
+                    if let Some(ref mut group) = current {
+                        group.user_agents.push(value.to_ascii_lowercase());
+                    }
+                    in_agent_lines = true;
+                }
+                "allow" | "disallow" => {
+                    in_agent_lines = false;
+                    // An empty Disallow allows everything, so it adds no rule
+                    if let (Some(ref mut group), false) = (&mut current, value.is_empty()) {
+                        group.rules.push(RobotsRule {
+                            allow: key == "allow",
+                            pattern: value.to_string(),
+                        });
+                    }
+                }
+                _ => in_agent_lines = false,
+            }
+
+            robots.lines.push(raw.to_string());
+        }
+
+        if let Some(group) = current {
+            robots.groups.push(group);
+        }
+
+        robots
+    }
+
+    /// Read and parse a robots.txt file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::parse(&fs::read_to_string(&path).with_path(&path)?))
+    }
+
+    /// Write the robots.txt to a file
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        fs::write(&path, self.to_string()).with_path(&path)?;
+        Ok(())
+    }
+
+    /// Get the user-agent groups
+    pub fn groups(&self) -> &[RobotsGroup] {
+        &self.groups
+    }
+
+    /// Get the `Sitemap:` URLs
+    pub fn sitemaps(&self) -> &[String] {
+        &self.sitemaps
+    }
+
+    /// Add a `Sitemap:` directive unless it is already present
+    pub fn add_sitemap(&mut self, url: impl Into<String>) -> &mut Self {
+        let url = url.into();
+        if !self.sitemaps.contains(&url) {
+            self.sitemaps.push(url);
+        }
+        self
+    }
+
+    /// Replace all `Sitemap:` directives
+    pub fn set_sitemaps<I, S>(&mut self, urls: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.sitemaps.clear();
+        for url in urls {
+            self.add_sitemap(url);
+        }
+        self
+    }
+
+    /// Replace all `Sitemap:` directives with the URL of a sitemap index
+    ///
+    /// Crawlers find the child sitemaps through the index, so only the index
+    /// itself needs to be listed.
+    pub fn set_sitemap_index(&mut self, index_url: impl Into<String>) -> &mut Self {
+        self.set_sitemaps([index_url])
+    }
+
+    /// Check whether a URL may be crawled by the given user agent
+    ///
+    /// Only the path and query of the URL are considered; the caller is
+    /// responsible for checking the URL against the robots.txt of its own host.
+    /// Unparseable URLs are treated as allowed.
+    #[allow(clippy::unnecessary_map_or)] // `Option::is_none_or` needs Rust 1.82
+    pub fn is_allowed(&self, user_agent: &str, url: &str) -> bool {
+        let path = match Url::parse(url) {
+            Ok(url) => match url.query() {
+                Some(query) => format!("{}?{}", url.path(), query),
+                None => url.path().to_string(),
+            },
+            Err(_) => return true,
+        };
+        let path = normalize_encoding(&path);
+
+        // The matching rule with the longest pattern, and whether it allows
+        let mut best: Option<(usize, bool)> = None;
+        for group in self.groups_for(user_agent) {
+            for rule in &group.rules {
+                let pattern = normalize_encoding(&rule.pattern);
+                if !pattern_matches(&pattern, &path) {
+                    continue;
+                }
+                best = match best {
+                    Some((len, allow)) if len > pattern.len() || (len == pattern.len() && allow) => {
+                        Some((len, allow))
+                    }
+                    _ => Some((pattern.len(), rule.allow)),
+                };
+            }
+        }
+
+        best.map_or(true, |(_, allow)| allow)
+    }
+
+    /// Return the entries that the given user agent is not allowed to crawl
+    ///
+    /// Listing blocked URLs in a sitemap is a common error: search engines
+    /// report them and they waste crawl budget.
+    pub fn blocked_entries<'a>(&self, user_agent: &str, entries: &'a [UrlEntry]) -> Vec<&'a UrlEntry> {
+        entries
+            .iter()
+            .filter(|entry| !self.is_allowed(user_agent, &entry.loc))
+            .collect()
+    }
+
+    /// Select the groups that apply to a user agent
+    ///
+    /// Groups naming the longest product token contained in the user agent win;
+    /// if none match, the `*` groups apply.
+    fn groups_for(&self, user_agent: &str) -> Vec<&RobotsGroup> {
+        let user_agent = user_agent.to_ascii_lowercase();
+        let specificity = |group: &RobotsGroup| {
+            group
+                .user_agents
+                .iter()
+                .filter(|ua| ua.as_str() != "*" && user_agent.contains(ua.as_str()))
+                .map(|ua| ua.len())
+                .max()
+        };
+
+        let best = self.groups.iter().filter_map(specificity).max();
+        match best {
+            Some(len) => self
+                .groups
+                .iter()
+                .filter(|g| specificity(g) == Some(len))
+                .collect(),
+            None => self
+                .groups
+                .iter()
+                .filter(|g| g.user_agents.iter().any(|ua| ua == "*"))
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for RobotsTxt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = self.lines.as_slice();
+        while let Some((last, rest)) = lines.split_last() {
+            if !last.trim().is_empty() {
+                break;
+            }
+            lines = rest;
+        }
+
+        for line in lines {
+            writeln!(f, "{}", line)?;
+        }
+
+        if !self.sitemaps.is_empty() {
+            if !lines.is_empty() {
+                writeln!(f)?;
+            }
+            for sitemap in &self.sitemaps {
+                writeln!(f, "Sitemap: {}", sitemap)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Bring a path or pattern to the form used for matching (RFC 9309, 2.2.2)
+///
+/// Non-ASCII characters and characters a URL path never holds unescaped are
+/// percent-encoded, escapes of unreserved characters are decoded, and the
+/// remaining escapes use uppercase hex digits.
+fn normalize_encoding(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        let escaped = (b == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit())
+        .then(|| u8::from_str_radix(&text[i + 1..i + 3], 16).ok())
+        .flatten();
+
+        match escaped {
+            Some(c) if c.is_ascii_alphanumeric() || matches!(c, b'-' | b'.' | b'_' | b'~') => {
+                out.push(c as char);
+                i += 3;
+            }
+            Some(c) => {
+                out.push_str(&format!("%{:02X}", c));
+                i += 3;
+            }
+            None if b.is_ascii_control() || b >= 0x80 || matches!(b, b' ' | b'"' | b'<' | b'>' | b'`' | b'{' | b'}') => {
+                out.push_str(&format!("%{:02X}", b));
+                i += 1;
+            }
+            None => {
+                out.push(b as char);
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+/// Match a robots.txt path pattern (`*` wildcard, `$` end anchor) as a prefix
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+
+    fn matches(pattern: &[u8], path: &[u8], anchored: bool) -> bool {
+        match pattern.split_first() {
+            None => !anchored || path.is_empty(),
+            Some((b'*', rest)) => (0..=path.len()).any(|skip| matches(rest, &path[skip..], anchored)),
+            Some((c, rest)) => path.first() == Some(c) && matches(rest, &path[1..], anchored),
+        }
+    }
+
+    matches(pattern.as_bytes(), path.as_bytes(), anchored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "# Example robots.txt
+User-agent: *
+Disallow: /private/
+Allow: /private/public-page
+Disallow: /*.pdf$
+
+User-agent: Googlebot
+User-agent: Bingbot
+Disallow: /no-search/
+
+Sitemap: https://example.com/old-sitemap.xml
+";
+
+    #[test]
+    fn test_parse_groups() {
+        let robots = RobotsTxt::parse(ROBOTS);
+        assert_eq!(robots.groups().len(), 2);
+        assert_eq!(robots.groups()[1].user_agents, vec!["googlebot", "bingbot"]);
+        assert_eq!(robots.sitemaps(), ["https://example.com/old-sitemap.xml"]);
+    }
+
+    #[test]
+    fn test_is_allowed() {
+        let robots = RobotsTxt::parse(ROBOTS);
+
+        assert!(!robots.is_allowed("SomeBot/1.0", "https://example.com/private/a"));
+        assert!(robots.is_allowed("SomeBot/1.0", "https://example.com/private/public-page"));
+        assert!(!robots.is_allowed("SomeBot/1.0", "https://example.com/docs/a.pdf"));
+        assert!(robots.is_allowed("SomeBot/1.0", "https://example.com/docs/a.pdf?x=1"));
+
+        // Googlebot only obeys its own group
+        assert!(robots.is_allowed("Mozilla/5.0 (compatible; Googlebot/2.1)", "https://example.com/private/a"));
+        assert!(!robots.is_allowed("Googlebot", "https://example.com/no-search/x"));
+    }
+
+    #[test]
+    fn test_percent_encoding() {
+        let robots = RobotsTxt::parse("User-agent: *\nDisallow: /café\nDisallow: /%7Euser/\nDisallow: /a%2fb\n");
+
+        assert!(!robots.is_allowed("SomeBot", "https://example.com/caf%C3%A9"));
+        assert!(!robots.is_allowed("SomeBot", "https://example.com/café/menu"));
+        assert!(!robots.is_allowed("SomeBot", "https://example.com/~user/page"));
+        assert!(!robots.is_allowed("SomeBot", "https://example.com/a%2Fb"));
+        assert!(robots.is_allowed("SomeBot", "https://example.com/a/b"));
+
+        assert_eq!(normalize_encoding("/caf%c3%a9/%62az"), "/caf%C3%A9/baz");
+        assert_eq!(normalize_encoding("/ツ*$"), "/%E3%83%84*$");
+        assert_eq!(normalize_encoding("/100%"), "/100%");
+    }
+
+    #[test]
+    fn test_replace_sitemaps() {
+        let mut robots = RobotsTxt::parse(ROBOTS);
+        robots
+            .set_sitemaps(["https://example.com/sitemap_index.xml"])
+            .add_sitemap("https://example.com/news.xml")
+            .add_sitemap("https://example.com/news.xml");
+
+        let text = robots.to_string();
+        assert!(text.starts_with("# Example robots.txt\nUser-agent: *\n"));
+        assert!(!text.contains("old-sitemap"));
+        assert!(text.ends_with(
+            "Disallow: /no-search/\n\nSitemap: https://example.com/sitemap_index.xml\nSitemap: https://example.com/news.xml\n"
+        ));
+        assert_eq!(RobotsTxt::parse(&text).groups(), robots.groups());
+    }
+}
@@ -0,0 +1,405 @@
+//! Directory scanner that builds sitemap entries from a static site's output
+
+use crate::error::{Error, IoContext, Result};
+use crate::html;
+use crate::incremental::content_hash;
+use crate::types::UrlEntry;
+use chrono::{DateTime, SecondsFormat, Utc};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// Characters escaped in a path segment built from a file name
+const SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b':')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// Where the scanner takes `UrlEntry.lastmod` from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LastmodSource {
+    /// Leave `lastmod` unset
+    None,
+
+    /// Use the file modification time
+    Mtime,
+
+    /// Hash file contents and keep the previous date while the hash is unchanged
+    ///
+    /// Hashes and dates are stored in the given JSON cache file, so the result
+    /// does not depend on mtimes (which checkouts and CI builds reset). Pages
+    /// that are no longer published are removed from the cache.
+    ContentHash(PathBuf),
+}
+
+/// A cached content hash and the date it was first seen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HashCacheEntry {
+    hash: String,
+    lastmod: String,
+}
+
+/// Scanner that walks a static site's output directory and maps HTML files to URLs
+///
+/// - `index.html` maps to its directory (`blog/index.html` → `/blog/`)
+/// - other files keep their name, or drop `.html` with [`pretty_urls`](Self::pretty_urls)
+/// - pages with `<meta name="robots" content="noindex">` are skipped
+/// - pages whose `<link rel="canonical">` points to another URL are skipped;
+///   a trailing slash, `index.html` or `.html` does not make a URL different
+/// - file names are percent-encoded, so `?`, `#`, `:` and `%` stay in the path
+///
+/// # Example
+/// ```no_run
+/// use sitemap_generator::{DirectoryScanner, LastmodSource, SitemapBuilder};
+///
+/// let entries = DirectoryScanner::new("public", "https://docs.example.com/")
+///     .exclude("drafts/**")
+///     .lastmod(LastmodSource::Mtime)
+///     .scan()
+///     .unwrap();
+///
+/// let mut builder = SitemapBuilder::with_capacity(entries.len());
+/// builder.add_urls(entries);
+/// builder.write("public/sitemap.xml").unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct DirectoryScanner {
+    root: PathBuf,
+    base_url: String,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    pretty_urls: bool,
+    lastmod: LastmodSource,
+    honor_noindex: bool,
+    honor_canonical: bool,
+}
+
+impl DirectoryScanner {
+    /// Create a scanner for `root`, publishing pages under `base_url`
+    pub fn new(root: impl Into<PathBuf>, base_url: impl Into<String>) -> Self {
+        Self {
+            root: root.into(),
+            base_url: base_url.into(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            pretty_urls: false,
+            lastmod: LastmodSource::None,
+            honor_noindex: true,
+            honor_canonical: true,
+        }
+    }
+
+    /// Only include files matching this glob (default: `**/*.html`)
+    ///
+    /// Globs match the path relative to the root, using `/` separators.
+    /// `*` and `?` do not cross directories, `**` does.
+    pub fn include(mut self, pattern: impl Into<String>) -> Self {
+        self.include.push(pattern.into());
+        self
+    }
+
+    /// Skip files matching this glob
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+
+    /// Drop the `.html` extension from URLs (`about.html` → `/about`)
+    pub fn pretty_urls(mut self, pretty: bool) -> Self {
+        self.pretty_urls = pretty;
+        self
+    }
+
+    /// Set where `lastmod` comes from (default: [`LastmodSource::None`])
+    pub fn lastmod(mut self, source: LastmodSource) -> Self {
+        self.lastmod = source;
+        self
+    }
+
+    /// Skip pages marked `noindex` (enabled by default)
+    pub fn honor_noindex(mut self, honor: bool) -> Self {
+        self.honor_noindex = honor;
+        self
+    }
+
+    /// Skip pages whose canonical URL differs from their own (enabled by default)
+    pub fn honor_canonical(mut self, honor: bool) -> Self {
+        self.honor_canonical = honor;
+        self
+    }
+
+    /// Walk the directory and return one entry per published page, sorted by path
+    pub fn scan(&self) -> Result<Vec<UrlEntry>> {
+        let mut base = Url::parse(&self.base_url).map_err(|_| Error::InvalidUrl {
+            url: self.base_url.clone(),
+        })?;
+        if !base.path().ends_with('/') {
+            let path = format!("{}/", base.path());
+            base.set_path(&path);
+        }
+
+        let mut files = Vec::new();
+        Self::walk(&self.root, &mut files)?;
+        files.sort();
+
+        let mut cache = match &self.lastmod {
+            LastmodSource::ContentHash(path) if path.exists() => {
+                serde_json::from_str::<BTreeMap<String, HashCacheEntry>>(&fs::read_to_string(path).with_path(path)?)?
+            }
+            _ => BTreeMap::new(),
+        };
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+
+        let mut entries = Vec::new();
+        let mut seen = HashSet::new();
+        let mut hashed = HashSet::new();
+
+        for path in files {
+            let relative = match path.strip_prefix(&self.root) {
+                Ok(rel) => rel
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/"),
+                Err(_) => continue,
+            };
+
+            if !self.is_selected(&relative) {
+                continue;
+            }
+
+            let loc = base
+                .join(&self.url_path(&relative))
+                .map_err(|_| Error::InvalidUrl { url: relative.clone() })?;
+
+            let content = fs::read(&path).with_path(&path)?;
+            let page = String::from_utf8_lossy(&content);
+
+            if self.honor_noindex && is_noindex(&page) {
+                continue;
+            }
+
+            if self.honor_canonical {
+                if let Some(canonical) = canonical_url(&page, &loc) {
+                    if page_key(&canonical) != page_key(&loc) {
+                        continue;
+                    }
+                }
+            }
+
+            if !seen.insert(loc.to_string()) {
+                continue;
+            }
+
+            let mut entry = UrlEntry::new(loc.as_str());
+
+            match &self.lastmod {
+                LastmodSource::None => {}
+                LastmodSource::Mtime => {
+                    let modified: DateTime<Utc> = fs::metadata(&path).and_then(|m| m.modified()).with_path(&path)?.into();
+                    entry.lastmod = Some(modified.to_rfc3339_opts(SecondsFormat::Secs, true));
+                }
+                LastmodSource::ContentHash(_) => {
+                    let hash = content_hash(&content);
+                    let cached = cache.entry(relative.clone()).or_insert_with(|| HashCacheEntry {
+                        hash: hash.clone(),
+                        lastmod: today.clone(),
+                    });
+                    if cached.hash != hash {
+                        cached.hash = hash;
+                        cached.lastmod = today.clone();
+                    }
+                    entry.lastmod = Some(cached.lastmod.clone());
+                    hashed.insert(relative);
+                }
+            }
+
+            entries.push(entry);
+        }
+
+        if let LastmodSource::ContentHash(path) = &self.lastmod {
+            cache.retain(|relative, _| hashed.contains(relative));
+            fs::write(path, serde_json::to_string_pretty(&cache)?).with_path(path)?;
+        }
+
+        Ok(entries)
+    }
+
+    fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+        for entry in fs::read_dir(dir).with_path(dir)? {
+            let entry = entry.with_path(dir)?;
+            let file_type = entry.file_type().with_path(entry.path())?;
+            if file_type.is_dir() {
+                Self::walk(&entry.path(), files)?;
+            } else if file_type.is_file() {
+                files.push(entry.path());
+            }
+        }
+        Ok(())
+    }
+
+    fn is_selected(&self, relative: &str) -> bool {
+        let included = if self.include.is_empty() {
+            glob_match("**/*.html", relative)
+        } else {
+            self.include.iter().any(|p| glob_match(p, relative))
+        };
+        included && !self.exclude.iter().any(|p| glob_match(p, relative))
+    }
+
+    /// Relative URL of a file, with each path segment percent-encoded
+    fn url_path(&self, relative: &str) -> String {
+        let path = if relative == "index.html" {
+            ""
+        } else if let Some(dir) = relative.strip_suffix("index.html").filter(|dir| dir.ends_with('/')) {
+            dir
+        } else if self.pretty_urls {
+            relative.strip_suffix(".html").unwrap_or(relative)
+        } else {
+            relative
+        };
+
+        path.split('/')
+            .map(|segment| utf8_percent_encode(segment, SEGMENT).to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+/// Check for `<meta name="robots" content="...noindex...">`
+fn is_noindex(page: &str) -> bool {
+    html::tags(page).any(|tag| {
+        tag.name == "meta"
+            && tag.attr("name").is_some_and(|n| n.eq_ignore_ascii_case("robots"))
+            && tag.attr("content").is_some_and(|content| {
+                content.split(',').map(str::trim).any(|directive| {
+                    directive.eq_ignore_ascii_case("noindex") || directive.eq_ignore_ascii_case("none")
+                })
+            })
+    })
+}
+
+/// Find `<link rel="canonical" href="...">`, resolved against the page URL
+fn canonical_url(page: &str, page_url: &Url) -> Option<Url> {
+    html::tags(page)
+        .find(|tag| {
+            tag.name == "link"
+                && tag.attr("rel").is_some_and(|rel| {
+                    rel.split_ascii_whitespace().any(|r| r.eq_ignore_ascii_case("canonical"))
+                })
+        })
+        .and_then(|tag| tag.attr("href").and_then(|href| page_url.join(href.trim()).ok()))
+}
+
+/// URL of a page with the fragment, a trailing slash, `index.html` and
+/// `.html` removed, so pretty and file forms of the same page compare equal
+fn page_key(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    let path = url.path();
+    let path = path.strip_suffix("index.html").filter(|dir| dir.ends_with('/')).unwrap_or(path);
+    let path = path.strip_suffix(".html").unwrap_or(path);
+    let path = path.trim_end_matches('/').to_string();
+    url.set_path(&path);
+    url.into()
+}
+
+/// Match a path against a glob supporting `*`, `?` and `**`
+pub(crate) fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+    match_segments(&pattern, &path)
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, path_rest)) => {
+                match_segment(first.as_bytes(), segment.as_bytes()) && match_segments(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+fn match_segment(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| match_segment(rest, &text[skip..])),
+        Some((b'?', rest)) => !text.is_empty() && match_segment(rest, &text[1..]),
+        Some((c, rest)) => text.first() == Some(c) && match_segment(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("**/*.html", "index.html"));
+        assert!(glob_match("**/*.html", "blog/2025/post.html"));
+        assert!(!glob_match("*.html", "blog/post.html"));
+        assert!(glob_match("drafts/**", "drafts/a/b.html"));
+        assert!(glob_match("blog/page?.html", "blog/page2.html"));
+        assert!(!glob_match("**/*.html", "style.css"));
+    }
+
+    #[test]
+    fn test_url_path() {
+        let scanner = DirectoryScanner::new("public", "https://example.com/");
+        assert_eq!(scanner.url_path("index.html"), "");
+        assert_eq!(scanner.url_path("blog/index.html"), "blog/");
+        assert_eq!(scanner.url_path("about.html"), "about.html");
+
+        let scanner = scanner.pretty_urls(true);
+        assert_eq!(scanner.url_path("about.html"), "about");
+        assert_eq!(scanner.url_path("a?b.html"), "a%3Fb");
+        assert_eq!(scanner.url_path("docs/c#:100%/index.html"), "docs/c%23%3A100%25/");
+
+        let base = Url::parse("https://example.com/").unwrap();
+        assert_eq!(base.join(&scanner.url_path("a?b.html")).unwrap().as_str(), "https://example.com/a%3Fb");
+        assert_eq!(base.join(&scanner.url_path("x:y.html")).unwrap().as_str(), "https://example.com/x%3Ay");
+    }
+
+    #[test]
+    fn test_robots_and_canonical() {
+        let page = r#"<head><meta name="Robots" content="NOINDEX, follow">
+<link rel="canonical" href="/docs/"></head>"#;
+        let url = Url::parse("https://example.com/docs/index.html").unwrap();
+
+        assert!(is_noindex(page));
+        assert_eq!(
+            canonical_url(page, &url).unwrap().as_str(),
+            "https://example.com/docs/"
+        );
+        assert!(!is_noindex("<meta name=\"description\" content=\"noindex\">"));
+
+        let key = |url: &str| page_key(&Url::parse(url).unwrap());
+        assert_eq!(key("https://example.com/docs/"), key("https://example.com/docs/index.html"));
+        assert_eq!(key("https://example.com/about"), key("https://example.com/about.html"));
+        assert_eq!(key("https://example.com/about/"), key("https://example.com/about#team"));
+        assert_ne!(key("https://example.com/about"), key("https://example.com/contact"));
+        assert_ne!(key("https://example.com/docs/myindex.html"), key("https://example.com/docs/my"));
+        assert_eq!(key("https://example.com/index.html"), key("https://example.com/"));
+    }
+}
@@ -0,0 +1,330 @@
+//! Sitemap location scope and host rules
+//!
+//! The protocol requires every URL in a sitemap to use the same scheme and
+//! host as the sitemap file and to live under the directory the sitemap is
+//! published in, unless the host cross-submits the sitemap via robots.txt.
+
+use crate::error::{Error, Result};
+use crate::resolve::{resolved, ResolveUrls};
+use crate::types::*;
+use std::fmt;
+use url::Url;
+
+/// Which URL of an entry is out of scope
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeViolationKind {
+    /// The page URL (`<loc>`)
+    Loc,
+
+    /// An image URL (`<image:loc>`)
+    Image,
+
+    /// A video file URL (`<video:content_loc>`)
+    VideoContent,
+}
+
+/// A URL that falls outside the sitemap scope
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeViolation {
+    /// The offending URL
+    pub url: String,
+
+    /// Page URL of the entry containing it
+    pub page: String,
+
+    /// Which URL of the entry is out of scope
+    pub kind: ScopeViolationKind,
+}
+
+impl fmt::Display for ScopeViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ScopeViolationKind::Loc => write!(f, "{} is outside the sitemap scope", self.url),
+            ScopeViolationKind::Image => {
+                write!(f, "image {} on {} is on a host that is not allowed", self.url, self.page)
+            }
+            ScopeViolationKind::VideoContent => {
+                write!(f, "video {} on {} is on a host that is not allowed", self.url, self.page)
+            }
+        }
+    }
+}
+
+/// Scope rules derived from the public URL of a sitemap
+///
+/// Set on a builder with `scope()` to reject out-of-scope URLs at build time,
+/// or combine with [`warn_only`](SitemapScope::warn_only) and inspect
+/// `scope_violations()` instead.
+///
+/// # Example
+/// ```
+/// use sitemap_generator::{SitemapBuilder, SitemapScope, UrlEntry};
+///
+/// let scope = SitemapScope::new("https://example.com/blog/sitemap.xml").unwrap();
+/// let mut builder = SitemapBuilder::new().scope(scope);
+/// builder.add_url(UrlEntry::new("https://example.com/blog/post-1"));
+/// builder.add_url(UrlEntry::new("https://example.com/shop/item"));
+///
+/// assert_eq!(builder.scope_violations().len(), 1);
+/// assert!(builder.build().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SitemapScope {
+    sitemap_url: Url,
+    directory: String,
+    cross_submit_hosts: Vec<String>,
+    asset_hosts: Vec<String>,
+    warn_only: bool,
+}
+
+impl SitemapScope {
+    /// Create scope rules from the public URL the sitemap is served from
+    pub fn new(sitemap_url: &str) -> Result<Self> {
+        let url = Url::parse(sitemap_url).map_err(|_| Error::InvalidUrl {
+            url: sitemap_url.to_string(),
+        })?;
+        if url.host_str().is_none() {
+            return Err(Error::InvalidUrl {
+                url: sitemap_url.to_string(),
+            });
+        }
+
+        let path = url.path();
+        let directory = path[..=path.rfind('/').unwrap_or(0)].to_string();
+
+        Ok(Self {
+            sitemap_url: url,
+            directory,
+            cross_submit_hosts: Vec::new(),
+            asset_hosts: Vec::new(),
+            warn_only: false,
+        })
+    }
+
+    /// Allow page URLs on a host that cross-submits this sitemap via robots.txt
+    ///
+    /// Cross-submitted hosts are also allowed for images and videos.
+    pub fn cross_submit_host(mut self, host: impl Into<String>) -> Self {
+        self.cross_submit_hosts.push(host.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Allow image and video files on another host, such as a CDN
+    ///
+    /// A leading `*.` matches any subdomain (`*.cdn.example.com`).
+    pub fn asset_host(mut self, host: impl Into<String>) -> Self {
+        self.asset_hosts.push(host.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Report violations through `scope_violations()` without failing the build
+    pub fn warn_only(mut self, warn_only: bool) -> Self {
+        self.warn_only = warn_only;
+        self
+    }
+
+    /// Get the sitemap URL
+    pub fn sitemap_url(&self) -> &str {
+        self.sitemap_url.as_str()
+    }
+
+    /// Check whether a page URL may be listed in the sitemap
+    ///
+    /// Unparseable URLs are reported as in scope; URL validation rejects them.
+    pub fn allows_loc(&self, loc: &str) -> bool {
+        let Ok(url) = Url::parse(loc) else {
+            return true;
+        };
+
+        if url.origin() == self.sitemap_url.origin() && url.path().starts_with(&self.directory) {
+            return true;
+        }
+
+        url.host_str()
+            .is_some_and(|host| self.cross_submit_hosts.iter().any(|h| h == host))
+    }
+
+    /// Check whether an image or video file URL is on an allowed host
+    pub fn allows_asset(&self, asset: &str) -> bool {
+        let Ok(url) = Url::parse(asset) else {
+            return true;
+        };
+        let Some(host) = url.host_str() else {
+            return true;
+        };
+
+        self.sitemap_url.host_str() == Some(host)
+            || self.cross_submit_hosts.iter().any(|h| h == host)
+            || self.asset_hosts.iter().any(|pattern| host_matches(pattern, host))
+    }
+
+    /// Collect the violations of a list of entries, resolving their URLs against `base`
+    pub(crate) fn violations<T: ScopedEntry + ResolveUrls + Clone>(
+        &self,
+        base: Option<&Url>,
+        entries: &[T],
+    ) -> Vec<ScopeViolation> {
+        let mut violations = Vec::new();
+        for entry in entries {
+            resolved(base, entry).check_scope(self, &mut violations);
+        }
+        violations
+    }
+
+    /// Fail on the first violation unless the scope only warns
+    pub(crate) fn enforce<T: ScopedEntry + ResolveUrls + Clone>(&self, base: Option<&Url>, entries: &[T]) -> Result<()> {
+        if self.warn_only {
+            return Ok(());
+        }
+
+        for (index, entry) in entries.iter().enumerate() {
+            let mut violations = Vec::new();
+            resolved(base, entry).check_scope(self, &mut violations);
+            if let Some(violation) = violations.into_iter().next() {
+                let page = violation.page.clone();
+                return Err(Error::OutOfScope(violation).in_entry(index, &page));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_loc(&self, loc: &str, violations: &mut Vec<ScopeViolation>) {
+        if !self.allows_loc(loc) {
+            violations.push(ScopeViolation {
+                url: loc.to_string(),
+                page: loc.to_string(),
+                kind: ScopeViolationKind::Loc,
+            });
+        }
+    }
+
+    fn check_images(&self, page: &str, images: &[ImageEntry], violations: &mut Vec<ScopeViolation>) {
+        for image in images {
+            if !self.allows_asset(&image.loc) {
+                violations.push(ScopeViolation {
+                    url: image.loc.clone(),
+                    page: page.to_string(),
+                    kind: ScopeViolationKind::Image,
+                });
+            }
+        }
+    }
+
+    fn check_videos(&self, page: &str, videos: &[VideoEntry], violations: &mut Vec<ScopeViolation>) {
+        for content_loc in videos.iter().filter_map(|v| v.content_loc.as_ref()) {
+            if !self.allows_asset(content_loc) {
+                violations.push(ScopeViolation {
+                    url: content_loc.clone(),
+                    page: page.to_string(),
+                    kind: ScopeViolationKind::VideoContent,
+                });
+            }
+        }
+    }
+}
+
+/// Entry types whose URLs can be checked against a [`SitemapScope`]
+pub(crate) trait ScopedEntry {
+    fn check_scope(&self, scope: &SitemapScope, violations: &mut Vec<ScopeViolation>);
+}
+
+impl ScopedEntry for UrlEntry {
+    fn check_scope(&self, scope: &SitemapScope, violations: &mut Vec<ScopeViolation>) {
+        scope.check_loc(&self.loc, violations);
+    }
+}
+
+impl ScopedEntry for UrlWithImages {
+    fn check_scope(&self, scope: &SitemapScope, violations: &mut Vec<ScopeViolation>) {
+        scope.check_loc(&self.url.loc, violations);
+        scope.check_images(&self.url.loc, &self.images, violations);
+    }
+}
+
+impl ScopedEntry for UrlWithVideos {
+    fn check_scope(&self, scope: &SitemapScope, violations: &mut Vec<ScopeViolation>) {
+        scope.check_loc(&self.url.loc, violations);
+        scope.check_videos(&self.url.loc, &self.videos, violations);
+    }
+}
+
+impl ScopedEntry for UrlWithNews {
+    fn check_scope(&self, scope: &SitemapScope, violations: &mut Vec<ScopeViolation>) {
+        scope.check_loc(&self.url.loc, violations);
+    }
+}
+
+impl ScopedEntry for UrlWithExtensions {
+    fn check_scope(&self, scope: &SitemapScope, violations: &mut Vec<ScopeViolation>) {
+        scope.check_loc(&self.url.loc, violations);
+        scope.check_images(&self.url.loc, &self.images, violations);
+        scope.check_videos(&self.url.loc, &self.videos, violations);
+    }
+}
+
+/// Match a host against an allowlist entry, with `*.` subdomain wildcards
+fn host_matches(pattern: &str, host: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => host
+            .strip_suffix(suffix)
+            .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+        None => pattern == host,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allows_loc() {
+        let scope = SitemapScope::new("https://example.com/catalog/sitemap.xml")
+            .unwrap()
+            .cross_submit_host("shop.example.net");
+
+        assert!(scope.allows_loc("https://example.com/catalog/"));
+        assert!(scope.allows_loc("https://example.com/catalog/items/1"));
+        assert!(!scope.allows_loc("https://example.com/about"));
+        assert!(!scope.allows_loc("http://example.com/catalog/items/1"));
+        assert!(!scope.allows_loc("https://example.com:8443/catalog/items/1"));
+        assert!(!scope.allows_loc("https://www.example.com/catalog/items/1"));
+        assert!(scope.allows_loc("https://shop.example.net/anything"));
+    }
+
+    #[test]
+    fn test_allows_asset() {
+        let scope = SitemapScope::new("https://example.com/sitemap.xml")
+            .unwrap()
+            .asset_host("*.cdn.example.org")
+            .asset_host("media.example.com");
+
+        assert!(scope.allows_asset("https://example.com/img/a.jpg"));
+        assert!(scope.allows_asset("https://eu.cdn.example.org/a.jpg"));
+        assert!(!scope.allows_asset("https://cdn.example.org/a.jpg"));
+        assert!(!scope.allows_asset("https://evilcdn.example.org/a.jpg"));
+        assert!(scope.allows_asset("https://media.example.com/v.mp4"));
+        assert!(!scope.allows_asset("https://other.example/v.mp4"));
+    }
+
+    #[test]
+    fn test_violations() {
+        let scope = SitemapScope::new("https://example.com/sitemap.xml").unwrap();
+        let entries = vec![
+            UrlWithExtensions::new(UrlEntry::new("https://other.example/page"))
+                .add_image(ImageEntry::new("https://cdn.example.org/a.jpg")),
+        ];
+
+        let violations = scope.violations(None, &entries);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].kind, ScopeViolationKind::Loc);
+        assert_eq!(violations[1].kind, ScopeViolationKind::Image);
+        assert_eq!(violations[1].page, "https://other.example/page");
+
+        assert!(matches!(
+            scope.enforce(None, &entries),
+            Err(Error::Entry { index: 0, ref source, .. }) if matches!(**source, Error::OutOfScope(_))
+        ));
+        assert!(scope.warn_only(true).enforce(None, &entries).is_ok());
+    }
+}
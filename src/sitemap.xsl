@@ -0,0 +1,89 @@
+<?xml version="1.0" encoding="UTF-8"?>
+<!--
+  Default human-readable stylesheet bundled with sitemap_generator.
+  Renders both <urlset> and <sitemapindex> documents as HTML tables,
+  including image, video and news counts for each URL.
+-->
+<xsl:stylesheet version="1.0"
+    xmlns:xsl="http://www.w3.org/1999/XSL/Transform"
+    xmlns:sitemap="http://www.sitemaps.org/schemas/sitemap/0.9"
+    xmlns:image="http://www.google.com/schemas/sitemap-image/1.1"
+    xmlns:video="http://www.google.com/schemas/sitemap-video/1.1"
+    xmlns:news="http://www.google.com/schemas/sitemap-news/0.9"
+    exclude-result-prefixes="sitemap image video news">
+
+  <xsl:output method="html" version="5.0" encoding="UTF-8" indent="yes"/>
+
+  <xsl:template match="/">
+    <html>
+      <head>
+        <meta charset="UTF-8"/>
+        <title>XML Sitemap</title>
+        <style>
+          body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Roboto, sans-serif; color: #333; margin: 2em; }
+          h1 { font-size: 1.5em; }
+          p.summary { color: #666; }
+          table { border-collapse: collapse; width: 100%; font-size: 0.9em; }
+          th { text-align: left; background: #f3f4f6; }
+          th, td { padding: 6px 10px; border-bottom: 1px solid #e5e7eb; }
+          td.num { text-align: right; }
+          tr:hover td { background: #fafafa; }
+          a { color: #1d4ed8; text-decoration: none; word-break: break-all; }
+        </style>
+      </head>
+      <body>
+        <xsl:apply-templates select="sitemap:urlset | sitemap:sitemapindex"/>
+      </body>
+    </html>
+  </xsl:template>
+
+  <xsl:template match="sitemap:sitemapindex">
+    <h1>Sitemap Index</h1>
+    <p class="summary">This index contains <xsl:value-of select="count(sitemap:sitemap)"/> sitemaps.</p>
+    <table>
+      <tr>
+        <th>Sitemap</th>
+        <th>Last Modified</th>
+      </tr>
+      <xsl:for-each select="sitemap:sitemap">
+        <tr>
+          <td><a href="{sitemap:loc}"><xsl:value-of select="sitemap:loc"/></a></td>
+          <td><xsl:value-of select="sitemap:lastmod"/></td>
+        </tr>
+      </xsl:for-each>
+    </table>
+  </xsl:template>
+
+  <xsl:template match="sitemap:urlset">
+    <h1>XML Sitemap</h1>
+    <p class="summary">
+      This sitemap contains <xsl:value-of select="count(sitemap:url)"/> URLs,
+      <xsl:value-of select="count(sitemap:url/image:image)"/> images,
+      <xsl:value-of select="count(sitemap:url/video:video)"/> videos and
+      <xsl:value-of select="count(sitemap:url/news:news)"/> news articles.
+    </p>
+    <table>
+      <tr>
+        <th>URL</th>
+        <th>Last Modified</th>
+        <th>Change Frequency</th>
+        <th>Priority</th>
+        <th>Images</th>
+        <th>Videos</th>
+        <th>News</th>
+      </tr>
+      <xsl:for-each select="sitemap:url">
+        <tr>
+          <td><a href="{sitemap:loc}"><xsl:value-of select="sitemap:loc"/></a></td>
+          <td><xsl:value-of select="sitemap:lastmod"/></td>
+          <td><xsl:value-of select="sitemap:changefreq"/></td>
+          <td class="num"><xsl:value-of select="sitemap:priority"/></td>
+          <td class="num"><xsl:value-of select="count(image:image)"/></td>
+          <td class="num"><xsl:value-of select="count(video:video)"/></td>
+          <td><xsl:value-of select="news:news/news:title"/></td>
+        </tr>
+      </xsl:for-each>
+    </table>
+  </xsl:template>
+
+</xsl:stylesheet>
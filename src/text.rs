@@ -0,0 +1,132 @@
+//! Character-aware text length and truncation
+//!
+//! Google's limits on video titles, descriptions and categories are in
+//! characters, not bytes. [`LengthUnit`] picks how characters are counted and
+//! [`truncate`] shortens text to a limit for CMS content that runs over.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Ellipsis appended by [`truncate`]
+pub const ELLIPSIS: char = '…';
+
+/// How text length limits are measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LengthUnit {
+    /// Unicode scalar values (Rust `char`s)
+    #[default]
+    Chars,
+
+    /// Extended grapheme clusters, i.e. characters as a reader sees them
+    ///
+    /// A letter with combining marks or an emoji with modifiers counts once.
+    Graphemes,
+}
+
+impl LengthUnit {
+    /// Length of `text` in this unit
+    pub fn count(self, text: &str) -> usize {
+        match self {
+            LengthUnit::Chars => text.chars().count(),
+            LengthUnit::Graphemes => text.graphemes(true).count(),
+        }
+    }
+
+    /// Byte offset just past the first `n` units of `text`, or `None` if it is shorter
+    fn offset(self, text: &str, n: usize) -> Option<usize> {
+        if n == 0 {
+            return Some(0);
+        }
+        match self {
+            LengthUnit::Chars => text.char_indices().nth(n - 1).map(|(i, c)| i + c.len_utf8()),
+            LengthUnit::Graphemes => text.grapheme_indices(true).nth(n - 1).map(|(i, g)| i + g.len()),
+        }
+    }
+}
+
+/// Shorten `text` to at most `limit` units, ending with an [`ELLIPSIS`]
+///
+/// Text within the limit is returned unchanged. Otherwise it is cut at the last
+/// whitespace that leaves room for the ellipsis, so words are not split; text
+/// without a usable word boundary (such as Japanese or one long word) is cut
+/// between characters. Trailing whitespace and punctuation before the
+/// ellipsis are dropped.
+///
+/// # Example
+/// ```
+/// use sitemap_generator::text::{truncate, LengthUnit};
+///
+/// assert_eq!(truncate("Learn Rust in one hour", 15, LengthUnit::Chars), "Learn Rust in…");
+/// assert_eq!(truncate("Học Rust trong một giờ", 30, LengthUnit::Chars), "Học Rust trong một giờ");
+/// assert_eq!(truncate("東京の夜景を楽しむ", 6, LengthUnit::Chars), "東京の夜景…");
+/// ```
+pub fn truncate(text: &str, limit: usize, unit: LengthUnit) -> String {
+    let Some(cut) = unit.offset(text, limit.saturating_sub(1)) else {
+        return text.to_string();
+    };
+    if unit.offset(text, limit + 1).is_none() {
+        return text.to_string();
+    }
+    if limit == 0 {
+        return String::new();
+    }
+
+    let head = &text[..cut];
+    let at_word_end = text[cut..].starts_with(char::is_whitespace);
+    let head = match head.rfind(char::is_whitespace) {
+        Some(space) if !at_word_end && !head[..space].trim().is_empty() => &head[..space],
+        _ => head,
+    };
+
+    let mut truncated = head
+        .trim_end_matches(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | ':' | '-' | '–' | '—'))
+        .to_string();
+    truncated.push(ELLIPSIS);
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count() {
+        assert_eq!(LengthUnit::Chars.count("Tiếng Việt"), 10);
+        assert_eq!("Tiếng Việt".len(), 14);
+
+        // "é" as e + combining acute, and a family emoji joined by ZWJs
+        let text = "e\u{301}👨\u{200d}👩\u{200d}👧";
+        assert_eq!(LengthUnit::Chars.count(text), 7);
+        assert_eq!(LengthUnit::Graphemes.count(text), 2);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let title = "Hướng dẫn nấu phở bò, ngon như ngoài hàng";
+
+        assert_eq!(truncate(title, 100, LengthUnit::Chars), title);
+        assert_eq!(truncate(title, title.chars().count(), LengthUnit::Chars), title);
+
+        let short = truncate(title, 22, LengthUnit::Chars);
+        assert_eq!(short, "Hướng dẫn nấu phở bò…");
+        assert!(short.chars().count() <= 22);
+
+        // Cut falling exactly at the end of a word keeps that word
+        assert_eq!(truncate("one two three", 8, LengthUnit::Chars), "one two…");
+
+        // No word boundary: cut between characters
+        assert_eq!(truncate("Supercalifragilistic", 6, LengthUnit::Chars), "Super…");
+        assert_eq!(truncate("abc", 0, LengthUnit::Chars), "");
+        assert_eq!(truncate("abc", 1, LengthUnit::Chars), "…");
+    }
+
+    #[test]
+    fn test_truncate_graphemes() {
+        let text = "e\u{301}e\u{301}e\u{301}e\u{301}";
+        let truncated = truncate(text, 3, LengthUnit::Graphemes);
+        assert_eq!(truncated, "e\u{301}e\u{301}…");
+        assert_eq!(LengthUnit::Graphemes.count(&truncated), 3);
+
+        // Counting chars could split a combining mark from its letter
+        assert_eq!(truncate(text, 4, LengthUnit::Chars), "e\u{301}e…");
+    }
+}
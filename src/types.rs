@@ -1,7 +1,13 @@
 //! Type definitions for sitemap entries
 
+use crate::extension::ExtensionElement;
+use crate::text::{truncate, LengthUnit};
+use crate::validator::{MAX_VIDEO_CATEGORY_LEN, MAX_VIDEO_DESCRIPTION_LEN, MAX_VIDEO_TITLE_LEN};
+use serde::{Deserialize, Serialize};
+
 /// How frequently the page is likely to change
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ChangeFreq {
     Always,
     Hourly,
@@ -32,6 +38,23 @@ impl std::fmt::Display for ChangeFreq {
     }
 }
 
+impl std::str::FromStr for ChangeFreq {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "always" => Ok(ChangeFreq::Always),
+            "hourly" => Ok(ChangeFreq::Hourly),
+            "daily" => Ok(ChangeFreq::Daily),
+            "weekly" => Ok(ChangeFreq::Weekly),
+            "monthly" => Ok(ChangeFreq::Monthly),
+            "yearly" => Ok(ChangeFreq::Yearly),
+            "never" => Ok(ChangeFreq::Never),
+            _ => Err(crate::error::Error::InvalidChangeFreq { value: s.to_string() }),
+        }
+    }
+}
+
 /// A standard URL entry in a sitemap
 #[derive(Debug, Clone)]
 pub struct UrlEntry {
@@ -46,6 +69,9 @@ pub struct UrlEntry {
 
     /// The priority of this URL relative to other URLs (0.0 to 1.0)
     pub priority: Option<f32>,
+
+    /// Elements in custom namespaces, written after the other elements of the URL
+    pub extensions: Vec<ExtensionElement>,
 }
 
 impl UrlEntry {
@@ -56,6 +82,7 @@ impl UrlEntry {
             lastmod: None,
             changefreq: None,
             priority: None,
+            extensions: Vec::new(),
         }
     }
 
@@ -76,6 +103,14 @@ impl UrlEntry {
         self.priority = Some(priority);
         self
     }
+
+    /// Add an element in a custom namespace
+    ///
+    /// The namespace prefix must be registered on the builder, see [`crate::extension`].
+    pub fn add_extension(mut self, element: ExtensionElement) -> Self {
+        self.extensions.push(element);
+        self
+    }
 }
 
 /// Geographic location for an image
@@ -87,6 +122,7 @@ pub struct GeoLocation {
 }
 
 impl GeoLocation {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Self {
             city: None,
@@ -486,6 +522,29 @@ impl VideoEntry {
         self.live = Some(live);
         self
     }
+
+    /// Shorten the title, description and category to Google's limits
+    ///
+    /// Text over a limit is cut at a word boundary and ends with an ellipsis,
+    /// see [`text::truncate`](crate::text::truncate).
+    ///
+    /// # Example
+    /// ```
+    /// use sitemap_generator::{LengthUnit, VideoEntry};
+    ///
+    /// let video = VideoEntry::new("https://example.com/thumb.jpg", "Tin tức ".repeat(20), "Mô tả")
+    ///     .truncate_to_limits(LengthUnit::Chars);
+    /// assert_eq!(video.title.chars().count(), 100);
+    /// assert!(video.title.ends_with("tức Tin…"));
+    /// ```
+    pub fn truncate_to_limits(mut self, unit: LengthUnit) -> Self {
+        self.title = truncate(&self.title, MAX_VIDEO_TITLE_LEN, unit);
+        self.description = truncate(&self.description, MAX_VIDEO_DESCRIPTION_LEN, unit);
+        self.category = self
+            .category
+            .map(|category| truncate(&category, MAX_VIDEO_CATEGORY_LEN, unit));
+        self
+    }
 }
 
 /// A URL entry with associated videos
@@ -560,6 +619,80 @@ impl NewsPublication {
     }
 }
 
+/// A stock ticker of a news article, e.g. `NASDAQ:AAPL`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StockTicker {
+    /// Exchange the stock is listed on, e.g. `NASDAQ`
+    pub exchange: Option<String>,
+
+    /// Ticker symbol, e.g. `AAPL`
+    pub symbol: String,
+}
+
+impl StockTicker {
+    /// Create a ticker listed on an exchange
+    pub fn new(exchange: impl Into<String>, symbol: impl Into<String>) -> Self {
+        Self {
+            exchange: Some(exchange.into()),
+            symbol: symbol.into(),
+        }
+    }
+
+    /// Create a ticker without an exchange
+    pub fn without_exchange(symbol: impl Into<String>) -> Self {
+        Self {
+            exchange: None,
+            symbol: symbol.into(),
+        }
+    }
+
+    /// Parse a comma-separated `<news:stock_tickers>` value
+    ///
+    /// # Example
+    /// ```
+    /// use sitemap_generator::StockTicker;
+    ///
+    /// let tickers = StockTicker::parse_list("NASDAQ:AAPL, NYSE:BRK.B").unwrap();
+    /// assert_eq!(tickers[1], StockTicker::new("NYSE", "BRK.B"));
+    /// assert!(StockTicker::parse_list("NASDAQ:AAPL, :MSFT").is_err());
+    /// ```
+    pub fn parse_list(tickers: &str) -> Result<Vec<StockTicker>, crate::error::Error> {
+        split_list(tickers).map(str::parse).collect()
+    }
+
+    /// Split `EXCHANGE:SYMBOL` without checking the parts
+    pub(crate) fn parse_lenient(ticker: &str) -> Self {
+        match ticker.trim().split_once(':') {
+            Some((exchange, symbol)) => Self::new(exchange.trim(), symbol.trim()),
+            None => Self::without_exchange(ticker.trim()),
+        }
+    }
+}
+
+impl std::fmt::Display for StockTicker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.exchange {
+            Some(ref exchange) => write!(f, "{}:{}", exchange, self.symbol),
+            None => write!(f, "{}", self.symbol),
+        }
+    }
+}
+
+impl std::str::FromStr for StockTicker {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ticker = Self::parse_lenient(s);
+        crate::validator::Validator::validate_stock_ticker(&ticker)?;
+        Ok(ticker)
+    }
+}
+
+/// Split a comma-separated list, trimming items and skipping empty ones
+fn split_list(list: &str) -> impl Iterator<Item = &str> {
+    list.split(',').map(str::trim).filter(|item| !item.is_empty())
+}
+
 /// A news article entry for Google News sitemap
 #[derive(Debug, Clone)]
 pub struct NewsEntry {
@@ -575,12 +708,12 @@ pub struct NewsEntry {
     pub title: String,
 
     /// Keywords describing the topic (optional)
-    /// Comma-separated list
-    pub keywords: Option<String>,
+    /// Written comma-separated, so keywords cannot contain commas
+    pub keywords: Vec<String>,
 
     /// Stock tickers related to the article (optional)
-    /// Up to 5 tickers, comma-separated
-    pub stock_tickers: Option<String>,
+    /// Up to 5 tickers
+    pub stock_tickers: Vec<StockTicker>,
 }
 
 impl NewsEntry {
@@ -593,20 +726,66 @@ impl NewsEntry {
             publication,
             publication_date: publication_date.into(),
             title: title.into(),
-            keywords: None,
-            stock_tickers: None,
+            keywords: Vec::new(),
+            stock_tickers: Vec::new(),
         }
     }
 
-    pub fn keywords(mut self, keywords: impl Into<String>) -> Self {
-        self.keywords = Some(keywords.into());
+    /// Set the keywords from a comma-separated list
+    pub fn keywords(mut self, keywords: impl AsRef<str>) -> Self {
+        self.keywords = split_list(keywords.as_ref()).map(String::from).collect();
+        self
+    }
+
+    /// Add a free-form keyword; Google sets no limit on their number
+    pub fn add_keyword(mut self, keyword: impl Into<String>) -> Self {
+        self.keywords.push(keyword.into());
+        self
+    }
+
+    /// Add several free-form keywords; Google sets no limit on their number
+    pub fn add_keywords<I>(mut self, keywords: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.keywords.extend(keywords.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set the stock tickers from a comma-separated list such as `"NASDAQ:AAPL, NYSE:IBM"`
+    ///
+    /// Malformed tickers are kept as given and reported by validation.
+    pub fn stock_tickers(mut self, tickers: impl AsRef<str>) -> Self {
+        self.stock_tickers = split_list(tickers.as_ref()).map(StockTicker::parse_lenient).collect();
         self
     }
 
-    pub fn stock_tickers(mut self, tickers: impl Into<String>) -> Self {
-        self.stock_tickers = Some(tickers.into());
+    /// Add a stock ticker; Google accepts at most 5 per article
+    pub fn add_stock_ticker(mut self, ticker: StockTicker) -> Self {
+        self.stock_tickers.push(ticker);
         self
     }
+
+    /// Add several stock tickers; Google accepts at most 5 per article
+    pub fn add_stock_tickers(mut self, tickers: impl IntoIterator<Item = StockTicker>) -> Self {
+        self.stock_tickers.extend(tickers);
+        self
+    }
+
+    /// Keywords as written in `<news:keywords>`
+    pub fn keywords_text(&self) -> String {
+        self.keywords.join(", ")
+    }
+
+    /// Stock tickers as written in `<news:stock_tickers>`
+    pub fn stock_tickers_text(&self) -> String {
+        self.stock_tickers
+            .iter()
+            .map(StockTicker::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
 }
 
 /// A URL entry with associated news article
@@ -678,3 +857,42 @@ impl UrlWithExtensions {
         !self.images.is_empty() || !self.videos.is_empty() || self.news.is_some()
     }
 }
+
+/// Access to the `UrlEntry` at the core of every URL-level sitemap entry
+///
+/// Lets URL-level processing (such as normalization) work on any entry type.
+pub trait AsUrlEntry {
+    /// Get the URL entry
+    fn url_entry(&self) -> &UrlEntry;
+
+    /// Get the URL entry mutably
+    fn url_entry_mut(&mut self) -> &mut UrlEntry;
+}
+
+impl AsUrlEntry for UrlEntry {
+    fn url_entry(&self) -> &UrlEntry {
+        self
+    }
+
+    fn url_entry_mut(&mut self) -> &mut UrlEntry {
+        self
+    }
+}
+
+macro_rules! impl_as_url_entry {
+    ($($ty:ty),*) => {
+        $(
+            impl AsUrlEntry for $ty {
+                fn url_entry(&self) -> &UrlEntry {
+                    &self.url
+                }
+
+                fn url_entry_mut(&mut self) -> &mut UrlEntry {
+                    &mut self.url
+                }
+            }
+        )*
+    };
+}
+
+impl_as_url_entry!(UrlWithImages, UrlWithVideos, UrlWithNews, UrlWithExtensions);
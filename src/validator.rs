@@ -1,6 +1,10 @@
 //! Validation utilities for sitemap entries
 
 use crate::error::{Error, Result};
+use crate::iso;
+use crate::text::LengthUnit;
+use crate::types::*;
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone, Utc};
 use url::Url;
 
 /// Maximum number of URLs allowed in a sitemap
@@ -15,6 +19,21 @@ pub const MAX_URL_LENGTH: usize = 2048;
 /// Maximum number of URLs allowed in a news sitemap
 pub const MAX_NEWS_URLS: usize = 1_000;
 
+/// Maximum number of stock tickers in a news entry
+pub const MAX_STOCK_TICKERS: usize = 5;
+
+/// Maximum number of tags on a video
+pub const MAX_VIDEO_TAGS: usize = 32;
+
+/// Maximum length of a video title, in characters
+pub const MAX_VIDEO_TITLE_LEN: usize = 100;
+
+/// Maximum length of a video description, in characters
+pub const MAX_VIDEO_DESCRIPTION_LEN: usize = 2048;
+
+/// Maximum length of a video category, in characters
+pub const MAX_VIDEO_CATEGORY_LEN: usize = 256;
+
 /// Validator for sitemap entries
 pub struct Validator;
 
@@ -23,11 +42,15 @@ impl Validator {
     pub fn validate_url(url: &str) -> Result<()> {
         // Check length
         if url.len() > MAX_URL_LENGTH {
-            return Err(Error::UrlTooLong(url.to_string()));
+            return Err(Error::UrlTooLong {
+                url: url.to_string(),
+                actual: url.len(),
+                limit: MAX_URL_LENGTH,
+            });
         }
 
         // Parse URL to ensure it's valid
-        Url::parse(url).map_err(|_| Error::InvalidUrl(url.to_string()))?;
+        Url::parse(url).map_err(|_| Error::InvalidUrl { url: url.to_string() })?;
 
         Ok(())
     }
@@ -35,36 +58,46 @@ impl Validator {
     /// Validate priority value (must be 0.0 to 1.0)
     pub fn validate_priority(priority: f32) -> Result<()> {
         if !(0.0..=1.0).contains(&priority) {
-            return Err(Error::InvalidPriority(priority));
+            return Err(Error::InvalidPriority { value: priority });
         }
         Ok(())
     }
 
-    /// Validate date format (W3C Datetime)
+    /// Validate date format (W3C Datetime) of a `<lastmod>`
     pub fn validate_date(date: &str) -> Result<()> {
+        Self::validate_date_field("lastmod", date)
+    }
+
+    /// Validate date format (W3C Datetime) of the named field
+    pub fn validate_date_field(field: &'static str, date: &str) -> Result<()> {
+        let invalid = || Error::InvalidDate {
+            field,
+            value: date.to_string(),
+        };
+
         // Basic validation for W3C Datetime format
         // Accept YYYY-MM-DD or full ISO 8601 datetime
         if date.len() < 10 {
-            return Err(Error::InvalidDate(date.to_string()));
+            return Err(invalid());
         }
 
         // Check basic format YYYY-MM-DD
         let parts: Vec<&str> = date.split('-').collect();
         if parts.len() < 3 {
-            return Err(Error::InvalidDate(date.to_string()));
+            return Err(invalid());
         }
 
         // Validate year, month, day are numeric
         if parts[0].len() != 4 || !parts[0].chars().all(|c| c.is_numeric()) {
-            return Err(Error::InvalidDate(date.to_string()));
+            return Err(invalid());
         }
         if parts[1].len() != 2 || !parts[1].chars().all(|c| c.is_numeric()) {
-            return Err(Error::InvalidDate(date.to_string()));
+            return Err(invalid());
         }
 
         // Day part might contain time, so just check first 2 chars
         if parts[2].len() < 2 || !parts[2].chars().take(2).all(|c| c.is_numeric()) {
-            return Err(Error::InvalidDate(date.to_string()));
+            return Err(invalid());
         }
 
         Ok(())
@@ -73,7 +106,10 @@ impl Validator {
     /// Validate number of URLs doesn't exceed maximum
     pub fn validate_url_count(count: usize) -> Result<()> {
         if count > MAX_URLS {
-            return Err(Error::TooManyUrls(count));
+            return Err(Error::TooManyUrls {
+                actual: count,
+                limit: MAX_URLS,
+            });
         }
         Ok(())
     }
@@ -81,7 +117,10 @@ impl Validator {
     /// Validate sitemap size doesn't exceed maximum
     pub fn validate_size(size: usize) -> Result<()> {
         if size > MAX_SIZE_BYTES {
-            return Err(Error::SizeExceeded(size));
+            return Err(Error::SizeExceeded {
+                actual: size,
+                limit: MAX_SIZE_BYTES,
+            });
         }
         Ok(())
     }
@@ -89,10 +128,11 @@ impl Validator {
     /// Validate video duration (0 to 28800 seconds = 8 hours)
     pub fn validate_video_duration(duration: u32) -> Result<()> {
         if duration > 28_800 {
-            return Err(Error::Validation(format!(
-                "Video duration exceeds maximum (28800 seconds): {}",
-                duration
-            )));
+            return Err(Error::InvalidField {
+                field: "video:duration",
+                value: duration.to_string(),
+                reason: "must be at most 28800 seconds",
+            });
         }
         Ok(())
     }
@@ -100,43 +140,189 @@ impl Validator {
     /// Validate video rating (0.0 to 5.0)
     pub fn validate_video_rating(rating: f32) -> Result<()> {
         if !(0.0..=5.0).contains(&rating) {
-            return Err(Error::Validation(format!(
-                "Video rating must be between 0.0 and 5.0: {}",
-                rating
-            )));
+            return Err(Error::InvalidField {
+                field: "video:rating",
+                value: rating.to_string(),
+                reason: "must be between 0.0 and 5.0",
+            });
         }
         Ok(())
     }
 
-    /// Validate video title length (max 100 characters)
-    pub fn validate_video_title(title: &str) -> Result<()> {
-        if title.len() > 100 {
-            return Err(Error::Validation(format!(
-                "Video title exceeds 100 characters: {}",
-                title.len()
-            )));
+    /// Validate the length of a text field, counted in `unit`
+    pub fn validate_text_length(field: &'static str, text: &str, limit: usize, unit: LengthUnit) -> Result<()> {
+        // Byte length is an upper bound on both units, so short text skips counting
+        if text.len() <= limit {
+            return Ok(());
+        }
+        let len = unit.count(text);
+        if len > limit {
+            return Err(Error::FieldTooLong {
+                field,
+                actual: len,
+                limit,
+            });
         }
         Ok(())
     }
 
+    /// Validate video title length (max 100 characters)
+    pub fn validate_video_title(title: &str) -> Result<()> {
+        Self::validate_text_length("video:title", title, MAX_VIDEO_TITLE_LEN, LengthUnit::Chars)
+    }
+
     /// Validate video description length (max 2048 characters)
     pub fn validate_video_description(description: &str) -> Result<()> {
-        if description.len() > 2048 {
-            return Err(Error::Validation(format!(
-                "Video description exceeds 2048 characters: {}",
-                description.len()
-            )));
+        Self::validate_text_length(
+            "video:description",
+            description,
+            MAX_VIDEO_DESCRIPTION_LEN,
+            LengthUnit::Chars,
+        )
+    }
+
+    /// Validate video tag count (max 32 tags)
+    pub fn validate_video_tags(tags: &[String]) -> Result<()> {
+        if tags.len() > MAX_VIDEO_TAGS {
+            return Err(Error::TooManyValues {
+                field: "video:tag",
+                actual: tags.len(),
+                limit: MAX_VIDEO_TAGS,
+            });
+        }
+        Ok(())
+    }
+
+    /// Validate video category length (max 256 characters)
+    pub fn validate_video_category(category: &str) -> Result<()> {
+        Self::validate_text_length("video:category", category, MAX_VIDEO_CATEGORY_LEN, LengthUnit::Chars)
+    }
+
+    /// Validate a country code (ISO 3166-1 alpha-2, uppercase)
+    pub fn validate_country_code(code: &str) -> Result<()> {
+        if !iso::is_country_code(code) {
+            return Err(Error::InvalidField {
+                field: "video:restriction",
+                value: code.to_string(),
+                reason: "must be an ISO 3166-1 alpha-2 country code",
+            });
+        }
+        Ok(())
+    }
+
+    /// Validate a currency code (ISO 4217, uppercase)
+    pub fn validate_currency_code(code: &str) -> Result<()> {
+        if !iso::is_currency_code(code) {
+            return Err(Error::InvalidField {
+                field: "video:price",
+                value: code.to_string(),
+                reason: "currency must be an ISO 4217 code",
+            });
+        }
+        Ok(())
+    }
+
+    /// Validate a video against all rules of the Google video extension
+    ///
+    /// `page_url` is the `<loc>` of the page hosting the video, and `unit` is how
+    /// the title, description and category lengths are counted. On top of the
+    /// field checks above, this requires exactly one of `content_loc` and
+    /// `player_loc`, different from the page URL, an uploader info URL on the
+    /// page's domain, and an expiration date after the publication date.
+    pub fn validate_video(page_url: &str, video: &VideoEntry, unit: LengthUnit) -> Result<()> {
+        Self::validate_url(&video.thumbnail_loc)?;
+        Self::validate_text_length("video:title", &video.title, MAX_VIDEO_TITLE_LEN, unit)?;
+        Self::validate_text_length("video:description", &video.description, MAX_VIDEO_DESCRIPTION_LEN, unit)?;
+
+        let (field, location) = match (&video.content_loc, &video.player_loc) {
+            (Some(content_loc), None) => ("video:content_loc", content_loc),
+            (None, Some(player_loc)) => ("video:player_loc", player_loc),
+            (Some(_), Some(player_loc)) => {
+                return Err(Error::InvalidField {
+                    field: "video:player_loc",
+                    value: player_loc.clone(),
+                    reason: "only one of content_loc and player_loc is allowed",
+                })
+            }
+            (None, None) => {
+                return Err(Error::InvalidField {
+                    field: "video:content_loc",
+                    value: String::new(),
+                    reason: "one of content_loc and player_loc is required",
+                })
+            }
+        };
+        Self::validate_url(location)?;
+        if location == page_url {
+            return Err(Error::InvalidField {
+                field,
+                value: location.clone(),
+                reason: "must differ from the page URL",
+            });
+        }
+
+        if let Some(duration) = video.duration {
+            Self::validate_video_duration(duration)?;
+        }
+
+        if let Some(rating) = video.rating {
+            Self::validate_video_rating(rating)?;
+        }
+
+        if let Some(ref pub_date) = video.publication_date {
+            Self::validate_date_field("video:publication_date", pub_date)?;
+        }
+
+        if let Some(ref exp_date) = video.expiration_date {
+            Self::validate_date_field("video:expiration_date", exp_date)?;
+
+            let published = video.publication_date.as_deref().and_then(parse_w3c_datetime);
+            if published.is_some_and(|published| parse_w3c_datetime(exp_date).is_some_and(|exp| exp <= published)) {
+                return Err(Error::InvalidField {
+                    field: "video:expiration_date",
+                    value: exp_date.clone(),
+                    reason: "must be after the publication date",
+                });
+            }
+        }
+
+        Self::validate_video_tags(&video.tags)?;
+
+        if let Some(ref category) = video.category {
+            Self::validate_text_length("video:category", category, MAX_VIDEO_CATEGORY_LEN, unit)?;
+        }
+
+        if let Some(VideoCountryRestriction::Allow(codes) | VideoCountryRestriction::Deny(codes)) = &video.restriction {
+            for code in codes {
+                Self::validate_country_code(code)?;
+            }
+        }
+
+        for price in &video.prices {
+            Self::validate_currency_code(&price.currency)?;
         }
+
+        if let Some(info_url) = video.uploader.as_ref().and_then(|u| u.info_url.as_ref()) {
+            Self::validate_url(info_url)?;
+            if !same_domain(page_url, info_url) {
+                return Err(Error::InvalidField {
+                    field: "video:uploader",
+                    value: info_url.clone(),
+                    reason: "info URL must be on the same domain as the page",
+                });
+            }
+        }
+
         Ok(())
     }
 
     /// Validate news sitemap URL count (max 1,000 URLs)
     pub fn validate_news_url_count(count: usize) -> Result<()> {
         if count > MAX_NEWS_URLS {
-            return Err(Error::Validation(format!(
-                "News sitemap exceeds maximum of {} URLs: {}",
-                MAX_NEWS_URLS, count
-            )));
+            return Err(Error::TooManyUrls {
+                actual: count,
+                limit: MAX_NEWS_URLS,
+            });
         }
         Ok(())
     }
@@ -152,23 +338,153 @@ impl Validator {
         if code == "zh-cn" || code == "zh-tw" {
             return Ok(());
         }
-        Err(Error::Validation(format!(
-            "Invalid language code (must be ISO 639 2-3 letters or zh-cn/zh-tw): {}",
-            code
-        )))
+        Err(Error::InvalidField {
+            field: "news:language",
+            value: code.to_string(),
+            reason: "must be ISO 639 2-3 letters or zh-cn/zh-tw",
+        })
     }
 
-    /// Validate stock tickers (max 5, comma-separated)
-    pub fn validate_stock_tickers(tickers: &str) -> Result<()> {
-        let ticker_list: Vec<&str> = tickers.split(',').map(|s| s.trim()).collect();
-        if ticker_list.len() > 5 {
-            return Err(Error::Validation(format!(
-                "Stock tickers exceed maximum of 5: {}",
-                ticker_list.len()
-            )));
+    /// Validate news keywords (non-empty, without commas)
+    pub fn validate_keywords(keywords: &[String]) -> Result<()> {
+        for keyword in keywords {
+            let reason = if keyword.trim().is_empty() {
+                "keywords must not be empty"
+            } else if keyword.contains(',') {
+                "keywords must not contain commas"
+            } else {
+                continue;
+            };
+            return Err(Error::InvalidField {
+                field: "news:keywords",
+                value: keyword.clone(),
+                reason,
+            });
         }
         Ok(())
     }
+
+    /// Validate stock tickers (max 5, each `EXCHANGE:SYMBOL` or `SYMBOL`)
+    pub fn validate_stock_tickers(tickers: &[StockTicker]) -> Result<()> {
+        if tickers.len() > MAX_STOCK_TICKERS {
+            return Err(Error::TooManyValues {
+                field: "news:stock_tickers",
+                actual: tickers.len(),
+                limit: MAX_STOCK_TICKERS,
+            });
+        }
+        tickers.iter().try_for_each(Self::validate_stock_ticker)
+    }
+
+    /// Validate a single stock ticker
+    #[allow(clippy::unnecessary_map_or)] // `Option::is_none_or` needs Rust 1.82
+    pub fn validate_stock_ticker(ticker: &StockTicker) -> Result<()> {
+        let well_formed = |part: &str| {
+            !part.is_empty() && !part.contains(|c: char| c.is_whitespace() || c == ',' || c == ':')
+        };
+        if well_formed(&ticker.symbol) && ticker.exchange.as_deref().map_or(true, well_formed) {
+            return Ok(());
+        }
+        Err(Error::InvalidField {
+            field: "news:stock_tickers",
+            value: ticker.to_string(),
+            reason: "must be EXCHANGE:SYMBOL or SYMBOL",
+        })
+    }
+}
+
+/// Check that two URLs share a host, or that one host is a subdomain of the other
+fn same_domain(a: &str, b: &str) -> bool {
+    match (Url::parse(a), Url::parse(b)) {
+        (Ok(a), Ok(b)) => match (a.host_str(), b.host_str()) {
+            (Some(a), Some(b)) => {
+                let (a, b) = (a.to_ascii_lowercase(), b.to_ascii_lowercase());
+                a == b || a.ends_with(&format!(".{}", b)) || b.ends_with(&format!(".{}", a))
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Minimum number of entries before validation runs in parallel
+#[cfg(feature = "rayon")]
+pub(crate) const PARALLEL_VALIDATION_THRESHOLD: usize = 10_000;
+
+/// Entry types whose validation errors name the entry's page URL
+pub(crate) trait EntryUrl {
+    fn entry_url(&self) -> &str;
+}
+
+impl EntryUrl for UrlEntry {
+    fn entry_url(&self) -> &str {
+        &self.loc
+    }
+}
+
+impl EntryUrl for SitemapIndexEntry {
+    fn entry_url(&self) -> &str {
+        &self.loc
+    }
+}
+
+macro_rules! impl_entry_url {
+    ($($ty:ty),*) => {
+        $(impl EntryUrl for $ty {
+            fn entry_url(&self) -> &str {
+                &self.url.loc
+            }
+        })*
+    };
+}
+
+impl_entry_url!(UrlWithImages, UrlWithVideos, UrlWithNews, UrlWithExtensions);
+
+/// Run a check on every entry, stopping at the first failure
+///
+/// The error is wrapped in [`Error::Entry`] with the index and URL of the
+/// entry. With the `rayon` feature, large lists are checked in parallel; the
+/// error returned is still the one of the first failing entry.
+pub(crate) fn validate_each<T, F>(entries: &[T], check: F) -> Result<()>
+where
+    T: EntryUrl + Sync,
+    F: Fn(&T) -> Result<()> + Sync + Send,
+{
+    let check = |(index, entry): (usize, &T)| check(entry).map_err(|err| err.in_entry(index, entry.entry_url()));
+
+    #[cfg(feature = "rayon")]
+    if entries.len() >= PARALLEL_VALIDATION_THRESHOLD {
+        use rayon::prelude::*;
+        return entries
+            .par_iter()
+            .enumerate()
+            .map(check)
+            .find_first(|result| result.is_err())
+            .unwrap_or(Ok(()));
+    }
+
+    entries.iter().enumerate().try_for_each(check)
+}
+
+/// Parse a W3C Datetime value into a timestamp
+///
+/// Accepts `YYYY-MM-DD` (midnight UTC) and full date-times with a timezone,
+/// with or without seconds and fractional seconds.
+pub(crate) fn parse_w3c_datetime(date: &str) -> Option<DateTime<FixedOffset>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(date) {
+        return Some(dt);
+    }
+    // hh:mm without seconds is allowed by W3C Datetime
+    if let Ok(dt) = DateTime::parse_from_str(date, "%Y-%m-%dT%H:%M%:z") {
+        return Some(dt);
+    }
+    if let Some(naive) = date.strip_suffix('Z') {
+        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(naive, "%Y-%m-%dT%H:%M") {
+            return Some(Utc.from_utc_datetime(&dt).fixed_offset());
+        }
+    }
+    let day = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    Some(Utc.from_utc_datetime(&day.and_hms_opt(0, 0, 0)?).fixed_offset())
 }
 
 #[cfg(test)]
@@ -204,6 +520,41 @@ mod tests {
         assert!(Validator::validate_date("24-01-01").is_err());
     }
 
+    #[test]
+    fn test_parse_w3c_datetime() {
+        let day = parse_w3c_datetime("2025-11-01").unwrap();
+        assert_eq!(day.to_rfc3339(), "2025-11-01T00:00:00+00:00");
+        assert!(parse_w3c_datetime("2025-11-01T12:30Z").is_some());
+        assert!(parse_w3c_datetime("2025-11-01T12:30+07:00").is_some());
+        assert!(parse_w3c_datetime("2025-11-01T12:30:45.5+07:00").is_some());
+        assert!(parse_w3c_datetime("2025-13-01").is_none());
+        assert!(
+            parse_w3c_datetime("2025-11-01T08:00:00+07:00").unwrap()
+                < parse_w3c_datetime("2025-11-01T02:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_validate_each_reports_first_error() {
+        let urls: Vec<UrlEntry> = (0..20_000)
+            .map(|i| match i {
+                12_000 | 15_000 => UrlEntry::new(format!("bad url {}", i)),
+                _ => UrlEntry::new(format!("https://example.com/{}", i)),
+            })
+            .collect();
+
+        let result = validate_each(&urls, |entry| Validator::validate_url(&entry.loc));
+        match result {
+            Err(Error::Entry { index, url, source }) => {
+                assert_eq!(index, 12_000);
+                assert_eq!(url, "bad url 12000");
+                assert!(matches!(*source, Error::InvalidUrl { .. }));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(validate_each(&urls[..12_000], |entry| Validator::validate_url(&entry.loc)).is_ok());
+    }
+
     #[test]
     fn test_validate_url_count() {
         assert!(Validator::validate_url_count(1000).is_ok());
@@ -220,6 +571,62 @@ mod tests {
         assert!(Validator::validate_video_rating(5.1).is_err());
     }
 
+    #[test]
+    fn test_validate_text_length() {
+        // 60 characters, 180 bytes
+        let title = "東京".repeat(30);
+        assert!(Validator::validate_video_title(&title).is_ok());
+        assert!(matches!(
+            Validator::validate_video_title(&"東".repeat(101)),
+            Err(Error::FieldTooLong { field: "video:title", actual: 101, limit: 100 })
+        ));
+
+        // 100 graphemes of e + combining acute, 200 chars
+        let accented = "e\u{301}".repeat(100);
+        assert!(Validator::validate_text_length("video:title", &accented, 100, LengthUnit::Graphemes).is_ok());
+        assert!(Validator::validate_text_length("video:title", &accented, 100, LengthUnit::Chars).is_err());
+    }
+
+    #[test]
+    fn test_validate_video() {
+        let page = "https://www.example.com/watch/1";
+        let video = || {
+            VideoEntry::new("https://example.com/thumb.jpg", "Title", "Description")
+                .content_loc("https://cdn.example.com/1.mp4")
+        };
+        let field = |video: VideoEntry| match Validator::validate_video(page, &video, LengthUnit::Chars) {
+            Err(Error::InvalidField { field, .. }) => field,
+            Err(Error::TooManyValues { field, .. }) | Err(Error::FieldTooLong { field, .. }) => field,
+            other => panic!("unexpected result: {:?}", other),
+        };
+
+        let valid = video()
+            .publication_date("2025-11-01")
+            .expiration_date("2025-11-01T00:00:01Z")
+            .restriction(VideoCountryRestriction::Deny(vec!["GB".to_string()]))
+            .add_price(VideoPrice::new("EUR", 2.0))
+            .uploader(VideoUploader::new("Studio").info_url("https://example.com/studio"));
+        assert!(Validator::validate_video(page, &valid, LengthUnit::Chars).is_ok());
+
+        let neither = VideoEntry::new("https://example.com/thumb.jpg", "Title", "Description");
+        assert_eq!(field(neither), "video:content_loc");
+        assert_eq!(field(video().player_loc("https://example.com/player")), "video:player_loc");
+        let on_page = VideoEntry::new("https://example.com/thumb.jpg", "Title", "Description").player_loc(page);
+        assert_eq!(field(on_page), "video:player_loc");
+
+        assert_eq!(field(video().restriction(VideoCountryRestriction::Allow(vec!["UK".to_string()]))), "video:restriction");
+        assert_eq!(field(video().add_price(VideoPrice::new("usd", 1.0))), "video:price");
+        assert_eq!(field((0..33).fold(video(), |v, i| v.add_tag(format!("tag{}", i)))), "video:tag");
+        assert_eq!(field(video().category("é".repeat(257))), "video:category");
+        assert!(Validator::validate_video(page, &video().category("é".repeat(256)), LengthUnit::Chars).is_ok());
+
+        let uploader = video().uploader(VideoUploader::new("Studio").info_url("https://example.org/studio"));
+        assert_eq!(field(uploader), "video:uploader");
+
+        let expired = video().publication_date("2025-11-01").expiration_date("2025-11-01");
+        assert_eq!(field(expired), "video:expiration_date");
+    }
+
     #[test]
     fn test_validate_news_url_count() {
         assert!(Validator::validate_news_url_count(100).is_ok());
@@ -251,9 +658,20 @@ mod tests {
 
     #[test]
     fn test_validate_stock_tickers() {
-        assert!(Validator::validate_stock_tickers("AAPL").is_ok());
-        assert!(Validator::validate_stock_tickers("AAPL,GOOGL,MSFT").is_ok());
-        assert!(Validator::validate_stock_tickers("AAPL, GOOGL, MSFT, TSLA, AMZN").is_ok());
-        assert!(Validator::validate_stock_tickers("AAPL,GOOGL,MSFT,TSLA,AMZN,FB").is_err()); // 6 tickers
+        let tickers = |list: &str| NewsEntry::new(NewsPublication::new("Daily", "en"), "2025-11-01", "Title")
+            .stock_tickers(list)
+            .stock_tickers;
+        assert!(Validator::validate_stock_tickers(&tickers("AAPL")).is_ok());
+        assert!(Validator::validate_stock_tickers(&tickers("AAPL,GOOGL,MSFT")).is_ok());
+        assert!(Validator::validate_stock_tickers(&tickers("NASDAQ:AAPL, GOOGL, MSFT, TSLA, AMZN")).is_ok());
+        assert!(Validator::validate_stock_tickers(&tickers("AAPL,GOOGL,MSFT,TSLA,AMZN,FB")).is_err()); // 6 tickers
+        assert!(Validator::validate_stock_tickers(&tickers("NASDAQ:")).is_err());
+        assert!(Validator::validate_stock_tickers(&tickers("NASDAQ:AAPL:X")).is_err());
+        assert!(Validator::validate_stock_tickers(&tickers("BRK B")).is_err());
+
+        let keywords = |list: &[&str]| list.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+        assert!(Validator::validate_keywords(&keywords(&["business", "markets"])).is_ok());
+        assert!(Validator::validate_keywords(&keywords(&["business, markets"])).is_err());
+        assert!(Validator::validate_keywords(&keywords(&[" "])).is_err());
     }
 }
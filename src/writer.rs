@@ -1,41 +1,240 @@
 //! XML writer for sitemaps
 
-use crate::error::{Error, Result};
+use crate::error::{Error, IoContext, Result};
+use crate::borrowed::*;
+use crate::extension::{is_xml_name, validate_namespace, ExtensionElement};
+use crate::resolve::resolved_url;
 use crate::types::*;
-use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::events::{BytesDecl, BytesEnd, BytesPI, BytesStart, BytesText, Event};
 use quick_xml::Writer;
+use std::borrow::Cow;
+use std::fmt::{self, Write as _};
+use std::fs;
 use std::io::Cursor;
+use std::path::Path;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+use url::Url;
 
 /// XML namespaces
-const SITEMAP_NS: &str = "http://www.sitemaps.org/schemas/sitemap/0.9";
-const IMAGE_NS: &str = "http://www.google.com/schemas/sitemap-image/1.1";
-const VIDEO_NS: &str = "http://www.google.com/schemas/sitemap-video/1.1";
-const NEWS_NS: &str = "http://www.google.com/schemas/sitemap-news/0.9";
+pub(crate) const SITEMAP_NS: &str = "http://www.sitemaps.org/schemas/sitemap/0.9";
+pub(crate) const IMAGE_NS: &str = "http://www.google.com/schemas/sitemap-image/1.1";
+pub(crate) const VIDEO_NS: &str = "http://www.google.com/schemas/sitemap-video/1.1";
+pub(crate) const NEWS_NS: &str = "http://www.google.com/schemas/sitemap-news/0.9";
+
+/// Default XSL stylesheet that renders sitemaps and sitemap indexes as HTML tables
+///
+/// Reference it with [`XmlWriter::stylesheet`] (or the `stylesheet()` option on
+/// any builder) and publish it with [`XmlWriter::write_default_stylesheet`].
+pub const DEFAULT_STYLESHEET: &str = include_str!("sitemap.xsl");
+
+/// How URLs are serialized on output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UrlEncoding {
+    /// Write URLs exactly as given
+    #[default]
+    AsIs,
+
+    /// Serialize URLs through `url::Url`
+    ///
+    /// Non-ASCII paths and queries are percent-encoded and unicode hostnames
+    /// are punycoded; `url::Url` also lowercases the host and drops default
+    /// ports. URLs that cannot be parsed are written as given.
+    Normalized,
+}
+
+/// What to do with characters that XML 1.0 does not allow
+///
+/// These are the C0 controls other than tab, newline and carriage return,
+/// and the noncharacters U+FFFE and U+FFFF. User content such as titles and
+/// captions copied from other tools sometimes carries them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InvalidCharPolicy {
+    /// Fail with [`Error::InvalidXmlChar`]
+    #[default]
+    Reject,
+
+    /// Drop the characters
+    Strip,
+
+    /// Replace each character with U+FFFD (`�`)
+    Replace,
+}
+
+/// Output options shared by the writer and all builders
+#[derive(Debug, Clone, Default)]
+pub struct WriterOptions {
+    /// URL of an XSL stylesheet referenced by an `xml-stylesheet` processing instruction
+    pub stylesheet: Option<String>,
+
+    /// How URLs are serialized (default: [`UrlEncoding::AsIs`])
+    pub url_encoding: UrlEncoding,
+
+    /// How characters not allowed in XML are handled (default: [`InvalidCharPolicy::Reject`])
+    pub invalid_chars: InvalidCharPolicy,
+
+    /// Normalize text fields (not URLs) to Unicode NFC
+    pub normalize_nfc: bool,
+
+    /// Prefixes and URIs of custom namespaces used by extension elements
+    pub namespaces: Vec<(String, String)>,
+
+    /// Base URL that relative URLs are resolved against when written
+    pub base_url: Option<Url>,
+}
+
+/// Check for a character that XML 1.0 does not allow
+///
+/// Surrogates cannot occur in a `str`, so only controls and U+FFFE/U+FFFF remain.
+pub(crate) fn is_invalid_xml_char(c: char) -> bool {
+    matches!(c, '\u{0}'..='\u{8}' | '\u{b}' | '\u{c}' | '\u{e}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}')
+}
 
 /// Writer for generating sitemap XML
 pub struct XmlWriter {
     writer: Writer<Cursor<Vec<u8>>>,
+    options: WriterOptions,
+    /// Reused buffer for formatting numbers
+    scratch: String,
+    /// Custom namespaces declared on the root element
+    declared: Vec<String>,
 }
 
 impl XmlWriter {
     /// Create a new XmlWriter
     pub fn new() -> Self {
+        Self::with_options(WriterOptions::default())
+    }
+
+    /// Create a new XmlWriter with the given output options
+    pub fn with_options(options: WriterOptions) -> Self {
         let cursor = Cursor::new(Vec::with_capacity(8192)); // Pre-allocate 8KB
         let writer = Writer::new_with_indent(cursor, b' ', 2);
 
-        Self { writer }
+        Self {
+            writer,
+            options,
+            scratch: String::new(),
+            declared: Vec::new(),
+        }
+    }
+
+    /// Reference an XSL stylesheet so browsers render the sitemap as HTML
+    ///
+    /// Writes `<?xml-stylesheet type="text/xsl" href="..."?>` after the XML declaration.
+    ///
+    /// # Example
+    /// ```
+    /// use sitemap_generator::writer::XmlWriter;
+    /// use sitemap_generator::UrlEntry;
+    ///
+    /// let mut writer = XmlWriter::new().stylesheet("/sitemap.xsl");
+    /// writer.write_sitemap(&[UrlEntry::new("https://example.com/")]).unwrap();
+    /// let xml = writer.into_string().unwrap();
+    /// assert!(xml.contains(r#"<?xml-stylesheet type="text/xsl" href="/sitemap.xsl"?>"#));
+    /// ```
+    pub fn stylesheet(mut self, href: impl Into<String>) -> Self {
+        self.options.stylesheet = Some(href.into());
+        self
+    }
+
+    /// Set how URLs are serialized (default: [`UrlEncoding::AsIs`])
+    ///
+    /// # Example
+    /// ```
+    /// use sitemap_generator::writer::{UrlEncoding, XmlWriter};
+    /// use sitemap_generator::UrlEntry;
+    ///
+    /// let mut writer = XmlWriter::new().url_encoding(UrlEncoding::Normalized);
+    /// writer.write_sitemap(&[UrlEntry::new("https://bücher.de/tin-tức/thời-sự")]).unwrap();
+    /// let xml = writer.into_string().unwrap();
+    /// assert!(xml.contains("<loc>https://xn--bcher-kva.de/tin-t%E1%BB%A9c/th%E1%BB%9Di-s%E1%BB%B1</loc>"));
+    /// ```
+    pub fn url_encoding(mut self, encoding: UrlEncoding) -> Self {
+        self.options.url_encoding = encoding;
+        self
+    }
+
+    /// Set how characters not allowed in XML are handled (default: [`InvalidCharPolicy::Reject`])
+    ///
+    /// # Example
+    /// ```
+    /// use sitemap_generator::writer::{InvalidCharPolicy, XmlWriter};
+    /// use sitemap_generator::{ImageEntry, UrlEntry, UrlWithImages};
+    ///
+    /// let entry = UrlWithImages::new(UrlEntry::new("https://example.com/"))
+    ///     .add_image(ImageEntry::new("https://example.com/a.jpg").caption("Sunset\u{8} over the bay"));
+    ///
+    /// assert!(XmlWriter::new().write_image_sitemap(&[entry.clone()]).is_err());
+    ///
+    /// let mut writer = XmlWriter::new().invalid_chars(InvalidCharPolicy::Strip);
+    /// writer.write_image_sitemap(&[entry]).unwrap();
+    /// assert!(writer.into_string().unwrap().contains("<image:caption>Sunset over the bay</image:caption>"));
+    /// ```
+    pub fn invalid_chars(mut self, policy: InvalidCharPolicy) -> Self {
+        self.options.invalid_chars = policy;
+        self
+    }
+
+    /// Normalize text fields to Unicode NFC (disabled by default)
+    ///
+    /// Titles, captions, descriptions and other text are composed, so `e` followed
+    /// by a combining acute accent is written as `é`. URLs are left as given.
+    pub fn normalize_nfc(mut self, normalize: bool) -> Self {
+        self.options.normalize_nfc = normalize;
+        self
+    }
+
+    /// Register a prefix for extension elements in a custom namespace
+    ///
+    /// The namespace is declared on `<urlset>` only if an entry uses it. Writing
+    /// fails if the prefix is also registered for another URI. See
+    /// [`crate::extension`] for attaching elements to entries.
+    pub fn namespace(mut self, prefix: impl Into<String>, uri: impl Into<String>) -> Self {
+        self.options.namespaces.push((prefix.into(), uri.into()));
+        self
+    }
+
+    /// Resolve relative URLs against a base URL when writing
+    ///
+    /// # Example
+    /// ```
+    /// use sitemap_generator::writer::XmlWriter;
+    /// use sitemap_generator::{UrlEntry, Url};
+    ///
+    /// let mut writer = XmlWriter::new().base_url(Url::parse("https://example.com/").unwrap());
+    /// writer.write_sitemap(&[UrlEntry::new("/about")]).unwrap();
+    /// assert!(writer.into_string().unwrap().contains("<loc>https://example.com/about</loc>"));
+    /// ```
+    pub fn base_url(mut self, base: Url) -> Self {
+        self.options.base_url = Some(base);
+        self
+    }
+
+    /// Write the bundled [`DEFAULT_STYLESHEET`] to a file
+    pub fn write_default_stylesheet<P: AsRef<Path>>(path: P) -> Result<()> {
+        fs::write(&path, DEFAULT_STYLESHEET).with_path(&path)?;
+        Ok(())
     }
 
     /// Get the generated XML as a String
     pub fn into_string(self) -> Result<String> {
         let result = self.writer.into_inner().into_inner();
-        String::from_utf8(result).map_err(|e| Error::Xml(e.to_string()))
+        String::from_utf8(result).map_err(|e| Error::Xml(e.into()))
     }
 
-    /// Write XML declaration
+    /// Write XML declaration (and stylesheet processing instruction, if set)
     fn write_declaration(&mut self) -> Result<()> {
         self.writer
             .write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+
+        if let Some(ref href) = self.options.stylesheet {
+            let pi = format!(
+                "xml-stylesheet type=\"text/xsl\" href=\"{}\"",
+                Self::escape_xml(href)
+            );
+            self.writer.write_event(Event::PI(BytesPI::new(pi)))?;
+        }
+
         Ok(())
     }
 
@@ -48,76 +247,230 @@ impl XmlWriter {
             .replace('\'', "&apos;")
     }
 
+    /// Apply the invalid character policy, and NFC normalization if `normalize` is set
+    fn clean_text<'a>(&self, field: &'static str, text: &'a str, normalize: bool) -> Result<Cow<'a, str>> {
+        let mut text = Cow::Borrowed(text);
+
+        if let Some(character) = text.chars().find(|&c| is_invalid_xml_char(c)) {
+            text = match self.options.invalid_chars {
+                InvalidCharPolicy::Reject => {
+                    return Err(Error::InvalidXmlChar {
+                        field,
+                        character,
+                        value: text.into_owned(),
+                    })
+                }
+                InvalidCharPolicy::Strip => Cow::Owned(text.chars().filter(|&c| !is_invalid_xml_char(c)).collect()),
+                InvalidCharPolicy::Replace => Cow::Owned(
+                    text.chars()
+                        .map(|c| if is_invalid_xml_char(c) { char::REPLACEMENT_CHARACTER } else { c })
+                        .collect(),
+                ),
+            };
+        }
+
+        if normalize && self.options.normalize_nfc && is_nfc_quick(text.chars()) != IsNormalized::Yes {
+            text = Cow::Owned(text.nfc().collect());
+        }
+
+        Ok(text)
+    }
+
     /// Write a simple text element
-    fn write_text_element(&mut self, name: &str, text: &str) -> Result<()> {
+    fn write_text_element(&mut self, name: &'static str, text: &str) -> Result<()> {
+        let text = self.clean_text(name, text, true)?;
+        self.write_raw_text_element(name, &text)
+    }
+
+    /// Write a text element without applying the text policies
+    fn write_raw_text_element(&mut self, name: &str, text: &str) -> Result<()> {
         self.writer
             .write_event(Event::Start(BytesStart::new(name)))?;
         self.writer
-            .write_event(Event::Text(BytesText::new(&Self::escape_xml(text))))?;
+            .write_event(Event::Text(BytesText::new(text)))?;
         self.writer
             .write_event(Event::End(BytesEnd::new(name)))?;
         Ok(())
     }
 
-    /// Write a standard sitemap
-    pub fn write_sitemap(&mut self, entries: &[UrlEntry]) -> Result<()> {
-        self.write_declaration()?;
+    /// Write an element containing a formatted value, reusing the scratch buffer
+    fn write_display_element(&mut self, name: &str, value: impl fmt::Display) -> Result<()> {
+        let mut scratch = std::mem::take(&mut self.scratch);
+        scratch.clear();
+        let _ = write!(scratch, "{}", value);
+        let result = self.write_raw_text_element(name, &scratch);
+        self.scratch = scratch;
+        result
+    }
 
-        // Start urlset
+    /// Write an element containing a URL, resolving it and applying the URL encoding policy
+    fn write_url_element(&mut self, name: &'static str, url: &str) -> Result<()> {
+        let url = self.clean_text(name, url, false)?;
+        let url = resolved_url(self.options.base_url.as_ref(), &url);
+        let url = self.encode_url(&url);
+        self.write_raw_text_element(name, &url)
+    }
+
+    /// Apply the URL encoding policy to a URL
+    fn encode_url<'a>(&self, url: &'a str) -> Cow<'a, str> {
+        match self.options.url_encoding {
+            UrlEncoding::AsIs => Cow::Borrowed(url),
+            UrlEncoding::Normalized => match Url::parse(url) {
+                Ok(parsed) => Cow::Owned(parsed.into()),
+                Err(_) => Cow::Borrowed(url),
+            },
+        }
+    }
+
+    /// Start `<urlset>` with the given extension namespaces and the custom namespaces in `used`
+    fn start_urlset(&mut self, namespaces: &[(&str, &str)], used: &[&str]) -> Result<()> {
         let mut urlset = BytesStart::new("urlset");
         urlset.push_attribute(("xmlns", SITEMAP_NS));
-        self.writer.write_event(Event::Start(urlset))?;
-
-        // Write each URL entry
-        for entry in entries {
-            self.write_url_entry(entry)?;
+        for &namespace in namespaces {
+            urlset.push_attribute(namespace);
         }
 
-        // End urlset
-        self.writer
-            .write_event(Event::End(BytesEnd::new("urlset")))?;
+        self.declared.clear();
+        for &uri in used {
+            let prefix = self.prefix_for(uri)?;
+            urlset.push_attribute((format!("xmlns:{}", prefix).as_str(), uri));
+            self.declared.push(uri.to_string());
+        }
 
+        self.writer.write_event(Event::Start(urlset))?;
         Ok(())
     }
 
-    /// Write a single URL entry
-    fn write_url_entry(&mut self, entry: &UrlEntry) -> Result<()> {
-        self.writer
-            .write_event(Event::Start(BytesStart::new("url")))?;
+    /// Get the registered prefix of a custom namespace
+    fn prefix_for(&self, uri: &str) -> Result<String> {
+        let (prefix, _) = self
+            .options
+            .namespaces
+            .iter()
+            .find(|(_, registered)| registered == uri)
+            .ok_or_else(|| Error::UnknownNamespace {
+                namespace: uri.to_string(),
+            })?;
+        validate_namespace(prefix, uri)?;
+
+        // A prefix bound to two URIs would be declared twice on the root
+        if let Some((_, other)) = self
+            .options
+            .namespaces
+            .iter()
+            .find(|(registered, other)| registered == prefix && other != uri)
+        {
+            return Err(Error::InvalidField {
+                field: "xmlns",
+                value: format!("{}={}", prefix, other),
+                reason: "prefix is already registered for another namespace",
+            });
+        }
+        Ok(prefix.clone())
+    }
 
-        self.write_text_element("loc", &entry.loc)?;
+    /// Write the extension elements of a URL entry
+    fn write_extensions(&mut self, elements: &[ExtensionElement]) -> Result<()> {
+        let mut scope = Vec::new();
+        for element in elements {
+            self.write_extension_element(element, &mut scope)?;
+        }
+        Ok(())
+    }
 
-        if let Some(ref lastmod) = entry.lastmod {
-            self.write_text_element("lastmod", lastmod)?;
+    /// Write an extension element and its children
+    ///
+    /// Namespaces not declared on the root (entries written from an iterator)
+    /// are declared on the outermost element that uses them.
+    fn write_extension_element<'a>(&mut self, element: &'a ExtensionElement, scope: &mut Vec<&'a str>) -> Result<()> {
+        if !is_xml_name(&element.name) {
+            return Err(Error::InvalidField {
+                field: "extension",
+                value: element.name.clone(),
+                reason: "element name must be an XML name without a prefix",
+            });
+        }
+
+        let prefix = self.prefix_for(&element.namespace)?;
+        let name = format!("{}:{}", prefix, element.name);
+        let mut start = BytesStart::new(name.as_str());
+
+        let namespace = element.namespace.as_str();
+        let declare = !self.declared.iter().any(|uri| uri == namespace) && !scope.contains(&namespace);
+        if declare {
+            start.push_attribute((format!("xmlns:{}", prefix).as_str(), namespace));
+            scope.push(namespace);
+        }
+
+        for (attribute, value) in &element.attributes {
+            if !is_xml_name(attribute) {
+                return Err(Error::InvalidField {
+                    field: "extension",
+                    value: attribute.clone(),
+                    reason: "attribute name must be an XML name without a prefix",
+                });
+            }
+            let value = self.clean_text("extension", value, true)?;
+            start.push_attribute((attribute.as_str(), value.as_ref()));
         }
 
-        if let Some(changefreq) = entry.changefreq {
-            self.write_text_element("changefreq", changefreq.as_str())?;
+        if element.text.is_none() && element.children.is_empty() {
+            self.writer.write_event(Event::Empty(start))?;
+        } else {
+            self.writer.write_event(Event::Start(start))?;
+            if let Some(ref text) = element.text {
+                let text = self.clean_text("extension", text, true)?;
+                self.writer.write_event(Event::Text(BytesText::new(&text)))?;
+            }
+            for child in &element.children {
+                self.write_extension_element(child, scope)?;
+            }
+            self.writer.write_event(Event::End(BytesEnd::new(name.as_str())))?;
         }
 
-        if let Some(priority) = entry.priority {
-            self.write_text_element("priority", &priority.to_string())?;
+        if declare {
+            scope.pop();
         }
+        Ok(())
+    }
 
-        self.writer
-            .write_event(Event::End(BytesEnd::new("url")))?;
+    /// Write a standard sitemap
+    pub fn write_sitemap(&mut self, entries: &[UrlEntry]) -> Result<()> {
+        let used = used_namespaces(entries.iter().map(|entry| entry.extensions.as_slice()));
+        self.write_sitemap_with(entries, &used)
+    }
 
-        Ok(())
+    /// Write a standard sitemap from borrowed entries
+    ///
+    /// Accepts any type implementing [`UrlEntryRef`], so rows already in memory
+    /// can be written without building owned [`UrlEntry`] values.
+    pub fn write_sitemap_ref<I>(&mut self, entries: I) -> Result<()>
+    where
+        I: IntoIterator,
+        I::Item: UrlEntryRef,
+    {
+        self.write_sitemap_with(entries, &[])
     }
 
-    /// Write an image sitemap
-    pub fn write_image_sitemap(&mut self, entries: &[UrlWithImages]) -> Result<()> {
+    /// Write a standard sitemap, declaring the custom namespaces in `used` on the root
+    fn write_sitemap_with<I>(&mut self, entries: I, used: &[&str]) -> Result<()>
+    where
+        I: IntoIterator,
+        I::Item: UrlEntryRef,
+    {
         self.write_declaration()?;
 
-        // Start urlset with image namespace
-        let mut urlset = BytesStart::new("urlset");
-        urlset.push_attribute(("xmlns", SITEMAP_NS));
-        urlset.push_attribute(("xmlns:image", IMAGE_NS));
-        self.writer.write_event(Event::Start(urlset))?;
+        // Start urlset
+        self.start_urlset(&[], used)?;
 
-        // Write each URL with images
+        // Write each URL entry
         for entry in entries {
-            self.write_url_with_images(entry)?;
+            self.writer
+                .write_event(Event::Start(BytesStart::new("url")))?;
+            self.write_url_fields(&entry)?;
+            self.write_extensions(entry.extensions())?;
+            self.writer
+                .write_event(Event::End(BytesEnd::new("url")))?;
         }
 
         // End urlset
@@ -127,62 +480,94 @@ impl XmlWriter {
         Ok(())
     }
 
-    /// Write a URL entry with images
-    fn write_url_with_images(&mut self, entry: &UrlWithImages) -> Result<()> {
-        self.writer
-            .write_event(Event::Start(BytesStart::new("url")))?;
-
-        self.write_text_element("loc", &entry.url.loc)?;
+    /// Write the `loc`, `lastmod`, `changefreq` and `priority` of a URL entry
+    fn write_url_fields<E: UrlEntryRef>(&mut self, entry: &E) -> Result<()> {
+        self.write_url_element("loc", entry.loc())?;
 
-        if let Some(ref lastmod) = entry.url.lastmod {
+        if let Some(lastmod) = entry.lastmod() {
             self.write_text_element("lastmod", lastmod)?;
         }
 
-        if let Some(changefreq) = entry.url.changefreq {
+        if let Some(changefreq) = entry.changefreq() {
             self.write_text_element("changefreq", changefreq.as_str())?;
         }
 
-        if let Some(priority) = entry.url.priority {
-            self.write_text_element("priority", &priority.to_string())?;
+        if let Some(priority) = entry.priority() {
+            self.write_display_element("priority", priority)?;
         }
 
-        // Write images
-        for image in &entry.images {
-            self.write_image_entry(image)?;
+        Ok(())
+    }
+
+    /// Write an image sitemap
+    pub fn write_image_sitemap(&mut self, entries: &[UrlWithImages]) -> Result<()> {
+        let used = used_namespaces(entries.iter().map(|entry| entry.url.extensions.as_slice()));
+        self.write_image_sitemap_with(entries, &used)
+    }
+
+    /// Write an image sitemap from borrowed entries
+    pub fn write_image_sitemap_ref<I>(&mut self, entries: I) -> Result<()>
+    where
+        I: IntoIterator,
+        I::Item: ImagesRef,
+    {
+        self.write_image_sitemap_with(entries, &[])
+    }
+
+    /// Write an image sitemap, declaring the custom namespaces in `used` on the root
+    fn write_image_sitemap_with<I>(&mut self, entries: I, used: &[&str]) -> Result<()>
+    where
+        I: IntoIterator,
+        I::Item: ImagesRef,
+    {
+        self.write_declaration()?;
+
+        // Start urlset with image namespace
+        self.start_urlset(&[("xmlns:image", IMAGE_NS)], used)?;
+
+        // Write each URL with images
+        for entry in entries {
+            self.writer
+                .write_event(Event::Start(BytesStart::new("url")))?;
+            self.write_url_fields(&entry)?;
+
+            for image in entry.images() {
+                self.write_image_entry(image)?;
+            }
+
+            self.write_extensions(entry.extensions())?;
+
+            self.writer
+                .write_event(Event::End(BytesEnd::new("url")))?;
         }
 
+        // End urlset
         self.writer
-            .write_event(Event::End(BytesEnd::new("url")))?;
+            .write_event(Event::End(BytesEnd::new("urlset")))?;
 
         Ok(())
     }
 
     /// Write an image entry
-    fn write_image_entry(&mut self, image: &ImageEntry) -> Result<()> {
+    fn write_image_entry<I: ImageEntryRef>(&mut self, image: &I) -> Result<()> {
         self.writer
             .write_event(Event::Start(BytesStart::new("image:image")))?;
 
-        self.write_text_element("image:loc", &image.loc)?;
+        self.write_url_element("image:loc", image.loc())?;
 
-        if let Some(ref caption) = image.caption {
+        if let Some(caption) = image.caption() {
             self.write_text_element("image:caption", caption)?;
         }
 
-        if let Some(ref geo) = image.geo_location {
-            if let Some(ref city) = geo.city {
-                self.write_text_element("image:geo_location", city)?;
-            } else if let Some(ref state) = geo.state {
-                self.write_text_element("image:geo_location", state)?;
-            } else if let Some(ref country) = geo.country {
-                self.write_text_element("image:geo_location", country)?;
-            }
+        if let Some(geo_location) = image.geo_location() {
+            self.write_text_element("image:geo_location", geo_location)?;
         }
 
-        if let Some(ref title) = image.title {
+        if let Some(title) = image.title() {
             self.write_text_element("image:title", title)?;
         }
 
-        if let Some(ref license) = image.license {
+        if let Some(license) = image.license() {
             self.write_text_element("image:license", license)?;
         }
 
@@ -194,106 +579,118 @@ impl XmlWriter {
 
     /// Write a video sitemap
     pub fn write_video_sitemap(&mut self, entries: &[UrlWithVideos]) -> Result<()> {
+        let used = used_namespaces(entries.iter().map(|entry| entry.url.extensions.as_slice()));
+        self.write_video_sitemap_with(entries, &used)
+    }
+
+    /// Write a video sitemap from borrowed entries
+    ///
+    /// Only `loc` and `lastmod` of each URL are written.
+    pub fn write_video_sitemap_ref<I>(&mut self, entries: I) -> Result<()>
+    where
+        I: IntoIterator,
+        I::Item: VideosRef,
+    {
+        self.write_video_sitemap_with(entries, &[])
+    }
+
+    /// Write a video sitemap, declaring the custom namespaces in `used` on the root
+    fn write_video_sitemap_with<I>(&mut self, entries: I, used: &[&str]) -> Result<()>
+    where
+        I: IntoIterator,
+        I::Item: VideosRef,
+    {
         self.write_declaration()?;
 
         // Start urlset with video namespace
-        let mut urlset = BytesStart::new("urlset");
-        urlset.push_attribute(("xmlns", SITEMAP_NS));
-        urlset.push_attribute(("xmlns:video", VIDEO_NS));
-        self.writer.write_event(Event::Start(urlset))?;
+        self.start_urlset(&[("xmlns:video", VIDEO_NS)], used)?;
 
         // Write each URL with videos
         for entry in entries {
-            self.write_url_with_videos(entry)?;
-        }
-
-        // End urlset
-        self.writer
-            .write_event(Event::End(BytesEnd::new("urlset")))?;
+            self.writer
+                .write_event(Event::Start(BytesStart::new("url")))?;
 
-        Ok(())
-    }
+            self.write_url_element("loc", entry.loc())?;
 
-    /// Write a URL entry with videos
-    fn write_url_with_videos(&mut self, entry: &UrlWithVideos) -> Result<()> {
-        self.writer
-            .write_event(Event::Start(BytesStart::new("url")))?;
+            if let Some(lastmod) = entry.lastmod() {
+                self.write_text_element("lastmod", lastmod)?;
+            }
 
-        self.write_text_element("loc", &entry.url.loc)?;
+            for video in entry.videos() {
+                self.write_video_entry(video)?;
+            }
 
-        if let Some(ref lastmod) = entry.url.lastmod {
-            self.write_text_element("lastmod", lastmod)?;
-        }
+            self.write_extensions(entry.extensions())?;
 
-        // Write videos
-        for video in &entry.videos {
-            self.write_video_entry(video)?;
+            self.writer
+                .write_event(Event::End(BytesEnd::new("url")))?;
         }
 
+        // End urlset
         self.writer
-            .write_event(Event::End(BytesEnd::new("url")))?;
+            .write_event(Event::End(BytesEnd::new("urlset")))?;
 
         Ok(())
     }
 
     /// Write a video entry
-    fn write_video_entry(&mut self, video: &VideoEntry) -> Result<()> {
+    fn write_video_entry<V: VideoEntryRef>(&mut self, video: &V) -> Result<()> {
         self.writer
             .write_event(Event::Start(BytesStart::new("video:video")))?;
 
-        self.write_text_element("video:thumbnail_loc", &video.thumbnail_loc)?;
-        self.write_text_element("video:title", &video.title)?;
-        self.write_text_element("video:description", &video.description)?;
+        self.write_url_element("video:thumbnail_loc", video.thumbnail_loc())?;
+        self.write_text_element("video:title", video.title())?;
+        self.write_text_element("video:description", video.description())?;
 
-        if let Some(ref content_loc) = video.content_loc {
-            self.write_text_element("video:content_loc", content_loc)?;
+        if let Some(content_loc) = video.content_loc() {
+            self.write_url_element("video:content_loc", content_loc)?;
         }
 
-        if let Some(ref player_loc) = video.player_loc {
-            self.write_text_element("video:player_loc", player_loc)?;
+        if let Some(player_loc) = video.player_loc() {
+            self.write_url_element("video:player_loc", player_loc)?;
         }
 
-        if let Some(duration) = video.duration {
-            self.write_text_element("video:duration", &duration.to_string())?;
+        if let Some(duration) = video.duration() {
+            self.write_display_element("video:duration", duration)?;
         }
 
-        if let Some(ref pub_date) = video.publication_date {
+        if let Some(pub_date) = video.publication_date() {
             self.write_text_element("video:publication_date", pub_date)?;
         }
 
-        if let Some(ref exp_date) = video.expiration_date {
+        if let Some(exp_date) = video.expiration_date() {
             self.write_text_element("video:expiration_date", exp_date)?;
         }
 
-        if let Some(rating) = video.rating {
-            self.write_text_element("video:rating", &rating.to_string())?;
+        if let Some(rating) = video.rating() {
+            self.write_display_element("video:rating", rating)?;
         }
 
-        if let Some(view_count) = video.view_count {
-            self.write_text_element("video:view_count", &view_count.to_string())?;
+        if let Some(view_count) = video.view_count() {
+            self.write_display_element("video:view_count", view_count)?;
         }
 
-        if let Some(family_friendly) = video.family_friendly {
+        if let Some(family_friendly) = video.family_friendly() {
             let value = if family_friendly { "yes" } else { "no" };
             self.write_text_element("video:family_friendly", value)?;
         }
 
-        for tag in &video.tags {
+        for tag in video.tags() {
             self.write_text_element("video:tag", tag)?;
         }
 
-        if let Some(ref category) = video.category {
+        if let Some(category) = video.category() {
             self.write_text_element("video:category", category)?;
         }
 
-        if let Some(ref restriction) = video.restriction {
+        if let Some(restriction) = video.restriction() {
             match restriction {
                 VideoCountryRestriction::Allow(countries) => {
                     let mut elem = BytesStart::new("video:restriction");
                     elem.push_attribute(("relationship", "allow"));
                     self.writer.write_event(Event::Start(elem))?;
                     self.writer
-                        .write_event(Event::Text(BytesText::new(&countries.join(" "))))?;
+                        .write_event(Event::Text(BytesText::new(&self.clean_text("video:restriction", &countries.join(" "), false)?)))?;
                     self.writer
                         .write_event(Event::End(BytesEnd::new("video:restriction")))?;
                 }
@@ -302,18 +699,18 @@ impl XmlWriter {
                     elem.push_attribute(("relationship", "deny"));
                     self.writer.write_event(Event::Start(elem))?;
                     self.writer
-                        .write_event(Event::Text(BytesText::new(&countries.join(" "))))?;
+                        .write_event(Event::Text(BytesText::new(&self.clean_text("video:restriction", &countries.join(" "), false)?)))?;
                     self.writer
                         .write_event(Event::End(BytesEnd::new("video:restriction")))?;
                 }
             }
         }
 
-        if let Some(ref gallery_loc) = video.gallery_loc {
-            self.write_text_element("video:gallery_loc", gallery_loc)?;
+        if let Some(gallery_loc) = video.gallery_loc() {
+            self.write_url_element("video:gallery_loc", gallery_loc)?;
         }
 
-        if let Some(ref platform) = video.platform {
+        if let Some(platform) = video.platform() {
             match platform {
                 VideoPlatformRestriction::Allow(platforms) => {
                     let mut elem = BytesStart::new("video:platform");
@@ -338,14 +735,14 @@ impl XmlWriter {
             }
         }
 
-        for price in &video.prices {
+        for price in video.prices() {
             let mut elem = BytesStart::new("video:price");
-            elem.push_attribute(("currency", price.currency.as_str()));
+            elem.push_attribute(("currency", self.clean_text("video:price", &price.currency, false)?.as_ref()));
             if let Some(ref resolution) = price.resolution {
-                elem.push_attribute(("resolution", resolution.as_str()));
+                elem.push_attribute(("resolution", self.clean_text("video:price", resolution, false)?.as_ref()));
             }
             if let Some(ref type_) = price.type_ {
-                elem.push_attribute(("type", type_.as_str()));
+                elem.push_attribute(("type", self.clean_text("video:price", type_, false)?.as_ref()));
             }
             self.writer.write_event(Event::Start(elem))?;
             self.writer
@@ -354,17 +751,20 @@ impl XmlWriter {
                 .write_event(Event::End(BytesEnd::new("video:price")))?;
         }
 
-        if let Some(requires_sub) = video.requires_subscription {
+        if let Some(requires_sub) = video.requires_subscription() {
             self.write_text_element("video:requires_subscription", requires_sub.as_str())?;
         }
 
-        if let Some(ref uploader) = video.uploader {
+        if let Some(uploader) = video.uploader() {
             if let Some(ref info_url) = uploader.info_url {
+                let info_url = self.clean_text("video:uploader", info_url, false)?;
+                let info_url = self.encode_url(&info_url);
+                let name = self.clean_text("video:uploader", &uploader.name, true)?;
                 let mut elem = BytesStart::new("video:uploader");
-                elem.push_attribute(("info", info_url.as_str()));
+                elem.push_attribute(("info", info_url.as_ref()));
                 self.writer.write_event(Event::Start(elem))?;
                 self.writer
-                    .write_event(Event::Text(BytesText::new(&uploader.name)))?;
+                    .write_event(Event::Text(BytesText::new(&name)))?;
                 self.writer
                     .write_event(Event::End(BytesEnd::new("video:uploader")))?;
             } else {
@@ -372,7 +772,7 @@ impl XmlWriter {
             }
         }
 
-        if let Some(live) = video.live {
+        if let Some(live) = video.live() {
             self.write_text_element("video:live", live.as_str())?;
         }
 
@@ -387,10 +787,8 @@ impl XmlWriter {
         self.write_declaration()?;
 
         // Start urlset with news namespace
-        let mut urlset = BytesStart::new("urlset");
-        urlset.push_attribute(("xmlns", SITEMAP_NS));
-        urlset.push_attribute(("xmlns:news", NEWS_NS));
-        self.writer.write_event(Event::Start(urlset))?;
+        let used = used_namespaces(entries.iter().map(|entry| entry.url.extensions.as_slice()));
+        self.start_urlset(&[("xmlns:news", NEWS_NS)], &used)?;
 
         // Write each URL entry with news
         for entry in entries {
@@ -398,7 +796,7 @@ impl XmlWriter {
                 .write_event(Event::Start(BytesStart::new("url")))?;
 
             // Write URL loc
-            self.write_text_element("loc", &entry.url.loc)?;
+            self.write_url_element("loc", &entry.url.loc)?;
 
             // Write news metadata
             self.writer
@@ -419,19 +817,21 @@ impl XmlWriter {
             self.write_text_element("news:title", &entry.news.title)?;
 
             // Write optional keywords
-            if let Some(ref keywords) = entry.news.keywords {
-                self.write_text_element("news:keywords", keywords)?;
+            if !entry.news.keywords.is_empty() {
+                self.write_text_element("news:keywords", &entry.news.keywords_text())?;
             }
 
             // Write optional stock_tickers
-            if let Some(ref tickers) = entry.news.stock_tickers {
-                self.write_text_element("news:stock_tickers", tickers)?;
+            if !entry.news.stock_tickers.is_empty() {
+                self.write_text_element("news:stock_tickers", &entry.news.stock_tickers_text())?;
             }
 
             // End news:news
             self.writer
                 .write_event(Event::End(BytesEnd::new("news:news")))?;
 
+            self.write_extensions(&entry.url.extensions)?;
+
             // End url
             self.writer
                 .write_event(Event::End(BytesEnd::new("url")))?;
@@ -449,26 +849,25 @@ impl XmlWriter {
     pub fn write_combined_sitemap(&mut self, entries: &[UrlWithExtensions]) -> Result<()> {
         self.write_declaration()?;
 
-        // Start urlset with all necessary namespaces
-        let mut urlset = BytesStart::new("urlset");
-        urlset.push_attribute(("xmlns", SITEMAP_NS));
-
         // Check which namespaces are needed
         let needs_image = entries.iter().any(|e| !e.images.is_empty());
         let needs_video = entries.iter().any(|e| !e.videos.is_empty());
         let needs_news = entries.iter().any(|e| e.news.is_some());
 
+        let mut namespaces = Vec::with_capacity(3);
         if needs_image {
-            urlset.push_attribute(("xmlns:image", IMAGE_NS));
+            namespaces.push(("xmlns:image", IMAGE_NS));
         }
         if needs_video {
-            urlset.push_attribute(("xmlns:video", VIDEO_NS));
+            namespaces.push(("xmlns:video", VIDEO_NS));
         }
         if needs_news {
-            urlset.push_attribute(("xmlns:news", NEWS_NS));
+            namespaces.push(("xmlns:news", NEWS_NS));
         }
 
-        self.writer.write_event(Event::Start(urlset))?;
+        // Start urlset with all necessary namespaces
+        let used = used_namespaces(entries.iter().map(|entry| entry.url.extensions.as_slice()));
+        self.start_urlset(&namespaces, &used)?;
 
         // Write each URL with its extensions
         for entry in entries {
@@ -476,7 +875,7 @@ impl XmlWriter {
                 .write_event(Event::Start(BytesStart::new("url")))?;
 
             // Write URL loc
-            self.write_text_element("loc", &entry.url.loc)?;
+            self.write_url_element("loc", &entry.url.loc)?;
 
             // Write optional URL fields
             if let Some(ref lastmod) = entry.url.lastmod {
@@ -488,7 +887,7 @@ impl XmlWriter {
             }
 
             if let Some(priority) = entry.url.priority {
-                self.write_text_element("priority", &priority.to_string())?;
+                self.write_display_element("priority", priority)?;
             }
 
             // Write images
@@ -521,13 +920,13 @@ impl XmlWriter {
                 self.write_text_element("news:title", &news.title)?;
 
                 // Write optional keywords
-                if let Some(ref keywords) = news.keywords {
-                    self.write_text_element("news:keywords", keywords)?;
+                if !news.keywords.is_empty() {
+                    self.write_text_element("news:keywords", &news.keywords_text())?;
                 }
 
                 // Write optional stock_tickers
-                if let Some(ref tickers) = news.stock_tickers {
-                    self.write_text_element("news:stock_tickers", tickers)?;
+                if !news.stock_tickers.is_empty() {
+                    self.write_text_element("news:stock_tickers", &news.stock_tickers_text())?;
                 }
 
                 // End news:news
@@ -535,6 +934,8 @@ impl XmlWriter {
                     .write_event(Event::End(BytesEnd::new("news:news")))?;
             }
 
+            self.write_extensions(&entry.url.extensions)?;
+
             // End url
             self.writer
                 .write_event(Event::End(BytesEnd::new("url")))?;
@@ -561,7 +962,7 @@ impl XmlWriter {
             self.writer
                 .write_event(Event::Start(BytesStart::new("sitemap")))?;
 
-            self.write_text_element("loc", &entry.loc)?;
+            self.write_url_element("loc", &entry.loc)?;
 
             if let Some(ref lastmod) = entry.lastmod {
                 self.write_text_element("lastmod", lastmod)?;
@@ -579,6 +980,15 @@ impl XmlWriter {
     }
 }
 
+/// Custom namespace URIs used by a set of entries, in order of first use
+fn used_namespaces<'a>(extensions: impl Iterator<Item = &'a [ExtensionElement]>) -> Vec<&'a str> {
+    let mut namespaces = Vec::new();
+    for element in extensions.flatten() {
+        element.collect_namespaces(&mut namespaces);
+    }
+    namespaces
+}
+
 impl Default for XmlWriter {
     fn default() -> Self {
         Self::new()
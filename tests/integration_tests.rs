@@ -211,6 +211,248 @@ fn test_parser_roundtrip() {
     assert_eq!(entries[1].priority, Some(0.8));
 }
 
+#[test]
+fn test_diff_parsed_sitemaps() {
+    let mut old = SitemapBuilder::new();
+    old.add_url(UrlEntry::new("https://example.com/").lastmod("2025-11-01"));
+    old.add_url(UrlEntry::new("https://example.com/removed"));
+
+    let mut new = SitemapBuilder::new();
+    new.add_url(UrlEntry::new("https://example.com/").lastmod("2025-11-02"));
+    new.add_url(UrlEntry::new("https://example.com/added"));
+
+    let old = SitemapParser::parse_string(&old.build().unwrap()).unwrap();
+    let new = SitemapParser::parse_string(&new.build().unwrap()).unwrap();
+
+    let diff = SitemapDiff::between(&old, &new);
+    assert_eq!(diff.added, vec!["https://example.com/added"]);
+    assert_eq!(diff.removed, vec!["https://example.com/removed"]);
+    assert_eq!(diff.modified.len(), 1);
+    assert_eq!(diff.modified[0].loc, "https://example.com/");
+
+    let json = diff.to_json().unwrap();
+    assert!(json.contains("\"added\""));
+    assert!(json.contains("\"old\": \"2025-11-01\""));
+}
+
+#[test]
+fn test_incremental_rewrites_only_changed_shards() {
+    let dir = tempfile::tempdir().unwrap();
+    let urls: Vec<UrlEntry> = (0..10)
+        .map(|i| UrlEntry::new(format!("https://example.com/page{}", i)))
+        .collect();
+
+    let first = IncrementalSitemapWriter::new(dir.path(), "https://example.com/sitemaps/")
+        .urls_per_shard(4)
+        .lastmod("2025-11-01")
+        .write(&urls)
+        .unwrap();
+    assert_eq!(first.written, vec!["sitemap-0.xml", "sitemap-1.xml", "sitemap-2.xml"]);
+
+    // Change a single URL in the second shard and add a new one
+    let mut urls = urls;
+    urls[5] = UrlEntry::new("https://example.com/page5").lastmod("2025-11-02");
+    urls.push(UrlEntry::new("https://example.com/page10"));
+
+    let writer = IncrementalSitemapWriter::new(dir.path(), "https://example.com/sitemaps/")
+        .urls_per_shard(4)
+        .lastmod("2025-11-02");
+    let second = writer.write(&urls).unwrap();
+    assert_eq!(second.written, vec!["sitemap-1.xml", "sitemap-2.xml"]);
+    assert_eq!(second.unchanged, vec!["sitemap-0.xml"]);
+
+    let index = SitemapParser::parse_index_file(writer.index_path()).unwrap();
+    assert_eq!(index.len(), 3);
+    assert_eq!(index[0].loc, "https://example.com/sitemaps/sitemap-0.xml");
+    assert_eq!(index[0].lastmod, Some("2025-11-01".to_string()));
+    assert_eq!(index[1].lastmod, Some("2025-11-02".to_string()));
+
+    let manifest = ShardManifest::load(writer.manifest_path()).unwrap();
+    assert_eq!(manifest.shards[2].urls.len(), 3);
+
+    // Extension elements are written with the registered namespaces
+    let urls = vec![UrlEntry::new("https://example.com/").add_extension(ExtensionElement::new("urn:acme", "Tag"))];
+    let writer = IncrementalSitemapWriter::new(dir.path().join("ext"), "https://example.com/sitemaps/");
+    assert_eq!(writer.write(&urls).unwrap_err().code(), "unknown_namespace");
+
+    let writer = writer.namespace("acme", "urn:acme");
+    writer.write(&urls).unwrap();
+    let xml = std::fs::read_to_string(dir.path().join("ext/sitemap-0.xml")).unwrap();
+    assert!(xml.contains(r#"xmlns:acme="urn:acme""#));
+    assert!(xml.contains("<acme:Tag/>"));
+}
+
+#[test]
+fn test_rolling_news_sitemap() {
+    use chrono::{Duration, TimeZone, Utc};
+    use std::cell::Cell;
+
+    let dir = tempfile::tempdir().unwrap();
+    let now = Cell::new(Utc.with_ymd_and_hms(2025, 11, 1, 12, 0, 0).unwrap());
+    let article = |slug: &str, date: &str| {
+        UrlWithNews::new(
+            UrlEntry::new(format!("https://example.com/news/{}", slug)),
+            NewsEntry::new(NewsPublication::new("Daily", "en"), date, slug),
+        )
+    };
+
+    let sitemap = RollingNewsSitemap::new(dir.path().join("news.xml"), dir.path().join("news.json"))
+        .clock(|| now.get());
+
+    let report = sitemap
+        .update(vec![article("monday", "2025-11-01T08:00:00Z"), article("noon", "2025-11-01T12:00:00Z")])
+        .unwrap();
+    assert_eq!(report.added.len(), 2);
+    assert_eq!(report.total, 2);
+
+    // A day later, only new articles are passed; the stored ones stay
+    now.set(now.get() + Duration::days(1));
+    let report = sitemap.update(vec![article("tuesday", "2025-11-02T12:00:00Z")]).unwrap();
+    assert_eq!(report.added, vec!["https://example.com/news/tuesday"]);
+    assert_eq!(report.total, 3);
+
+    // After 48 hours the first article expires
+    now.set(now.get() + Duration::hours(23));
+    let report = sitemap.update(Vec::new()).unwrap();
+    assert_eq!(report.expired, vec!["https://example.com/news/monday"]);
+    assert_eq!(report.total, 2);
+
+    let xml = std::fs::read_to_string(sitemap.sitemap_path()).unwrap();
+    assert!(xml.contains("https://example.com/news/tuesday"));
+    assert!(xml.contains("https://example.com/news/noon"));
+    assert!(!xml.contains("https://example.com/news/monday"));
+
+    let state = RollingNewsState::load(sitemap.state_path()).unwrap();
+    assert_eq!(state.articles[0].loc, "https://example.com/news/tuesday");
+
+    // A shorter window expires the rest of the first day
+    let sitemap = sitemap.window(Duration::hours(24));
+    let report = sitemap.update(Vec::new()).unwrap();
+    assert_eq!(report.expired, vec!["https://example.com/news/noon"]);
+}
+
+#[test]
+fn test_directory_scanner() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("blog")).unwrap();
+    std::fs::create_dir_all(root.join("drafts")).unwrap();
+    std::fs::write(root.join("index.html"), "<html></html>").unwrap();
+    std::fs::write(root.join("about.html"), "<html></html>").unwrap();
+    std::fs::write(root.join("blog/index.html"), "<html></html>").unwrap();
+    std::fs::write(root.join("blog/style.css"), "body {}").unwrap();
+    std::fs::write(root.join("drafts/wip.html"), "<html></html>").unwrap();
+    std::fs::write(
+        root.join("private.html"),
+        r#"<meta name="robots" content="noindex">"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("copy.html"),
+        r#"<link rel="canonical" href="https://example.com/about">"#,
+    )
+    .unwrap();
+
+    let cache = root.join("lastmod-cache.json");
+    let entries = DirectoryScanner::new(root, "https://example.com")
+        .exclude("drafts/**")
+        .pretty_urls(true)
+        .lastmod(LastmodSource::ContentHash(cache.clone()))
+        .scan()
+        .unwrap();
+
+    let locs: Vec<&str> = entries.iter().map(|e| e.loc.as_str()).collect();
+    assert_eq!(
+        locs,
+        vec![
+            "https://example.com/about",
+            "https://example.com/blog/",
+            "https://example.com/",
+        ]
+    );
+    assert!(entries.iter().all(|e| e.lastmod.is_some()));
+    assert!(cache.exists());
+    assert!(std::fs::read_to_string(&cache).unwrap().contains("about.html"));
+
+    // Deleted pages are pruned from the cache
+    std::fs::remove_file(root.join("about.html")).unwrap();
+    DirectoryScanner::new(root, "https://example.com")
+        .lastmod(LastmodSource::ContentHash(cache.clone()))
+        .scan()
+        .unwrap();
+    assert!(!std::fs::read_to_string(&cache).unwrap().contains("about.html"));
+}
+
+#[test]
+fn test_html_extractor_feeds_combined_builder() {
+    let html = r#"<html><head>
+<title>Intro</title>
+<meta property="og:description" content="Getting started">
+</head><body>
+<img src="/img/hero.png">
+<video src="intro.mp4" poster="intro.jpg"></video>
+</body></html>"#;
+
+    let url = HtmlExtractor::extract(html, UrlEntry::new("https://example.com/docs/intro")).unwrap();
+
+    let mut builder = CombinedSitemapBuilder::new();
+    builder.add_url(url);
+    let xml = builder.build().unwrap();
+
+    assert!(xml.contains("<image:loc>https://example.com/img/hero.png</image:loc>"));
+    assert!(xml.contains("<video:thumbnail_loc>https://example.com/docs/intro.jpg</video:thumbnail_loc>"));
+    assert!(xml.contains("<video:content_loc>https://example.com/docs/intro.mp4</video:content_loc>"));
+    assert!(xml.contains("<video:description>Getting started</video:description>"));
+}
+
+#[test]
+fn test_robots_txt_sitemaps_and_blocked_urls() {
+    let mut robots = RobotsTxt::parse("User-agent: *\nDisallow: /cart\n\nSitemap: https://example.com/old.xml\n");
+    robots.set_sitemap_index("https://example.com/sitemap_index.xml");
+    assert_eq!(
+        robots.to_string(),
+        "User-agent: *\nDisallow: /cart\n\nSitemap: https://example.com/sitemap_index.xml\n"
+    );
+
+    let entries = vec![
+        UrlEntry::new("https://example.com/products"),
+        UrlEntry::new("https://example.com/cart?item=1"),
+    ];
+    let blocked = robots.blocked_entries("Googlebot", &entries);
+    assert_eq!(blocked.len(), 1);
+    assert_eq!(blocked[0].loc, "https://example.com/cart?item=1");
+}
+
+#[test]
+fn test_xsl_stylesheet_instruction() {
+    let mut builder = SitemapBuilder::new().stylesheet("/sitemap.xsl");
+    builder.add_url(UrlEntry::new("https://example.com/"));
+    let xml = builder.build().unwrap();
+    assert!(xml.starts_with(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<?xml-stylesheet type=\"text/xsl\" href=\"/sitemap.xsl\"?>\n<urlset"
+    ));
+
+    let mut index = SitemapIndexBuilder::new().stylesheet("https://example.com/a.xsl?v=1&x=2");
+    index.add_sitemap(SitemapIndexEntry::new("https://example.com/sitemap1.xml"));
+    let xml = index.build().unwrap();
+    assert!(xml.contains("href=\"https://example.com/a.xsl?v=1&amp;x=2\"?>"));
+
+    // The stylesheet does not change how the sitemap parses
+    assert_eq!(SitemapParser::parse_index_string(&xml).unwrap().len(), 1);
+}
+
+#[test]
+fn test_write_default_stylesheet() {
+    let temp_file = NamedTempFile::new().unwrap();
+    writer::XmlWriter::write_default_stylesheet(temp_file.path()).unwrap();
+
+    let xsl = std::fs::read_to_string(temp_file.path()).unwrap();
+    assert_eq!(xsl, writer::DEFAULT_STYLESHEET);
+    assert!(xsl.contains("match=\"sitemap:urlset\""));
+    assert!(xsl.contains("match=\"sitemap:sitemapindex\""));
+    assert!(xsl.contains("count(video:video)"));
+}
+
 #[test]
 fn test_xml_escaping() {
     let mut builder = SitemapBuilder::new();
@@ -220,8 +462,20 @@ fn test_xml_escaping() {
 
     let xml = builder.build().unwrap();
 
-    // Verify special characters are escaped
-    assert!(xml.contains("&amp;"));
+    // Verify special characters are escaped, and only once
+    assert!(xml.contains("<loc>https://example.com/page?foo=bar&amp;baz=qux</loc>"));
+    assert!(!xml.contains("&amp;amp;"));
+
+    // Text elements are escaped once as well
+    let mut builder = ImageSitemapBuilder::new();
+    builder.add_url(
+        UrlWithImages::new(UrlEntry::new("https://example.com/"))
+            .add_image(ImageEntry::new("https://example.com/a.jpg").caption("Salt & <pepper>")),
+    );
+
+    let xml = builder.build().unwrap();
+    assert!(xml.contains("<image:caption>Salt &amp; &lt;pepper&gt;</image:caption>"));
+    assert!(!xml.contains("&amp;amp;"));
 }
 
 #[test]
@@ -247,10 +501,9 @@ fn test_video_with_all_fields() {
                 "Video with all fields",
             )
             .content_loc("https://example.com/videos/full.mp4")
-            .player_loc("https://example.com/player?video=full")
             .duration(600)
             .publication_date("2025-11-01")
-            .expiration_date("2025-01-01")
+            .expiration_date("2026-11-01")
             .rating(4.5)
             .view_count(10000)
             .family_friendly(true)
@@ -259,6 +512,10 @@ fn test_video_with_all_fields() {
             .category("Education")
             .requires_subscription(VideoRequiresSubscription::Yes)
             .uploader(VideoUploader::new("Test User").info_url("https://example.com/user")),
+        )
+        .add_video(
+            VideoEntry::new("https://example.com/thumbnails/embed.jpg", "Embedded Video", "Played in a page")
+                .player_loc("https://example.com/player?video=full"),
         );
 
     builder.add_url(url_with_video);
@@ -270,7 +527,7 @@ fn test_video_with_all_fields() {
     assert!(xml.contains("<video:player_loc>"));
     assert!(xml.contains("<video:duration>600</video:duration>"));
     assert!(xml.contains("<video:publication_date>2025-11-01</video:publication_date>"));
-    assert!(xml.contains("<video:expiration_date>2025-01-01</video:expiration_date>"));
+    assert!(xml.contains("<video:expiration_date>2026-11-01</video:expiration_date>"));
     assert!(xml.contains("<video:rating>4.5</video:rating>"));
     assert!(xml.contains("<video:view_count>10000</video:view_count>"));
     assert!(xml.contains("<video:tag>tag1</video:tag>"));
@@ -345,6 +602,7 @@ fn test_video_sitemap_bytes() {
             "Test Video",
             "A test video"
         )
+        .content_loc("https://example.com/videos/test.mp4")
     );
 
     builder.add_url(url_with_video);
@@ -438,6 +696,41 @@ fn test_news_sitemap_with_keywords() {
     assert!(xml.contains("AAPL, GOOGL"));
 }
 
+#[test]
+fn test_news_typed_keywords_and_tickers() {
+    let news = NewsEntry::new(NewsPublication::new("BusinessNews", "en"), "2025-11-01T09:00:00Z", "Market Update")
+        .add_keywords(["business", "finance"])
+        .add_keyword("market")
+        .add_stock_tickers(vec![StockTicker::new("NASDAQ", "AAPL"), StockTicker::new("NYSE", "BRK.B")]);
+
+    let mut builder = NewsSitemapBuilder::new();
+    builder.add_url(UrlWithNews::new(UrlEntry::new("https://example.com/business/market"), news.clone()));
+    let xml = builder.build().unwrap();
+    assert!(xml.contains("<news:keywords>business, finance, market</news:keywords>"));
+    assert!(xml.contains("<news:stock_tickers>NASDAQ:AAPL, NYSE:BRK.B</news:stock_tickers>"));
+
+    // The written text splits back into the same values
+    let parsed = NewsEntry::new(NewsPublication::new("BusinessNews", "en"), "2025-11-01", "Market Update")
+        .keywords(news.keywords_text());
+    assert_eq!(parsed.keywords, news.keywords);
+    assert_eq!(StockTicker::parse_list(&news.stock_tickers_text()).unwrap(), news.stock_tickers);
+
+    // A keyword with a comma would not survive the round trip
+    let mut builder = NewsSitemapBuilder::new();
+    builder.add_url(UrlWithNews::new(
+        UrlEntry::new("https://example.com/business/market"),
+        news.clone().add_keyword("stocks, bonds"),
+    ));
+    assert_eq!(builder.build().unwrap_err().code(), "invalid_field");
+
+    let mut builder = NewsSitemapBuilder::new();
+    builder.add_url(UrlWithNews::new(
+        UrlEntry::new("https://example.com/business/market"),
+        news.add_stock_ticker(StockTicker::new("NASDAQ", "")),
+    ));
+    assert_eq!(builder.build().unwrap_err().code(), "invalid_field");
+}
+
 #[test]
 fn test_news_sitemap_multiple_languages() {
     use sitemap_generator::{NewsSitemapBuilder, NewsEntry, NewsPublication, UrlEntry, UrlWithNews};
@@ -601,7 +894,8 @@ fn test_combined_sitemap_all_extensions() {
         "https://example.com/thumb.jpg",
         "Test Video",
         "Test Description"
-    );
+    )
+    .content_loc("https://example.com/video.mp4");
 
     builder.add_url(
         UrlWithExtensions::new(UrlEntry::new("https://example.com/article"))
@@ -739,7 +1033,8 @@ fn test_combined_sitemap_file_write() {
         "https://example.com/thumb.jpg",
         "Test",
         "Description"
-    );
+    )
+    .content_loc("https://example.com/video.mp4");
 
     builder.add_url(
         UrlWithExtensions::new(UrlEntry::new("https://example.com/test"))
@@ -812,7 +1107,7 @@ fn test_video_sitemap_builder_with_capacity() {
                 format!("https://example.com/thumb{}.jpg", i),
                 format!("Video {}", i),
                 "Description"
-            ).duration(120)
+            ).content_loc(format!("https://example.com/video{}.mp4", i)).duration(120)
         );
         builder.add_url(url);
     }
@@ -902,3 +1197,675 @@ fn test_with_capacity_large() {
     assert_eq!(builder.len(), 10_000);
     assert!(builder.build().is_ok());
 }
+
+#[test]
+fn test_normalize_urls_image_builder() {
+    let mut builder = ImageSitemapBuilder::new();
+    builder.add_url(
+        UrlWithImages::new(UrlEntry::new("http://example.com/gallery/"))
+            .add_image(ImageEntry::new("https://example.com/a.jpg")),
+    );
+    builder.add_url(
+        UrlWithImages::new(UrlEntry::new("https://EXAMPLE.com/gallery#top"))
+            .add_image(ImageEntry::new("https://example.com/b.jpg")),
+    );
+
+    let normalizer = UrlNormalizer::new()
+        .force_https(true)
+        .trailing_slash(TrailingSlash::Add);
+    let report = builder.normalize_urls(&normalizer, DedupPolicy::KeepLast);
+
+    assert_eq!(builder.len(), 1);
+    assert_eq!(report.rewritten.len(), 2);
+    assert_eq!(report.removed_count(), 1);
+
+    let xml = builder.build().unwrap();
+    assert!(xml.contains("<loc>https://example.com/gallery/</loc>"));
+    assert!(xml.contains("b.jpg"));
+    assert!(!xml.contains("a.jpg"));
+}
+
+#[test]
+fn test_scope_video_builder() {
+    let scope = SitemapScope::new("https://example.com/videos/sitemap.xml")
+        .unwrap()
+        .asset_host("*.cdn.example.net");
+
+    let video = |content: &str| {
+        VideoEntry::new("https://example.com/thumb.jpg", "Title", "Description")
+            .content_loc(content)
+    };
+
+    let mut builder = VideoSitemapBuilder::new().scope(scope.clone());
+    builder.add_url(
+        UrlWithVideos::new(UrlEntry::new("https://example.com/videos/1"))
+            .add_video(video("https://eu.cdn.example.net/1.mp4")),
+    );
+    assert!(builder.scope_violations().is_empty());
+    assert!(builder.build().is_ok());
+
+    builder.add_url(
+        UrlWithVideos::new(UrlEntry::new("https://example.com/videos/2"))
+            .add_video(video("https://elsewhere.example/2.mp4")),
+    );
+    let violations = builder.scope_violations();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].kind, ScopeViolationKind::VideoContent);
+    match builder.build() {
+        Err(Error::Entry { index, url, source }) => {
+            assert_eq!(index, 1);
+            assert_eq!(url, "https://example.com/videos/2");
+            assert!(matches!(*source, Error::OutOfScope(ref v) if v.url == "https://elsewhere.example/2.mp4"));
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    // Disabling validation does not disable the scope
+    let unvalidated = builder.validate(false);
+    assert!(matches!(unvalidated.build(), Err(Error::Entry { index: 1, .. })));
+
+    let mut lenient = VideoSitemapBuilder::new().scope(scope.warn_only(true));
+    lenient.add_url(UrlWithVideos::new(UrlEntry::new("https://example.com/other")));
+    assert_eq!(lenient.scope_violations().len(), 1);
+    assert!(lenient.build().is_ok());
+}
+
+#[test]
+fn test_base_url_resolution() {
+    let mut builder = ImageSitemapBuilder::new();
+    builder.add_url(
+        UrlWithImages::new(UrlEntry::new("/blog/post-1"))
+            .add_image(ImageEntry::new("/images/cover.jpg"))
+            .add_image(ImageEntry::new("https://cdn.example.net/photo.jpg")),
+    );
+
+    // Relative URLs fail validation without a base
+    assert!(builder.build().is_err());
+
+    let builder = builder.base_url(Url::parse("https://staging.example.com").unwrap());
+    let staging = builder.build().unwrap();
+    assert!(staging.contains("<loc>https://staging.example.com/blog/post-1</loc>"));
+    assert!(staging.contains("<image:loc>https://staging.example.com/images/cover.jpg</image:loc>"));
+    assert!(staging.contains("<image:loc>https://cdn.example.net/photo.jpg</image:loc>"));
+
+    let production = builder
+        .base_url(Url::parse("https://example.com").unwrap())
+        .build()
+        .unwrap();
+    assert!(production.contains("<loc>https://example.com/blog/post-1</loc>"));
+
+    let mut index =
+        SitemapIndexBuilder::new().base_url(Url::parse("https://example.com/sitemaps/").unwrap());
+    index.add_sitemap(SitemapIndexEntry::new("sitemap-1.xml"));
+    assert!(index.build().unwrap().contains("<loc>https://example.com/sitemaps/sitemap-1.xml</loc>"));
+}
+
+#[test]
+fn test_url_escaped_once() {
+    let mut builder = SitemapBuilder::new();
+    builder.add_url(UrlEntry::new("https://example.com/page?foo=bar&baz=qux"));
+
+    let xml = builder.build().unwrap();
+    assert!(xml.contains("<loc>https://example.com/page?foo=bar&amp;baz=qux</loc>"));
+
+    let entries = SitemapParser::parse_string(&xml).unwrap();
+    assert_eq!(entries[0].loc, "https://example.com/page?foo=bar&baz=qux");
+}
+
+#[test]
+fn test_url_encoding_round_trip() {
+    let display = "https://bücher.de/tin-tức/thời-sự?tag=bóng đá";
+    let mut builder = CombinedSitemapBuilder::new().url_encoding(UrlEncoding::Normalized);
+    builder.add_url(
+        UrlWithExtensions::new(UrlEntry::new(display))
+            .add_image(ImageEntry::new("https://bücher.de/ảnh.jpg")),
+    );
+
+    let xml = builder.build().unwrap();
+    assert!(xml.contains(
+        "<loc>https://xn--bcher-kva.de/tin-t%E1%BB%A9c/th%E1%BB%9Di-s%E1%BB%B1?tag=b%C3%B3ng%20%C4%91%C3%A1</loc>"
+    ));
+    assert!(xml.contains("<image:loc>https://xn--bcher-kva.de/%E1%BA%A3nh.jpg</image:loc>"));
+    assert!(xml.is_ascii());
+
+    let mut entries = SitemapParser::parse_string(&xml).unwrap();
+    SitemapParser::decode_urls(&mut entries);
+    assert_eq!(entries[0].loc, "https://bücher.de/tin-tức/thời-sự?tag=bóng%20đá");
+
+    // The default policy writes URLs as given
+    let mut raw = SitemapBuilder::new();
+    raw.add_url(UrlEntry::new(display));
+    assert!(raw.build().unwrap().contains(display));
+}
+
+#[test]
+fn test_borrowed_entries_match_owned_output() {
+    use sitemap_generator::writer::XmlWriter;
+
+    struct Photo {
+        src: String,
+        alt: String,
+    }
+
+    impl ImageEntryRef for Photo {
+        fn loc(&self) -> &str {
+            &self.src
+        }
+
+        fn title(&self) -> Option<&str> {
+            Some(&self.alt)
+        }
+    }
+
+    struct Article {
+        path: String,
+        updated: String,
+        photos: Vec<Photo>,
+    }
+
+    impl UrlEntryRef for Article {
+        fn loc(&self) -> &str {
+            &self.path
+        }
+
+        fn lastmod(&self) -> Option<&str> {
+            Some(&self.updated)
+        }
+
+        fn priority(&self) -> Option<f32> {
+            Some(0.5)
+        }
+    }
+
+    impl ImagesRef for Article {
+        type Image = Photo;
+
+        fn images(&self) -> &[Photo] {
+            &self.photos
+        }
+    }
+
+    let articles: Vec<Article> = (0..3)
+        .map(|i| Article {
+            path: format!("https://example.com/a/{}", i),
+            updated: "2025-11-01".to_string(),
+            photos: vec![Photo {
+                src: format!("https://example.com/a/{}.jpg", i),
+                alt: "A & B".to_string(),
+            }],
+        })
+        .collect();
+
+    let mut borrowed = XmlWriter::new();
+    borrowed.write_image_sitemap_ref(&articles).unwrap();
+
+    let mut builder = ImageSitemapBuilder::new();
+    for article in &articles {
+        builder.add_url(
+            UrlWithImages::new(
+                UrlEntry::new(article.path.clone())
+                    .lastmod(article.updated.clone())
+                    .priority(0.5),
+            )
+            .add_image(ImageEntry::new(article.photos[0].src.clone()).title("A & B")),
+        );
+    }
+
+    assert_eq!(borrowed.into_string().unwrap(), builder.build().unwrap());
+}
+
+#[test]
+fn test_parse_compressed_rejects_gzip_bomb() {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    // ~60MB of whitespace inside a valid document compresses to a few dozen KB
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut encoder = GzEncoder::new(temp_file.reopen().unwrap(), Compression::best());
+    encoder.write_all(b"<urlset>").unwrap();
+    let padding = vec![b' '; 1024 * 1024];
+    for _ in 0..60 {
+        encoder.write_all(&padding).unwrap();
+    }
+    encoder.write_all(b"</urlset>").unwrap();
+    encoder.finish().unwrap();
+
+    assert!(std::fs::metadata(temp_file.path()).unwrap().len() < 1024 * 1024);
+    assert!(matches!(
+        SitemapParser::parse_compressed(temp_file.path()),
+        Err(Error::DocumentTooLarge { limit: 52_428_800, .. })
+    ));
+
+    let limits = ParseLimits::new().max_bytes(100 * 1024 * 1024);
+    assert!(SitemapParser::parse_compressed_with_limits(temp_file.path(), &limits).unwrap().is_empty());
+}
+
+#[test]
+fn test_builder_output_is_conformant() {
+    let mut builder = CombinedSitemapBuilder::new();
+    builder.add_url(
+        UrlWithExtensions::new(
+            UrlEntry::new("https://example.com/watch/1")
+                .lastmod("2025-11-01")
+                .changefreq(ChangeFreq::Weekly)
+                .priority(0.8),
+        )
+        .add_image(ImageEntry::new("https://example.com/img/1.jpg").caption("Cover"))
+        .add_video(
+            VideoEntry::new("https://example.com/thumbs/1.jpg", "Episode 1", "The first episode")
+                .player_loc("https://example.com/player?id=1")
+                .duration(600)
+                .rating(4.5)
+                .family_friendly(true)
+                .add_tag("series")
+                .restriction(VideoCountryRestriction::Allow(vec!["US".to_string(), "CA".to_string()]))
+                .platform(VideoPlatformRestriction::Deny(vec![VideoPlatform::Tv]))
+                .add_price(VideoPrice::new("USD", 1.99))
+                .uploader(VideoUploader::new("Studio").info_url("https://example.com/studio")),
+        )
+        .set_news(NewsEntry::new(NewsPublication::new("Daily", "en"), "2025-11-01T09:00:00Z", "Episode 1 airs")),
+    );
+    let report = ConformanceChecker::check_string(&builder.build().unwrap());
+    assert_eq!(report.diagnostics, vec![]);
+
+    let mut index = SitemapIndexBuilder::new();
+    index.add_sitemap(SitemapIndexEntry::new("https://example.com/sitemap1.xml.gz").lastmod("2025-11-01"));
+    let temp_file = NamedTempFile::new().unwrap();
+    index.write(temp_file.path()).unwrap();
+    assert!(ConformanceChecker::check_file(temp_file.path()).unwrap().is_conformant());
+}
+
+#[test]
+fn test_conformance_of_partner_sitemap() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+        xmlns:video="http://www.google.com/schemas/sitemap-video/1.1">
+  <url>
+    <loc>https://example.com/a</loc>
+    <video:video>
+      <video:thumbnail_loc>https://example.com/a.jpg</video:thumbnail_loc>
+      <video:title>A</video:title>
+    </video:video>
+    <lastmod>2025-11-01</lastmod>
+  </url>
+  <url>
+    <loc>https://example.com/a</loc>
+  </url>
+</urlset>"#;
+
+    let report = ConformanceChecker::check_string(xml);
+    let lines: Vec<String> = report.diagnostics.iter().map(|d| d.to_string()).collect();
+    assert_eq!(
+        lines,
+        vec![
+            "6:5: error: <video:video> is missing <video:description> (/urlset/url[1]/video:video[1])",
+            "6:5: error: <video:video> requires <video:content_loc> or <video:player_loc> (/urlset/url[1]/video:video[1])",
+            "10:5: error: <lastmod> is out of order, expected loc, lastmod, changefreq, priority, then extensions (/urlset/url[1]/lastmod[1])",
+            "13:5: warning: https://example.com/a is already listed on line 5 (/urlset/url[2]/loc[1])",
+        ]
+    );
+    assert_eq!(report.errors().count(), 3);
+    assert_eq!(report.warnings().count(), 1);
+}
+
+#[test]
+fn test_parse_compressed_lenient_recovers_truncated_gzip() {
+    let mut builder = SitemapBuilder::new();
+    for i in 0..2000 {
+        builder.add_url(UrlEntry::new(format!("https://example.com/page/{}", i)).lastmod("2025-11-01"));
+    }
+    let temp_file = NamedTempFile::new().unwrap();
+    builder.write_compressed(temp_file.path()).unwrap();
+
+    let compressed = std::fs::read(temp_file.path()).unwrap();
+    std::fs::write(temp_file.path(), &compressed[..compressed.len() / 2]).unwrap();
+
+    assert!(SitemapParser::parse_compressed(temp_file.path()).is_err());
+
+    let parsed = SitemapParser::parse_compressed_lenient(temp_file.path()).unwrap();
+    assert!(!parsed.entries.is_empty() && parsed.entries.len() < 2000);
+    for (i, entry) in parsed.entries.iter().enumerate() {
+        assert_eq!(entry.loc, format!("https://example.com/page/{}", i));
+    }
+    assert!(parsed.issues[0].message.starts_with("stopped reading"));
+}
+
+#[test]
+fn test_error_context_and_codes() {
+    use std::error::Error as _;
+
+    let mut builder = NewsSitemapBuilder::new();
+    builder.add_url(UrlWithNews::new(
+        UrlEntry::new("https://example.com/news/1"),
+        NewsEntry::new(NewsPublication::new("Daily", "english"), "2025-11-01", "Headline"),
+    ));
+    let err = builder.build().unwrap_err();
+    assert_eq!(err.code(), "invalid_field");
+    assert!(matches!(
+        err,
+        Error::Entry { index: 0, ref source, .. }
+            if matches!(**source, Error::InvalidField { field: "news:language", .. })
+    ));
+    assert!(err.source().unwrap().to_string().contains("english"));
+
+    let missing = std::env::temp_dir().join("sitemap-generator-missing").join("sitemap.xml");
+    match SitemapParser::parse_file(&missing) {
+        Err(Error::Io { path: Some(path), .. }) => assert_eq!(path, missing),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let temp_file = NamedTempFile::new().unwrap();
+    std::fs::write(temp_file.path(), b"not gzip").unwrap();
+    let err = SitemapParser::parse_compressed(temp_file.path()).unwrap_err();
+    assert_eq!(err.code(), "xml");
+    assert!(err.source().and_then(|e| e.source()).is_some());
+
+    let err = Validator::validate_url(&format!("https://example.com/{}", "a".repeat(2048))).unwrap_err();
+    assert!(matches!(err, Error::UrlTooLong { actual: 2068, limit: 2048, .. }));
+}
+
+#[test]
+fn test_video_extension_rules() {
+    let video = || {
+        VideoEntry::new("https://example.com/thumbs/1.jpg", "Episode 1", "The first episode")
+            .content_loc("https://media.example.com/1.mp4")
+    };
+    let build = |video: VideoEntry| {
+        let mut builder = CombinedSitemapBuilder::new();
+        builder.add_url(UrlWithExtensions::new(UrlEntry::new("https://www.example.com/watch/1")).add_video(video));
+        builder.build()
+    };
+
+    let valid = video()
+        .restriction(VideoCountryRestriction::Allow(vec!["US".to_string(), "CA".to_string()]))
+        .add_price(VideoPrice::new("CAD", 3.99))
+        .uploader(VideoUploader::new("Studio").info_url("https://example.com/studio"));
+    assert!(build(valid).is_ok());
+
+    let err = build(video().add_price(VideoPrice::new("XYZ", 1.0))).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Entry { index: 0, ref source, .. }
+            if matches!(**source, Error::InvalidField { field: "video:price", ref value, .. } if value == "XYZ")
+    ));
+
+    let err = build(video().player_loc("https://www.example.com/watch/1")).unwrap_err();
+    assert_eq!(err.code(), "invalid_field");
+
+    let err = build(video().publication_date("2025-11-01").expiration_date("2025-10-01")).unwrap_err();
+    assert!(err.to_string().contains("video:expiration_date"));
+
+    // Validation can still be switched off
+    let mut builder = VideoSitemapBuilder::new().validate(false);
+    builder.add_url(UrlWithVideos::new(UrlEntry::new("https://example.com/")).add_video(video().player_loc("https://example.com/player")));
+    assert!(builder.build().is_ok());
+}
+
+#[test]
+fn test_video_length_limits_in_characters() {
+    // 80 characters, over 100 bytes
+    let title = "Hướng dẫn nấu phở bò Hà Nội chuẩn vị, ngon như ngoài hàng cho cả gia đình cùng ăn";
+    assert!(title.len() > 100 && title.chars().count() <= 100);
+
+    let video = |title: &str| {
+        VideoEntry::new("https://example.com/thumb.jpg", title, "Mô tả").content_loc("https://example.com/pho.mp4")
+    };
+
+    let mut builder = VideoSitemapBuilder::new();
+    builder.add_url(UrlWithVideos::new(UrlEntry::new("https://example.com/pho")).add_video(video(title)));
+    assert!(builder.build().unwrap().contains(title));
+
+    // Decomposed accents are two chars but one grapheme
+    let decomposed = "e\u{301}".repeat(80);
+    let mut builder = VideoSitemapBuilder::new();
+    builder.add_url(UrlWithVideos::new(UrlEntry::new("https://example.com/e")).add_video(video(&decomposed)));
+    assert!(builder.build().is_err());
+
+    let mut builder = VideoSitemapBuilder::new().length_unit(LengthUnit::Graphemes);
+    builder.add_url(UrlWithVideos::new(UrlEntry::new("https://example.com/e")).add_video(video(&decomposed)));
+    assert!(builder.build().is_ok());
+
+    // CMS content that runs over is truncated to fit
+    let long = "Một bài viết rất dài ".repeat(10);
+    let mut builder = VideoSitemapBuilder::new();
+    builder.add_url(
+        UrlWithVideos::new(UrlEntry::new("https://example.com/long"))
+            .add_video(video(&long).truncate_to_limits(LengthUnit::Chars)),
+    );
+    let xml = builder.build().unwrap();
+    assert!(xml.contains("rất dài Một bài viết…</video:title>"));
+}
+
+#[test]
+fn test_invalid_xml_chars_and_nfc() {
+    let entry = || {
+        UrlWithImages::new(UrlEntry::new("https://example.com/gallery"))
+            .add_image(ImageEntry::new("https://example.com/a.jpg").caption("Line\u{1}feed\ttab\u{fffe}"))
+    };
+
+    let mut builder = ImageSitemapBuilder::new();
+    builder.add_url(entry());
+    let err = builder.build().unwrap_err();
+    assert_eq!(err.code(), "invalid_xml_char");
+    assert!(matches!(err, Error::InvalidXmlChar { field: "image:caption", character: '\u{1}', .. }));
+    assert!(err.to_string().contains("U+0001"));
+
+    let mut builder = ImageSitemapBuilder::new().invalid_chars(InvalidCharPolicy::Strip);
+    builder.add_url(entry());
+    let xml = builder.build().unwrap();
+    assert!(xml.contains("<image:caption>Linefeed\ttab</image:caption>"));
+    assert!(SitemapParser::parse_string(&xml).is_ok());
+
+    let mut builder = ImageSitemapBuilder::new().invalid_chars(InvalidCharPolicy::Replace);
+    builder.add_url(entry());
+    assert!(builder.build().unwrap().contains("<image:caption>Line\u{fffd}feed\ttab\u{fffd}</image:caption>"));
+
+    // NFC composes text fields but leaves URLs alone
+    let decomposed = "Pho\u{31b}\u{309} bo\u{300}";
+    let news = || {
+        UrlWithNews::new(
+            UrlEntry::new("https://example.com/pho\u{31b}"),
+            NewsEntry::new(NewsPublication::new("Báo", "vi"), "2025-11-01", decomposed),
+        )
+    };
+    let mut builder = NewsSitemapBuilder::new();
+    builder.add_url(news());
+    assert!(builder.build().unwrap().contains(decomposed));
+
+    let mut builder = NewsSitemapBuilder::new().normalize_nfc(true);
+    builder.add_url(news());
+    let xml = builder.build().unwrap();
+    assert!(xml.contains("<news:title>Phở bò</news:title>"));
+    assert!(xml.contains("<loc>https://example.com/pho\u{31b}</loc>"));
+}
+
+#[test]
+fn test_build_repaired() {
+    let mut builder = CombinedSitemapBuilder::new();
+    builder.add_url(
+        UrlWithExtensions::new(UrlEntry::new("https://example.com/watch").priority(7.0)).add_video(
+            VideoEntry::new("https://example.com/thumb.jpg", "A very long title ".repeat(10), "Description")
+                .content_loc("https://example.com/v.mp4")
+                .player_loc("https://example.com/player"),
+        ),
+    );
+    builder.add_url(UrlWithExtensions::new(UrlEntry::new("https://example.com/news")).set_news(
+        NewsEntry::new(NewsPublication::new("Daily", "EN"), "2025-11-01", "Markets")
+            .stock_tickers("NASDAQ:A, NASDAQ:B, NASDAQ:C, NASDAQ:D, NASDAQ:E, NASDAQ:F"),
+    ));
+    builder.add_url(UrlWithExtensions::new(UrlEntry::new("https://example.com/bad").lastmod("soon")));
+    builder.add_url(UrlWithExtensions::new(UrlEntry::new("/relative")));
+
+    assert!(builder.build().is_err());
+
+    let RepairedSitemap { xml, report } = builder.build_repaired().unwrap();
+    assert!(ConformanceChecker::check_string(&xml).is_conformant());
+    assert!(xml.contains("<priority>1</priority>"));
+    assert!(!xml.contains("<video:player_loc>"));
+    assert!(xml.contains("<news:language>en</news:language>"));
+    assert!(xml.contains("<news:stock_tickers>NASDAQ:A, NASDAQ:B, NASDAQ:C, NASDAQ:D, NASDAQ:E</news:stock_tickers>"));
+
+    let fields: Vec<(usize, &str)> = report.repaired.iter().map(|r| (r.index, r.field)).collect();
+    assert_eq!(
+        fields,
+        vec![
+            (0, "priority"),
+            (0, "video:title"),
+            (0, "video:player_loc"),
+            (1, "news:language"),
+            (1, "news:stock_tickers"),
+        ]
+    );
+    assert!(report.repaired[1].repaired.as_ref().unwrap().ends_with('…'));
+    assert_eq!(report.repaired[2].repaired, None);
+
+    let dropped: Vec<(usize, &str, &str)> =
+        report.dropped.iter().map(|d| (d.index, d.url.as_str(), d.code)).collect();
+    assert_eq!(
+        dropped,
+        vec![(2, "https://example.com/bad", "invalid_date"), (3, "/relative", "invalid_url")]
+    );
+
+    // A clean build reports nothing
+    let mut builder = SitemapBuilder::new();
+    builder.add_url(UrlEntry::new("https://example.com/"));
+    assert!(builder.build_repaired().unwrap().report.is_clean());
+
+    // Text the invalid character policy rejects drops the entry
+    let image = |url: &str, caption: &str| {
+        UrlWithImages::new(UrlEntry::new(url)).add_image(ImageEntry::new("https://example.com/a.jpg").caption(caption))
+    };
+    let mut builder = ImageSitemapBuilder::new();
+    builder.add_url(image("https://example.com/a", "Sunset\u{8}"));
+    builder.add_url(image("https://example.com/b", "Sunrise"));
+
+    let RepairedSitemap { xml, report } = builder.build_repaired().unwrap();
+    assert!(xml.contains("Sunrise") && !xml.contains("Sunset"));
+    let dropped: Vec<(usize, &str)> = report.dropped.iter().map(|d| (d.index, d.code)).collect();
+    assert_eq!(dropped, vec![(0, "invalid_xml_char")]);
+
+    let builder = builder.invalid_chars(InvalidCharPolicy::Strip);
+    let RepairedSitemap { xml, report } = builder.build_repaired().unwrap();
+    assert!(xml.contains("<image:caption>Sunset</image:caption>"));
+    assert!(report.is_clean());
+}
+
+#[test]
+fn test_news_checks() {
+    use chrono::{TimeZone, Utc};
+
+    let checks = NewsChecks::all().now(Utc.with_ymd_and_hms(2025, 11, 3, 12, 0, 0).unwrap());
+    let article = |slug: &str, name: &str, date: &str, title: &str| {
+        UrlWithNews::new(
+            UrlEntry::new(format!("https://example.com/news/{}", slug)),
+            NewsEntry::new(NewsPublication::new(name, "en"), date, title),
+        )
+    };
+
+    let mut builder = NewsSitemapBuilder::new().news_checks(checks.clone());
+    let mut markets = article("a", "The Daily", "2025-11-03T08:00:00Z", "Markets rally");
+    markets.news.stock_tickers = vec![StockTicker::new("NASDAQ", "AAPL")];
+    builder.add_url(markets);
+    builder.add_url(article("b", "The Daily", "2025-11-02", "Storm warning"));
+    assert!(builder.build().is_ok());
+
+    // Without checks, old articles and mixed spellings are accepted
+    let mut builder = NewsSitemapBuilder::new();
+    builder.add_url(article("a", "The Daily", "2015-06-01", "Archive"));
+    builder.add_url(article("b", "the daily", "2025-11-03", "Storm warning"));
+    assert!(builder.build().is_ok());
+
+    let mut builder = builder.news_checks(checks.clone());
+    let err = builder.build().unwrap_err();
+    assert!(matches!(err, Error::Entry { index: 0, .. }));
+    assert!(err.to_string().contains("older than the news window"));
+
+    builder.add_url(article("c", "The Daily", "2025-11-03", "Results from Nov 2"));
+    let repaired = builder.build_repaired().unwrap();
+    let dropped: Vec<usize> = repaired.report.dropped.iter().map(|d| d.index).collect();
+    assert_eq!(dropped, vec![0, 2]);
+
+    // Combined builders check entries with news metadata and keep their indices
+    let mut builder = CombinedSitemapBuilder::new().news_checks(checks);
+    builder.add_url(UrlWithExtensions::new(UrlEntry::new("https://example.com/")));
+    builder.add_url(UrlWithExtensions::new(UrlEntry::new("https://example.com/news/a")).set_news(NewsEntry::new(
+        NewsPublication::new("The Daily", "en"),
+        "2025-11-03",
+        "Markets rally",
+    )));
+    builder.add_url(UrlWithExtensions::new(UrlEntry::new("https://example.com/news/b")).set_news(NewsEntry::new(
+        NewsPublication::new("Daily", "en"),
+        "2025-11-03",
+        "Storm warning",
+    )));
+    let err = builder.build().unwrap_err();
+    assert!(matches!(err, Error::Entry { index: 2, .. }));
+    assert_eq!(err.code(), "invalid_field");
+}
+
+#[test]
+fn test_extension_elements() {
+    const PAGEMAP: &str = "http://www.google.com/schemas/sitemap-pagemap/1.0";
+
+    let pagemap = ExtensionElement::new(PAGEMAP, "PageMap").child(
+        ExtensionElement::new(PAGEMAP, "DataObject")
+            .attribute("type", "document")
+            .child(ExtensionElement::new(PAGEMAP, "Attribute").attribute("name", "author").text("Jane <Doe>"))
+            .child(ExtensionElement::new(PAGEMAP, "Attribute").attribute("name", "draft")),
+    );
+
+    // The namespace is declared only when an entry uses it
+    let mut builder = SitemapBuilder::new().namespace("pagemap", PAGEMAP).namespace("acme", "urn:acme");
+    builder.add_url(UrlEntry::new("https://example.com/"));
+    let xml = builder.build().unwrap();
+    assert!(!xml.contains("xmlns:pagemap"));
+    assert!(!xml.contains("xmlns:acme"));
+
+    builder.add_url(UrlEntry::new("https://example.com/about").add_extension(pagemap.clone()));
+    let xml = builder.build().unwrap();
+    assert!(xml.contains(r#"xmlns:pagemap="http://www.google.com/schemas/sitemap-pagemap/1.0""#));
+    assert!(!xml.contains("xmlns:acme"));
+    assert!(xml.contains(r#"<pagemap:Attribute name="author">Jane &lt;Doe&gt;</pagemap:Attribute>"#));
+    assert!(xml.contains(r#"<pagemap:Attribute name="draft"/>"#));
+
+    // Parsing keeps the elements, so the sitemap can be rebuilt unchanged
+    let entries = SitemapParser::parse_string(&xml).unwrap();
+    assert_eq!(entries[1].extensions, vec![pagemap.clone()]);
+    let mut rebuilt = SitemapBuilder::new().namespace("pagemap", PAGEMAP);
+    rebuilt.add_urls(entries);
+    assert_eq!(rebuilt.build().unwrap(), builder.build().unwrap());
+
+    // Extension builders write the elements after their own
+    let mut builder = ImageSitemapBuilder::new().namespace("pagemap", PAGEMAP);
+    builder.add_url(
+        UrlWithImages::new(UrlEntry::new("https://example.com/").add_extension(pagemap.clone()))
+            .add_image(ImageEntry::new("https://example.com/a.jpg")),
+    );
+    let xml = builder.build().unwrap();
+    assert!(xml.find("</image:image>").unwrap() < xml.find("<pagemap:PageMap>").unwrap());
+
+    // Borrowed entries are not scanned up front, so the element declares its namespace
+    let entries = [UrlEntry::new("https://example.com/").add_extension(pagemap.clone())];
+    let mut writer = sitemap_generator::writer::XmlWriter::new().namespace("pagemap", PAGEMAP);
+    writer.write_sitemap_ref(entries.iter()).unwrap();
+    let xml = writer.into_string().unwrap();
+    assert!(xml.contains(r#"<pagemap:PageMap xmlns:pagemap="http://www.google.com/schemas/sitemap-pagemap/1.0">"#));
+
+    // Unregistered namespaces and reserved prefixes are rejected
+    let mut builder = SitemapBuilder::new();
+    builder.add_url(UrlEntry::new("https://example.com/").add_extension(pagemap.clone()));
+    assert_eq!(builder.build().unwrap_err().code(), "unknown_namespace");
+
+    let mut builder = SitemapBuilder::new().namespace("image", PAGEMAP);
+    builder.add_url(UrlEntry::new("https://example.com/").add_extension(pagemap.clone()));
+    assert_eq!(builder.build().unwrap_err().code(), "invalid_field");
+
+    // A prefix registered for two namespaces is rejected instead of declared twice
+    let mut builder = SitemapBuilder::new().namespace("pm", PAGEMAP).namespace("pm", "urn:acme");
+    builder.add_url(UrlEntry::new("https://example.com/").add_extension(pagemap));
+    builder.add_url(UrlEntry::new("https://example.com/a").add_extension(ExtensionElement::new("urn:acme", "Tag")));
+    let err = builder.build().unwrap_err();
+    assert_eq!(err.code(), "invalid_field");
+    assert!(err.to_string().contains("already registered"));
+}